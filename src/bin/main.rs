@@ -42,7 +42,8 @@ fn load_environment(opts: &mut cli::Options) -> Result<Arc<dyn Environment>> {
 
         let mut map = HashMap::new();
         tools.into_iter().for_each(|name| {
-            map.insert(name, PathBuf::new());
+            let path = PathBuf::from(&name);
+            map.insert(name, path);
         });
 
         return Ok(Arc::new(
@@ -50,5 +51,13 @@ fn load_environment(opts: &mut cli::Options) -> Result<Arc<dyn Environment>> {
         ));
     }
 
-    Ok(Arc::new(LiveEnvironment::new()?))
+    let env = LiveEnvironment::new()?;
+    if let Some(error) = env.config_error() {
+        eprintln!(
+            "{YELLOW_BOLD}⚠️ WARNING: Your configuration could not be loaded, so the default \
+            configuration is used and no tools will be installed: {error:#}{YELLOW_BOLD:#}"
+        );
+        eprintln!();
+    }
+    Ok(Arc::new(env))
 }
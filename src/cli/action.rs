@@ -1,7 +1,14 @@
+#[cfg(feature = "auto-install-tools")]
+use crate::cli::bundle::BundleAction;
+#[cfg(feature = "auto-install-tools")]
+use crate::cli::cache::CacheAction;
+use crate::cli::toolkit::ToolkitAction;
 use crate::registry::{ToolMetadata, ToolType, Toolkit};
 
 use clap::Parser;
 use std::borrow::Cow;
+#[cfg(feature = "auto-install-tools")]
+use std::path::PathBuf;
 
 /// An action that can be performed in the interactive menu.
 #[derive(Debug, PartialEq, Eq, Parser)]
@@ -14,16 +21,40 @@ pub enum Action<'a> {
     #[clap(name = "check")]
     CheckTools,
 
+    /// Finds well-known CTF tools that are installed on the system
+    /// but missing from the toolkit.
+    #[clap(name = "discover")]
+    Discover,
+
     /// Installs any tools from the toolkit that are not currently installed.
     #[cfg(feature = "auto-install-tools")]
     #[clap(name = "install")]
-    InstallMissingTools,
+    InstallMissingTools {
+        /// Installs the tools in an offline installation bundle made
+        /// with `ctftools bundle create` instead, without network access.
+        #[clap(long, value_name = "PATH")]
+        from_bundle: Option<PathBuf>,
+    },
 
     /// (Debug) Forcibly reinstalls all tools from the toolkit.
     #[cfg(all(debug_assertions, feature = "auto-install-tools"))]
     #[clap(name = "install-all")]
     InstallAllTools,
 
+    /// Manages your own toolkit file.
+    #[clap(name = "toolkit", subcommand)]
+    Toolkit(ToolkitAction),
+
+    /// Manages the cache of downloaded files.
+    #[cfg(feature = "auto-install-tools")]
+    #[clap(name = "cache", subcommand)]
+    Cache(CacheAction),
+
+    /// Manages offline installation bundles.
+    #[cfg(feature = "auto-install-tools")]
+    #[clap(name = "bundle", subcommand)]
+    Bundle(BundleAction),
+
     /// Exits the application.
     #[clap(skip)]
     Exit,
@@ -37,12 +68,20 @@ impl<'a> Action<'a> {
             Action::Tool(meta) => match meta.kind {
                 ToolType::Executable => format!("🔨 {}", meta.name).into(),
                 ToolType::Website => format!("📎 {}", meta.name).into(),
+                ToolType::Library => format!("📚 {}", meta.name).into(),
+                ToolType::Container => format!("🐳 {}", meta.name).into(),
             },
             Action::CheckTools => "🔎 Check which tools are installed".into(),
+            Action::Discover => "🔭 Discover other installed tools".into(),
             #[cfg(feature = "auto-install-tools")]
-            Action::InstallMissingTools => "📦 Install missing tools".into(),
+            Action::InstallMissingTools { .. } => "📦 Install missing tools".into(),
             #[cfg(all(debug_assertions, feature = "auto-install-tools"))]
             Action::InstallAllTools => "🚀 Install all tools".into(),
+            Action::Toolkit(..) => "🧰 Manage your toolkit".into(),
+            #[cfg(feature = "auto-install-tools")]
+            Action::Cache(..) => "🗄️ Manage the download cache".into(),
+            #[cfg(feature = "auto-install-tools")]
+            Action::Bundle(..) => "🎒 Manage offline installation bundles".into(),
             Action::Exit => "🚪 Exit".into(),
         }
     }
@@ -50,10 +89,11 @@ impl<'a> Action<'a> {
     /// Generates a list of available actions for the user to choose from.
     #[must_use]
     pub fn choices(toolkit: &'a Toolkit) -> Vec<Action<'a>> {
-        let last = vec![Action::CheckTools];
+        #[allow(unused_mut)]
+        let mut last = vec![Action::CheckTools, Action::Discover];
 
         #[cfg(feature = "auto-install-tools")]
-        last.push(Action::InstallMissingTools);
+        last.push(Action::InstallMissingTools { from_bundle: None });
 
         let mut choices: Vec<Action<'a>> = toolkit
             .tools()
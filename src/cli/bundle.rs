@@ -0,0 +1,312 @@
+use std::path::{Path, PathBuf};
+
+use anstream::{eprintln, println};
+use anyhow::{Context, Result, bail};
+use clap::Subcommand;
+use console::Term;
+use log::debug;
+use tempdir::TempDir;
+
+use crate::cli::ansi::*;
+use crate::cli::install_tools::{self, InstallGoal, ProgressRenderer};
+use crate::config::Config;
+use crate::env::Environment;
+use crate::install::bundle::{Bundle, BundleWriter};
+use crate::install::{InstallProgress, PackageOrigin, live, resolve_package};
+use crate::pkg::PackageManager;
+use crate::registry::{ToolMetadata, ToolType, Toolkit};
+
+/// Actions that manage offline installation bundles.
+#[derive(Debug, PartialEq, Eq, Subcommand)]
+pub enum BundleAction {
+    /// Downloads the tools into a bundle that can be installed on
+    /// another machine without network access with
+    /// `ctftools install --from-bundle`.
+    Create {
+        /// Only bundles the tools of a profile from the configuration
+        /// file instead of every tool in the toolkit.
+        #[clap(long)]
+        profile: Option<String>,
+
+        /// Operating system the bundle is made for. Defaults to the
+        /// current one.
+        #[clap(long, value_parser = ["linux", "macos", "windows"])]
+        os: Option<String>,
+
+        /// CPU architecture the bundle is made for, such as `x86_64`
+        /// or `aarch64`. Only the current one is supported for now.
+        #[clap(long)]
+        arch: Option<String>,
+
+        /// Also bundles the package files of tools that have no download
+        /// from the system's package manager (APT or DNF only). The bundle
+        /// must be made for the current system.
+        #[clap(long)]
+        with_packages: bool,
+
+        /// Path to write the bundle into.
+        #[clap(short, long, value_name = "PATH")]
+        output: PathBuf,
+    },
+}
+
+pub fn run(
+    action: BundleAction,
+    env: &dyn Environment,
+    stderr: &Term,
+    toolkit: &Toolkit,
+) -> Result<()> {
+    match action {
+        BundleAction::Create {
+            profile,
+            os,
+            arch,
+            with_packages,
+            output,
+        } => {
+            let tools = select_tools(env.config(), toolkit, profile.as_deref())?;
+            let os = os.as_deref().unwrap_or(std::env::consts::OS);
+            let arch = arch.as_deref().unwrap_or(std::env::consts::ARCH);
+            create(env, &tools, os, arch, with_packages, &output, stderr)
+        }
+    }
+}
+
+/// Installs the tools in the installation bundle at `path`
+/// that are missing from the system.
+pub fn install(env: &dyn Environment, path: &Path, stderr: &Term, toolkit: &Toolkit) -> Result<()> {
+    let dir = TempDir::new("ctftools_bundle").context("could not create a temporary directory")?;
+    let bundle = Bundle::extract(path, dir.path())
+        .with_context(|| format!("could not read the bundle at {}", path.display()))?;
+    bundle.check_platform(std::env::consts::OS, std::env::consts::ARCH)?;
+
+    debug!(
+        "installing from a bundle of {} tool(s)",
+        bundle.manifest().tools.len()
+    );
+    install_tools::install(env, InstallGoal::Bundle(&bundle), stderr, toolkit)
+}
+
+/// Selects the tools to bundle, which are either the tools of the given
+/// profile or every executable tool in the toolkit.
+fn select_tools<'t>(
+    config: &Config,
+    toolkit: &'t Toolkit,
+    profile: Option<&str>,
+) -> Result<Vec<&'t ToolMetadata>> {
+    let Some(profile) = profile else {
+        return Ok(toolkit
+            .tools()
+            .iter()
+            .filter(|v| v.kind == ToolType::Executable)
+            .collect());
+    };
+
+    let Some(commands) = config.profiles.get(profile) else {
+        bail!("there is no profile named '{profile}' in the configuration file");
+    };
+
+    commands
+        .iter()
+        .map(|command| {
+            toolkit
+                .find_tool(command)
+                .with_context(|| format!("profile '{profile}' refers to unknown tool '{command}'"))
+        })
+        .collect()
+}
+
+fn create(
+    env: &dyn Environment,
+    tools: &[&ToolMetadata],
+    os: &str,
+    arch: &str,
+    with_packages: bool,
+    output: &Path,
+    stderr: &Term,
+) -> Result<()> {
+    // Downloads are only picked by operating system, so they are
+    // made for the current architecture.
+    if arch != std::env::consts::ARCH {
+        bail!(
+            "tools can only be bundled for the current architecture ({})",
+            std::env::consts::ARCH
+        );
+    }
+
+    // Package files can only be downloaded for the system's own package manager.
+    let pkg_manager = if with_packages {
+        if os != std::env::consts::OS {
+            bail!("package files can only be bundled for the current system");
+        }
+        match env.pkg_manager() {
+            Some((pkg_manager @ (PackageManager::APT | PackageManager::DNF), _)) => {
+                Some(pkg_manager)
+            }
+            _ => bail!("package files can only be bundled with APT or DNF"),
+        }
+    } else {
+        None
+    };
+
+    let dir = TempDir::new("ctftools_bundle").context("could not create a temporary directory")?;
+    let mut writer = BundleWriter::new(dir.path(), os, arch);
+    let require_checksums = env.config().downloads.require_checksums;
+
+    eprintln!("⏳ {BOLD}Bundling tools for {os}/{arch}...{BOLD:#}");
+    for tool in tools {
+        let mut renderer = ProgressRenderer::new(stderr, "Bundling");
+        let mut progress_handler = |progress: InstallProgress| {
+            renderer.render(&progress);
+        };
+        let result = if let Some(instructions) = tool.downloads.for_os(os) {
+            if require_checksums && !instructions.is_verifiable() {
+                eprintln!(
+                    "{YELLOW_BOLD}⚠️ Skipped '{name}' as its download has no checksum \
+                    or signature to verify it{YELLOW_BOLD:#}",
+                    name = tool.name
+                );
+                continue;
+            }
+
+            writer.download_path(tool, instructions).and_then(|path| {
+                let signature = live::download_file(
+                    env,
+                    instructions,
+                    &path,
+                    &tool.name,
+                    &mut progress_handler,
+                )?;
+                writer.add_download(tool, instructions, signature.as_deref())
+            })
+        } else if let Some(pkg_manager) = pkg_manager
+            && let Some((package, PackageOrigin::Repository)) = resolve_package(pkg_manager, tool)
+        {
+            let dir = writer.packages_dir(tool);
+            live::download_package_files(env, package, &dir, &tool.name, &mut progress_handler)
+                .and_then(|files| writer.add_packages(tool, &files))
+        } else {
+            eprintln!(
+                "{YELLOW_BOLD}⚠️ Could not bundle '{name}': it has no download \
+                for {os}{YELLOW_BOLD:#}",
+                name = tool.name
+            );
+            continue;
+        };
+
+        if let Err(error) = result {
+            eprintln!();
+            return Err(error.context(format!("Failed to bundle {}", tool.name)));
+        }
+        eprintln!();
+    }
+
+    let manifest = writer.manifest();
+    if manifest.tools.is_empty() {
+        bail!("none of the tools could be bundled");
+    }
+    writer.write(output)?;
+
+    eprintln!(
+        "✅ {GREEN}{BOLD}Bundled the following tools into {}!{BOLD:#}{GREEN:#}",
+        output.display()
+    );
+    for tool in manifest.tools.iter() {
+        if tool.download.is_some() {
+            println!("{GRAY}* {BOLD}{}{BOLD:#}{GRAY:#}", tool.name);
+        } else {
+            println!(
+                "{GRAY}* {BOLD}{}{BOLD:#} ({} package file(s)){GRAY:#}",
+                tool.name,
+                tool.packages.len()
+            );
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use maplit::btreemap;
+    use pretty_assertions::assert_eq;
+
+    use console::Term;
+    use tempdir::TempDir;
+
+    use super::{create, select_tools};
+    use crate::config::Config;
+    use crate::env::MockEnvironment;
+    use crate::registry::{ToolMetadata, ToolType, Toolkit};
+
+    fn tool(command: &str, kind: ToolType) -> ToolMetadata {
+        ToolMetadata::builder()
+            .name(command.to_uppercase())
+            .command(command.into())
+            .kind(kind)
+            .build()
+    }
+
+    #[test]
+    fn test_select_tools() {
+        let toolkit = Toolkit::new(vec![
+            tool("ghidra", ToolType::Executable),
+            tool("cyberchef", ToolType::Website),
+            tool("nmap", ToolType::Executable),
+        ]);
+        let config = Config {
+            profiles: btreemap! {
+                "finals".into() => vec!["nmap".into()],
+                "broken".into() => vec!["nmap".into(), "foo".into()],
+            },
+            ..Config::default()
+        };
+
+        let commands = |profile| {
+            select_tools(&config, &toolkit, profile)
+                .map(|v| v.iter().map(|v| v.command.as_str()).collect::<Vec<_>>())
+                .map_err(|v| v.to_string())
+        };
+
+        assert_eq!(commands(None), Ok(vec!["ghidra", "nmap"]));
+        assert_eq!(commands(Some("finals")), Ok(vec!["nmap"]));
+        assert_eq!(
+            commands(Some("broken")),
+            Err("profile 'broken' refers to unknown tool 'foo'".into())
+        );
+        assert_eq!(
+            commands(Some("qualifiers")),
+            Err("there is no profile named 'qualifiers' in the configuration file".into())
+        );
+    }
+
+    #[test]
+    fn test_create_for_other_arch() {
+        let dir = TempDir::new("ctftools_test").unwrap();
+        let env = MockEnvironment::builder().build();
+        let arch = if std::env::consts::ARCH == "aarch64" {
+            "x86_64"
+        } else {
+            "aarch64"
+        };
+
+        for with_packages in [false, true] {
+            let error = create(
+                &env,
+                &[],
+                std::env::consts::OS,
+                arch,
+                with_packages,
+                &dir.path().join("bundle.tar.gz"),
+                &Term::stderr(),
+            )
+            .unwrap_err();
+
+            assert!(
+                error
+                    .to_string()
+                    .starts_with("tools can only be bundled for the current architecture")
+            );
+        }
+    }
+}
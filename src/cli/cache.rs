@@ -0,0 +1,69 @@
+use anstream::{eprintln, println};
+use anyhow::{Context, Result};
+use clap::Subcommand;
+
+use crate::cli::ansi::*;
+use crate::env::Environment;
+use crate::install::cache::DownloadCache;
+use crate::util::format_bytes;
+
+/// Actions that manage the cache of downloaded files.
+#[derive(Debug, PartialEq, Eq, Subcommand)]
+pub enum CacheAction {
+    /// Lists the downloaded files kept in the cache.
+    List,
+
+    /// Removes every downloaded file from the cache.
+    Clean,
+}
+
+pub fn run(action: CacheAction, env: &dyn Environment) -> Result<()> {
+    let dir = env
+        .program_dirs()
+        .and_then(|dirs| dirs.downloads_cache_dir())
+        .context("could not find the cache directory for ctftools")?;
+    let cache = DownloadCache::new(dir);
+
+    match action {
+        CacheAction::List => list(&cache),
+        CacheAction::Clean => {
+            let freed = cache.clean()?;
+            eprintln!(
+                "✅ {GREEN}{BOLD}Cleaned the download cache ({} freed){BOLD:#}{GREEN:#}",
+                format_bytes(freed)
+            );
+            Ok(())
+        }
+    }
+}
+
+fn list(cache: &DownloadCache) -> Result<()> {
+    let downloads = cache.list()?;
+    if downloads.is_empty() {
+        eprintln!("{BOLD}The download cache is empty.{BOLD:#}");
+        return Ok(());
+    }
+
+    for download in downloads.iter() {
+        let size = format_bytes(download.size);
+        if download.complete {
+            println!("* {BOLD}{}{BOLD:#} {GRAY}({size}){GRAY:#}", download.url);
+        } else {
+            println!(
+                "* {BOLD}{}{BOLD:#} {GRAY}({size}){GRAY:#} {YELLOW}(partial){YELLOW:#}",
+                download.url
+            );
+        }
+    }
+
+    let total = downloads.iter().map(|v| v.size).sum();
+    eprintln!();
+    eprintln!(
+        "{GRAY}{} file(s), {} in total at {}{GRAY:#}",
+        downloads.len(),
+        format_bytes(total),
+        cache.dir().display()
+    );
+
+    Ok(())
+}
@@ -0,0 +1,81 @@
+use anstream::{eprintln, println};
+use anyhow::{Context, Result};
+use console::Term;
+use dialoguer::MultiSelect;
+use dialoguer::theme::ColorfulTheme;
+use log::debug;
+
+use crate::cli::TermExt;
+use crate::cli::ansi::*;
+use crate::env::{DiscoveredTool, DiscoverySource, Environment};
+use crate::registry::Toolkit;
+
+pub fn run(env: &dyn Environment, stderr: &Term, toolkit: &Toolkit) -> Result<()> {
+    stderr.hide_cursor()?;
+    eprintln!("⏳ {BOLD}Looking for other known CTF tools in your system...{BOLD:#}");
+
+    let discovered = env
+        .discover_tools(Toolkit::catalog(), toolkit)
+        .context("failed to discover installed tools")?;
+
+    stderr.show_cursor()?;
+    stderr.clear_lines(1)?;
+
+    let divider = "=".repeat(25);
+    eprintln!("{BOLD}{divider} Discovered Tools {divider}{BOLD:#}");
+
+    if discovered.is_empty() {
+        println!("{GRAY}No other known tools are found in your system.{GRAY:#}");
+        return Ok(());
+    }
+
+    for DiscoveredTool { tool, source } in discovered.iter() {
+        let source = match source {
+            DiscoverySource::Executable(path) => format!("found at {}", path.display()),
+            #[cfg(feature = "auto-install-tools")]
+            DiscoverySource::Package(package) => format!("from package {package:?}"),
+        };
+        println!(
+            "* {GREEN}🔭 {}{GREEN:#} {GRAY}({}; {source}){GRAY:#}",
+            tool.name, tool.command
+        );
+    }
+
+    eprintln!();
+    println!(
+        "{GREEN}{BOLD}Found {} tool(s) that are not in your toolkit.{BOLD:#}{GREEN:#}",
+        discovered.len()
+    );
+
+    // Mocked environments cannot prompt nor write into the user's toolkit.
+    if !env.is_live() {
+        return Ok(());
+    }
+
+    let user_toolkit = crate::cli::toolkit::user_toolkit()?;
+    let items: Vec<_> = discovered.iter().map(|v| v.tool.name.as_str()).collect();
+    let selected = MultiSelect::with_theme(&ColorfulTheme::default())
+        .with_prompt("Select the tools to add into your toolkit")
+        .items(&items)
+        .defaults(&vec![true; items.len()])
+        .interact_opt()
+        .context("failed to prompt response")?;
+
+    let Some(selected) = selected.filter(|v| !v.is_empty()) else {
+        debug!("no discovered tools are selected");
+        eprintln!("{GRAY}Cancelled. Your toolkit file is left untouched.{GRAY:#}");
+        return Ok(());
+    };
+
+    for index in selected.iter() {
+        user_toolkit.add_tool(discovered[*index].tool)?;
+    }
+
+    eprintln!(
+        "✅ {GREEN}{BOLD}Added {} tool(s) into {}{BOLD:#}{GREEN:#}",
+        selected.len(),
+        user_toolkit.path().display()
+    );
+
+    Ok(())
+}
@@ -0,0 +1,154 @@
+use anstream::eprintln;
+use anyhow::{Context, Result};
+use dialoguer::theme::ColorfulTheme;
+use dialoguer::{Confirm, Input, Select};
+use log::debug;
+
+use crate::cli::ansi::*;
+use crate::cli::interrupted_as_none;
+use crate::process::ProcessBuilder;
+use crate::registry::{OptionValueType, ToolMetadata, ToolOption};
+
+/// Builds the arguments of a tool by asking the user to fill in
+/// each of its [options](ToolMetadata::options) step by step.
+///
+/// It returns `None` if the user aborted the form.
+pub fn fill_options(tool: &ToolMetadata) -> Result<Option<Vec<String>>> {
+    let theme = ColorfulTheme::default();
+    eprintln!(
+        "{BOLD}Please fill in the options for {} \
+        (press CTRL+C to abort):{BOLD:#}",
+        tool.name
+    );
+    eprintln!("{GRAY}Leave an optional value empty to skip it.{GRAY:#}");
+    eprintln!();
+
+    let mut args = Vec::new();
+    for option in tool.options.iter() {
+        let Some(option_args) = prompt_option(&theme, option)? else {
+            debug!("got interrupted");
+            return Ok(None);
+        };
+        args.extend(option_args);
+    }
+
+    let extra = Input::<String>::with_theme(&theme)
+        .with_prompt("Extra arguments")
+        .allow_empty(true)
+        .interact_text()
+        .map(Some)
+        .or_else(interrupted_as_none)
+        .context("failed to prompt response")?;
+
+    let Some(extra) = extra else {
+        debug!("got interrupted");
+        return Ok(None);
+    };
+    args.extend(extra.split_whitespace().map(String::from));
+
+    Ok(Some(args))
+}
+
+/// Shows the command built from the form and asks the user
+/// whether to run it.
+pub fn confirm_command(builder: &ProcessBuilder) -> Result<bool> {
+    eprintln!();
+    eprintln!("{BOLD}Command to run:{BOLD:#} {YELLOW}{builder}{YELLOW:#}");
+    for (key, value) in builder.get_envs() {
+        if let Some(value) = value {
+            eprintln!(
+                "{GRAY}-{GRAY:#} {key}={YELLOW}{}{YELLOW:#}",
+                value.to_string_lossy()
+            );
+        }
+    }
+    if let Some(cwd) = builder.get_cwd() {
+        eprintln!("{BOLD}Working directory:{BOLD:#} {}", cwd.display());
+    }
+
+    let confirmed = Confirm::with_theme(&ColorfulTheme::default())
+        .with_prompt("Do you want to run this command?")
+        .default(true)
+        .interact()
+        .map(Some)
+        .or_else(interrupted_as_none)
+        .context("failed to prompt response")?;
+
+    Ok(confirmed.unwrap_or(false))
+}
+
+/// Asks the user for the value of an option and returns the arguments
+/// it turns into. Skipped options do not produce any arguments.
+fn prompt_option(theme: &ColorfulTheme, option: &ToolOption) -> Result<Option<Vec<String>>> {
+    let label = option.label();
+    let Some(value_type) = option.value else {
+        let enabled = Confirm::with_theme(theme)
+            .with_prompt(label)
+            .default(false)
+            .interact()
+            .map(Some)
+            .or_else(interrupted_as_none)
+            .context("failed to prompt response")?;
+
+        return Ok(enabled.map(|v| if v { option.to_args("") } else { Vec::new() }));
+    };
+
+    if !option.choices.is_empty() {
+        let mut items = Vec::new();
+        if !option.required {
+            items.push("(skip)".to_string());
+        }
+        for choice in option.choices.iter() {
+            items.push(match choice.description.as_deref() {
+                Some(description) => format!("{} - {description}", choice.value),
+                None => choice.value.clone(),
+            });
+        }
+
+        let idx = Select::with_theme(theme)
+            .with_prompt(label)
+            .default(0)
+            .items(&items)
+            .interact()
+            .map(Some)
+            .or_else(interrupted_as_none)
+            .context("failed to prompt choice")?;
+
+        let offset = usize::from(!option.required);
+        return Ok(idx.map(|idx| match idx.checked_sub(offset) {
+            Some(idx) => option.to_args(&option.choices[idx].value),
+            None => Vec::new(),
+        }));
+    }
+
+    let hint = match value_type {
+        OptionValueType::Text => "text",
+        OptionValueType::Number => "number",
+        OptionValueType::Path => "path",
+    };
+
+    let value = Input::<String>::with_theme(theme)
+        .with_prompt(format!("{label} [{hint}]"))
+        .allow_empty(!option.required)
+        .validate_with(|input: &String| -> Result<(), String> {
+            let input = input.trim();
+            if (input.is_empty() && !option.required) || value_type.accepts(input) {
+                Ok(())
+            } else {
+                Err(format!("please enter a valid {hint}"))
+            }
+        })
+        .interact_text()
+        .map(Some)
+        .or_else(interrupted_as_none)
+        .context("failed to prompt response")?;
+
+    Ok(value.map(|v| {
+        let v = v.trim();
+        if v.is_empty() {
+            Vec::new()
+        } else {
+            option.to_args(v)
+        }
+    }))
+}
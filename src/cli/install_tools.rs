@@ -4,27 +4,40 @@ use anstream::eprintln;
 use anyhow::Context;
 use anyhow::Result;
 use console::Term;
+use dialoguer::theme::ColorfulTheme;
 use log::debug;
 use log::warn;
 
 use crate::cli::ansi::*;
-use crate::cli::debug_enabled;
+use crate::cli::{debug_enabled, interrupted_as_none};
 use crate::env::Environment;
 use crate::install::InstallPlanResult;
 use crate::install::InstallProgress;
+use crate::install::InstallTask;
+use crate::install::bundle::Bundle;
 use crate::registry::{ToolMetadata, Toolkit};
+use crate::util::format_bytes;
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum InstallGoal<'t> {
     Everything,
     Missing,
     Specific(&'t [ToolMetadata]),
+    Bundle(&'t Bundle),
 }
 
 #[derive(Debug)]
 enum InstallResult {
-    Successful { elapsed: Duration },
-    Failed { error: anyhow::Error },
+    Successful {
+        elapsed: Duration,
+
+        /// Whether the tool was installed from a download
+        /// that could not be verified with a checksum.
+        unverified: bool,
+    },
+    Failed {
+        error: anyhow::Error,
+    },
     Skipped,
 }
 
@@ -57,6 +70,7 @@ pub fn install(
         InstallGoal::Everything => env.plan_install_tools(toolkit.tools()),
         InstallGoal::Missing => env.plan_install_missing_tools(toolkit)?,
         InstallGoal::Specific(tools) => env.plan_install_tools(tools),
+        InstallGoal::Bundle(bundle) => bundle.plan_install_missing_tools(env, toolkit)?,
     };
     debug!(
         "found {} potential tool(s) that can be installed",
@@ -98,7 +112,14 @@ pub fn install(
     debug!("installing {} tool(s)", tasks.len());
     eprintln!("⏳ {BOLD}Installing the following missing tools...{BOLD:#}");
     for task in tasks.iter() {
-        println!("{GRAY}* {}{GRAY:#}", task.tool_name());
+        match task.installer() {
+            Some(installer) => println!(
+                "{GRAY}* {} (with {}){GRAY:#}",
+                task.tool_name(),
+                installer.as_display_name()
+            ),
+            None => println!("{GRAY}* {}{GRAY:#}", task.tool_name()),
+        }
     }
     eprintln!();
 
@@ -108,52 +129,48 @@ pub fn install(
         .map(|task| (task, InstallResult::Skipped))
         .collect::<Vec<_>>();
 
-    for (task, result) in results.iter_mut() {
+    'groups: for group in group_tasks(&tasks) {
         if got_interrupted {
             break;
         }
 
-        let mut progress_handler = &mut |progress: InstallProgress| {
-            match progress {
-                InstallProgress::Interrupted => {
-                    #[allow(unused)]
-                    {
-                        got_interrupted = true;
+        // Packages of the same installer are installed in a single transaction,
+        // then one by one if it fails so we can tell which one is failing.
+        let batch = InstallTask::batch(&group.iter().map(|&i| &tasks[i]).collect::<Vec<_>>());
+        if let Some(batch) = batch {
+            match run_task(env, &batch, stderr, &mut got_interrupted)? {
+                InstallResult::Successful { elapsed, .. } => {
+                    for &i in &group {
+                        results[i].1 = InstallResult::Successful {
+                            elapsed,
+                            unverified: false,
+                        };
                     }
+                    continue;
                 }
-                InstallProgress::InterruptFirstWarning => {
+                InstallResult::Failed { error } if !got_interrupted => {
+                    debug!("failed to install {}: {error:#}", batch.tool_name());
                     eprintln!(
-                        "{YELLOW_BOLD}⚠️ Triggered interrupt signal. Trigger again \
-                    to stop the installation process{YELLOW_BOLD:#}",
+                        "{YELLOW_BOLD}⚠️ Could not install {} at once. \
+                        Installing them one by one instead...{YELLOW_BOLD:#}",
+                        batch.tool_name()
                     );
                 }
-                InstallProgress::Command { text, tool_name } => {
-                    eprintln!("{BOLD}Installing {tool_name}{BOLD:#}: {GRAY}{text}{GRAY:#}");
+                InstallResult::Failed { error } => {
+                    results[group[0]].1 = InstallResult::Failed { error };
+                    break;
                 }
-                InstallProgress::Success { elapsed, .. } => {
-                    *result = InstallResult::Successful { elapsed };
-                }
-                _ => panic!("unimplemented progress: {progress:?}"),
-            };
-        };
-
-        let tool_name = task.tool_name();
-        let output = env
-            .run_install_task(task, &mut progress_handler)
-            .with_context(|| {
-                format!(
-                    "Failed to install {tool_name} (you may want to \
-                    install it manually instead)"
-                )
-            });
-
-        if let Err(error) = output {
-            *result = InstallResult::Failed { error };
-            break;
+                InstallResult::Skipped => continue,
+            }
         }
 
-        if !debug_enabled() {
-            stderr.clear_screen()?;
+        // A failing package does not keep the others from being installed,
+        // so only an interrupt stops the installation.
+        for i in group {
+            if got_interrupted {
+                break 'groups;
+            }
+            results[i].1 = run_task(env, &tasks[i], stderr, &mut got_interrupted)?;
         }
     }
 
@@ -170,12 +187,24 @@ pub fn install(
     let mut captured_error = None;
     for (task, result) in results.into_iter() {
         match result {
-            InstallResult::Successful { elapsed } => {
+            InstallResult::Successful {
+                elapsed,
+                unverified: false,
+            } => {
                 println!(
                     "{GRAY}* {BOLD}{}{BOLD:#} ({elapsed:.2?}){GRAY:#}",
                     task.tool_name()
                 );
             }
+            InstallResult::Successful {
+                elapsed,
+                unverified: true,
+            } => {
+                println!(
+                    "{GRAY}* {BOLD}{}{BOLD:#} ({elapsed:.2?}){GRAY:#} {YELLOW}(unverified){YELLOW:#}",
+                    task.tool_name()
+                );
+            }
             InstallResult::Failed { error } => {
                 println!(
                     "{GRAY}* {RED}{BOLD}{} (failed){BOLD:#}{RED:#} {GRAY:#}",
@@ -198,3 +227,344 @@ pub fn install(
 
     Ok(())
 }
+
+/// Groups the indices of the tasks that can be [installed
+/// together](InstallTask::batch), in the order of their first task.
+fn group_tasks(tasks: &[InstallTask]) -> Vec<Vec<usize>> {
+    let mut groups: Vec<Vec<usize>> = Vec::new();
+    for (i, task) in tasks.iter().enumerate() {
+        let group = groups
+            .iter_mut()
+            .find(|group| InstallTask::batch(&[&tasks[group[0]], task]).is_some());
+
+        match group {
+            Some(group) => group.push(i),
+            None => groups.push(vec![i]),
+        }
+    }
+    groups
+}
+
+/// Runs an install task while reporting its progress to the user.
+///
+/// `got_interrupted` is set if the user interrupted the installation.
+fn run_task(
+    env: &dyn Environment,
+    task: &InstallTask,
+    stderr: &Term,
+    got_interrupted: &mut bool,
+) -> Result<InstallResult> {
+    let mut result = InstallResult::Skipped;
+    let mut unverified = false;
+    let mut renderer = ProgressRenderer::new(stderr, "Installing");
+    let mut progress_handler = &mut |progress: InstallProgress| {
+        if matches!(progress, InstallProgress::UnverifiedDownload { .. }) {
+            unverified = true;
+        }
+        if renderer.render(&progress) {
+            return;
+        }
+
+        match progress {
+            InstallProgress::Interrupted => {
+                *got_interrupted = true;
+            }
+            InstallProgress::InterruptFirstWarning => {
+                eprintln!(
+                    "{YELLOW_BOLD}⚠️ Triggered interrupt signal. Trigger again \
+                to stop the installation process{YELLOW_BOLD:#}",
+                );
+            }
+            InstallProgress::BuildScript {
+                name,
+                contents,
+                tool_name,
+            } => {
+                eprintln!(
+                    "{YELLOW_BOLD}⚠️ Review the {name} of {tool_name} before it \
+                    is built and installed:{YELLOW_BOLD:#}"
+                );
+                for line in contents.lines() {
+                    eprintln!("{GRAY}{line}{GRAY:#}");
+                }
+                eprintln!();
+            }
+            InstallProgress::Success { elapsed, .. } => {
+                result = InstallResult::Successful {
+                    elapsed,
+                    unverified,
+                };
+            }
+            _ => {}
+        };
+    };
+
+    let tool_name = task.tool_name();
+    let output = env
+        .run_install_task(task, &mut confirm_build_script, &mut progress_handler)
+        .with_context(|| {
+            format!(
+                "Failed to install {tool_name} (you may want to \
+                install it manually instead)"
+            )
+        });
+
+    if let Err(error) = output {
+        return Ok(InstallResult::Failed { error });
+    }
+
+    if !debug_enabled() {
+        stderr.clear_screen()?;
+    }
+    Ok(result)
+}
+
+/// Asks the user whether the reviewed build script of a tool can be run.
+fn confirm_build_script(name: &str, tool_name: &str) -> Result<bool> {
+    let confirmed = dialoguer::Confirm::with_theme(&ColorfulTheme::default())
+        .with_prompt(format!(
+            "Do you want to build {tool_name} with this {name}?"
+        ))
+        .default(false)
+        .interact()
+        .map(Some)
+        .or_else(interrupted_as_none)
+        .context("failed to prompt response")?;
+
+    Ok(confirmed.unwrap_or(false))
+}
+
+/// Renders the progress that installing and bundling tools have in
+/// common, which is the output of commands and downloads.
+pub(crate) struct ProgressRenderer<'a> {
+    stderr: &'a Term,
+
+    /// What is being done to the tool (e.g. `Installing`).
+    verb: &'static str,
+
+    /// Whether the last line is a progress bar that is drawn over
+    /// the same line, so anything else must be written below it.
+    drawing_progress_bar: bool,
+}
+
+impl<'a> ProgressRenderer<'a> {
+    pub(crate) fn new(stderr: &'a Term, verb: &'static str) -> Self {
+        Self {
+            stderr,
+            verb,
+            drawing_progress_bar: false,
+        }
+    }
+
+    /// Renders the progress if it is one of the common ones.
+    ///
+    /// Returns `false` if it has to be rendered by the caller instead.
+    pub(crate) fn render(&mut self, progress: &InstallProgress) -> bool {
+        if self.drawing_progress_bar && !matches!(progress, InstallProgress::Download { .. }) {
+            eprintln!();
+            self.drawing_progress_bar = false;
+        }
+
+        match progress {
+            InstallProgress::Command { text, tool_name } => {
+                let verb = self.verb;
+                eprintln!("{BOLD}{verb} {tool_name}{BOLD:#}: {GRAY}{text}{GRAY:#}");
+            }
+            InstallProgress::Output { line, .. } => {
+                eprintln!("{GRAY}{line}{GRAY:#}");
+            }
+            InstallProgress::UnverifiedDownload { url, tool_name } => {
+                eprintln!(
+                    "{YELLOW_BOLD}⚠️ {tool_name} is downloaded from {url} \
+                    without a checksum or signature to verify it{YELLOW_BOLD:#}"
+                );
+            }
+            InstallProgress::Download {
+                url,
+                received,
+                total,
+                bytes_per_sec,
+                eta,
+                tool_name,
+            } => {
+                if !self.stderr.is_term() {
+                    debug!("downloaded {received} byte(s) from {url}");
+                    return true;
+                }
+
+                let bar = format_progress_bar(*received, *total, *bytes_per_sec, *eta);
+                _ = self.stderr.clear_line();
+                _ = self
+                    .stderr
+                    .write_str(&format!("{BOLD}Downloading {tool_name}{BOLD:#}: {bar}"));
+                self.drawing_progress_bar = true;
+            }
+            _ => return false,
+        }
+        true
+    }
+}
+
+/// Renders the progress of a download as a bar followed by the
+/// amount of bytes received, the download speed and the time left.
+///
+/// Only the amount of bytes received and the download speed are
+/// shown if the size of the file is unknown.
+pub(crate) fn format_progress_bar(
+    received: u64,
+    total: Option<u64>,
+    bytes_per_sec: u64,
+    eta: Option<Duration>,
+) -> String {
+    const BAR_WIDTH: u64 = 30;
+
+    let speed = format!("{}/s", format_bytes(bytes_per_sec));
+    let Some(total) = total.filter(|v| *v > 0) else {
+        return format!("{} {GRAY}{speed}{GRAY:#}", format_bytes(received));
+    };
+
+    let filled = (received.min(total) * BAR_WIDTH / total) as usize;
+    let bar = format!(
+        "{}{}",
+        "#".repeat(filled),
+        "-".repeat(BAR_WIDTH as usize - filled)
+    );
+
+    let eta = eta.map_or_else(
+        || "--:--".to_string(),
+        |v| format!("{}:{:02}", v.as_secs() / 60, v.as_secs() % 60),
+    );
+    format!(
+        "[{bar}] {}/{} {GRAY}{speed} ETA {eta}{GRAY:#}",
+        format_bytes(received),
+        format_bytes(total)
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+    use std::path::PathBuf;
+    use std::time::Duration;
+
+    use console::Term;
+    use maplit::{hashmap, hashset};
+
+    use super::{InstallGoal, format_progress_bar, group_tasks, install};
+    use crate::env::{Environment, MockEnvironment};
+    use crate::install::InstallTask;
+    use crate::pkg::{Installer, LanguagePackageManager, PackageManager};
+    use crate::registry::{
+        DownloadFileFormat, ToolDownloadInstructions, ToolMetadata, ToolPlatformDownloads, Toolkit,
+    };
+
+    #[test]
+    fn test_format_progress_bar() {
+        let bar = format_progress_bar(
+            512 * 1024,
+            Some(2048 * 1024),
+            256 * 1024,
+            Some(Duration::from_secs(66)),
+        );
+        let bar = anstream::adapter::strip_str(&bar).to_string();
+        assert_eq!(
+            bar,
+            format!(
+                "[{}{}] 512.0 KiB/2.0 MiB 256.0 KiB/s ETA 1:06",
+                "#".repeat(7),
+                "-".repeat(23)
+            )
+        );
+
+        let bar = format_progress_bar(2048, None, 1024, None);
+        assert_eq!(
+            anstream::adapter::strip_str(&bar).to_string(),
+            "2.0 KiB 1.0 KiB/s"
+        );
+    }
+
+    #[test]
+    fn test_group_tasks() {
+        let task = |exec: &str, package: &str, installer| InstallTask::PackageManager {
+            exec: PathBuf::from(exec),
+            arguments: vec!["install".into(), package.into()],
+            sudo: false,
+            installer,
+            tool_name: package.into(),
+        };
+
+        let brew = Installer::System(PackageManager::Homebrew);
+        let cargo = Installer::Language(LanguagePackageManager::Cargo);
+        let tasks = [
+            task("/usr/bin/brew", "nmap", brew),
+            task("/usr/bin/cargo", "rustscan", cargo),
+            InstallTask::PackageFiles {
+                files: Vec::new(),
+                tool_name: "Ghidra".into(),
+            },
+            task("/usr/bin/brew", "gdb", brew),
+            task("/usr/bin/cargo", "pwninit", cargo),
+            task("/usr/bin/brew", "binwalk", brew),
+        ];
+
+        assert_eq!(group_tasks(&tasks), [vec![0, 3, 5], vec![1, 4], vec![2]]);
+    }
+
+    #[test]
+    fn test_install_one_by_one_after_failed_batch() {
+        // The mock environment knows installed tools by their names.
+        let tool = |name: &str| {
+            ToolMetadata::builder()
+                .name(name.to_string())
+                .command(name.to_string())
+                .packages(hashmap! {
+                    "apt".to_string() => name.to_string(),
+                })
+                .build()
+        };
+        let instructions = ToolDownloadInstructions::builder()
+            .format(DownloadFileFormat::Binary)
+            .url("https://ghidra.local/ghidra".into())
+            .sha256("0".repeat(64))
+            .build();
+        let ghidra = ToolMetadata::builder()
+            .name("ghidra".into())
+            .command("ghidra".into())
+            .downloads(
+                ToolPlatformDownloads::builder()
+                    .linux(instructions.clone())
+                    .macos(instructions.clone())
+                    .windows(instructions)
+                    .build(),
+            )
+            .build();
+        let toolkit = Toolkit::new(vec![tool("nmap"), tool("gdb"), tool("binwalk"), ghidra]);
+
+        let env = MockEnvironment::builder()
+            .pkg_manager(PackageManager::APT)
+            .failing_packages(hashset! { "gdb".to_string() })
+            .build();
+
+        let error = install(&env, InstallGoal::Everything, &Term::stderr(), &toolkit).unwrap_err();
+        assert!(
+            error.to_string().starts_with("Failed to install gdb"),
+            "{error:#}"
+        );
+
+        // The batch fails because of gdb, so they are installed one by one
+        // and the others are still installed after gdb fails as well.
+        assert_eq!(
+            env.run_tasks(),
+            ["nmap, gdb, binwalk", "nmap", "gdb", "binwalk", "ghidra"]
+        );
+
+        let installed = |name: &str| {
+            let tool = toolkit.find_tool(name).unwrap();
+            env.find_tool_executable(tool).unwrap().is_some()
+        };
+        assert!(installed("nmap"));
+        assert!(!installed("gdb"));
+        assert!(installed("binwalk"));
+        assert!(installed("ghidra"));
+    }
+}
@@ -4,19 +4,47 @@ use console::{Color, Style};
 use dialoguer::{FuzzySelect, theme::ColorfulTheme};
 use log::debug;
 
-use crate::cli::{Action, TermExt};
+use crate::cli::{Action, TermExt, interrupted_as_none};
 use crate::cli::{ansi::*, debug_enabled};
 use crate::env::Environment;
-use crate::registry::Toolkit;
+use crate::registry::{Toolkit, ToolkitLoader};
 use crate::util::started_by_double_click;
 
+/// Enters the tool selector menu until the user exits.
+///
+/// The toolkit files watched by `loader` are reloaded between prompts
+/// whenever they change. If they fail to load, the previous toolkit is
+/// kept and the error is shown above the menu instead. `load_error` is
+/// the error from loading `toolkit` before entering the menu, if any.
 pub fn enter_interactive_mode(
     env: &dyn Environment,
     stderr: &Term,
-    toolkit: &Toolkit,
+    loader: &mut ToolkitLoader,
+    mut toolkit: Toolkit,
+    mut load_error: Option<anyhow::Error>,
 ) -> Result<()> {
     debug!("entering interactive mode");
     loop {
+        let reloaded = loader
+            .has_changed()
+            .and_then(|changed| changed.then(|| loader.load()).transpose());
+
+        match reloaded {
+            Ok(Some(reloaded)) => {
+                debug!(
+                    "reloaded toolkit; loaded tool(s) = {}",
+                    reloaded.tools().len()
+                );
+                toolkit = reloaded;
+                load_error = None;
+            }
+            Ok(None) => {}
+            Err(error) => {
+                debug!("could not reload toolkit: {error:#}");
+                load_error = Some(error);
+            }
+        }
+
         // Clear the screen or leave it as it is?
         if env.is_live() && !debug_enabled() && started_by_double_click() {
             stderr.clear_screen()?;
@@ -24,10 +52,13 @@ pub fn enter_interactive_mode(
 
         print_cli_header();
         print_cli_header_line();
+        if let Some(error) = load_error.as_ref() {
+            print_load_error_banner(error);
+        }
         print_select_action_instructions();
 
         // try to restore our cursor if CTRL+C has triggered
-        let action = prompt_select_action(toolkit)?;
+        let action = prompt_select_action(&toolkit)?;
         stderr.show_cursor()?;
 
         // clear the usage and the extra line so we can try to perform an action?
@@ -45,7 +76,7 @@ pub fn enter_interactive_mode(
             break;
         }
 
-        let result = crate::cli::try_run_action(action, env, stderr, toolkit);
+        let result = crate::cli::try_run_action(action, env, stderr, &toolkit);
         stderr.show_cursor()?;
 
         // If it has an error then, immediately stop the program.
@@ -69,17 +100,11 @@ pub fn prompt_yes_or_no(question: &str) -> Result<Option<bool>> {
             .with_prompt(format!("{question} [Y/n]"))
             .interact_text()
             .map(Some)
-            .or_else(|error| match error {
-                dialoguer::Error::IO(inner) if inner.kind() == std::io::ErrorKind::Interrupted => {
-                    Ok(None)
-                }
-                dialoguer::Error::IO(error) => Err(error),
-            })
+            .or_else(interrupted_as_none)
             .context("failed to prompt response")?;
 
         // If it's interrupted, then force exit!
         let Some(input) = input else {
-            debug!("got interrupted");
             return Ok(None);
         };
 
@@ -116,13 +141,7 @@ pub fn prompt_select_action<'a>(toolkit: &'a Toolkit) -> Result<Option<Action<'a
         .report(false)
         .interact()
         .map(Some)
-        .or_else(|error| match error {
-            dialoguer::Error::IO(inner) if inner.kind() == std::io::ErrorKind::Interrupted => {
-                debug!("got interrupted");
-                Ok(None)
-            }
-            dialoguer::Error::IO(error) => Err(error),
-        })
+        .or_else(interrupted_as_none)
         .context("failed to prompt choice")?;
 
     if let Some(idx) = idx {
@@ -146,6 +165,15 @@ pub fn print_goodbye_message() {
     eprintln!("{GREEN_BOLD}Good luck to your CTFs!! 🚩🫶{GREEN_BOLD:#}");
 }
 
+fn print_load_error_banner(error: &anyhow::Error) {
+    eprintln!(
+        "{YELLOW_BOLD}⚠️ Could not load your toolkit. \
+        The last toolkit that loaded is used until it is fixed.{YELLOW_BOLD:#}"
+    );
+    eprintln!("{YELLOW}{error:#}{YELLOW:#}");
+    eprintln!();
+}
+
 fn print_select_action_instructions() {
     eprintln!("{GRAY}Choose a tool to see quick usage notes.");
 
@@ -6,16 +6,24 @@ use log::{debug, info};
 use std::io::Write;
 
 use crate::env::Environment;
-use crate::registry::Toolkit;
+use crate::registry::{Toolkit, ToolkitLoader, TrustStore};
+use crate::util::ProgramDirs;
 
 pub mod action;
 pub mod interactive;
 pub mod options;
 
+#[cfg(feature = "auto-install-tools")]
+pub mod bundle;
+#[cfg(feature = "auto-install-tools")]
+pub mod cache;
 pub mod check_tools;
+pub mod discover;
+pub mod form;
 #[cfg(feature = "auto-install-tools")]
 pub mod install_tools;
 pub mod run_tool;
+pub mod toolkit;
 
 pub use self::action::Action;
 pub use self::options::Options;
@@ -29,8 +37,21 @@ pub fn run(env: &dyn Environment, opts: Options, toolkit: Option<Toolkit>) -> Re
     debug!("using environment: {env:?}");
     let stderr = Term::stderr();
 
-    // Load our toolkit to be used for the entire program's lifetime.
-    let toolkit = init_maybe_custom_toolkit(&opts, toolkit)?;
+    // Load our toolkit. It may be reloaded later in the interactive mode
+    // if the user edits their toolkit files.
+    let mut loader = init_maybe_custom_toolkit(&opts, toolkit)?;
+
+    // Commands given in the command line need the toolkit they are run
+    // with, while the tool selector menu can go on with the provided
+    // toolkit and show the error as a banner until it is fixed.
+    let (toolkit, load_error) = match loader.load() {
+        Ok(toolkit) => (toolkit, None),
+        Err(error) if opts.action.is_none() && is_env_live => {
+            debug!("could not load toolkit: {error:#}");
+            (loader.base().clone(), Some(error))
+        }
+        Err(error) => return Err(error),
+    };
 
     // Do not enter interactive if we're in a mock environment.
     //
@@ -57,7 +78,7 @@ pub fn run(env: &dyn Environment, opts: Options, toolkit: Option<Toolkit>) -> Re
         panic!("Action is required to perform an action in mocked system environment");
     }
 
-    self::interactive::enter_interactive_mode(env, &stderr, &toolkit)
+    self::interactive::enter_interactive_mode(env, &stderr, &mut loader, toolkit, load_error)
 }
 
 pub fn try_run_action(
@@ -71,30 +92,54 @@ pub fn try_run_action(
     match action {
         Action::Tool(tool) => self::run_tool::run(env, stderr, tool),
         Action::CheckTools => self::check_tools::run(env, stderr, toolkit),
+        Action::Discover => self::discover::run(env, stderr, toolkit),
         #[cfg(feature = "auto-install-tools")]
-        Action::InstallMissingTools => {
+        Action::InstallMissingTools { from_bundle: None } => {
             self::install_tools::install(env, InstallGoal::Missing, stderr, toolkit)
         }
+        #[cfg(feature = "auto-install-tools")]
+        Action::InstallMissingTools {
+            from_bundle: Some(path),
+        } => self::bundle::install(env, &path, stderr, toolkit),
         #[cfg(all(debug_assertions, feature = "auto-install-tools"))]
         Action::InstallAllTools => {
             self::install_tools::install(env, InstallGoal::Everything, stderr, toolkit)
         }
+        Action::Toolkit(action) => self::toolkit::run(action, env, stderr, toolkit),
+        #[cfg(feature = "auto-install-tools")]
+        Action::Cache(action) => self::cache::run(action, env),
+        #[cfg(feature = "auto-install-tools")]
+        Action::Bundle(action) => self::bundle::run(action, env, stderr, toolkit),
         Action::Exit => Ok(()),
     }
 }
 
+/// Turns an interrupted dialoguer prompt (CTRL+C) into `None` so
+/// callers can treat it as the user backing out.
+pub(crate) fn interrupted_as_none<T>(error: dialoguer::Error) -> std::io::Result<Option<T>> {
+    match error {
+        dialoguer::Error::IO(inner) if inner.kind() == std::io::ErrorKind::Interrupted => {
+            debug!("got interrupted");
+            Ok(None)
+        }
+        dialoguer::Error::IO(error) => Err(error),
+    }
+}
+
 fn debug_enabled() -> bool {
     std::env::var("CTFTOOLS_DEBUG").as_deref().unwrap_or("0") != "0"
 }
 
-#[allow(unused)]
-fn init_maybe_custom_toolkit(opts: &Options, existing_toolkit: Option<Toolkit>) -> Result<Toolkit> {
+fn init_maybe_custom_toolkit(
+    opts: &Options,
+    existing_toolkit: Option<Toolkit>,
+) -> Result<ToolkitLoader> {
     if let Some(toolkit) = existing_toolkit {
         debug!(
             "using existing toolkit passed from `run` function; loaded tool(s) = {}",
             toolkit.tools().len()
         );
-        return Ok(toolkit);
+        return Ok(ToolkitLoader::new(toolkit, Vec::new()));
     }
 
     #[cfg(debug_assertions)]
@@ -104,10 +149,34 @@ fn init_maybe_custom_toolkit(opts: &Options, existing_toolkit: Option<Toolkit>)
             "using custom toolkit; loaded tool(s) = {}",
             toolkit.tools().len()
         );
-        return Ok(toolkit);
+        return Ok(ToolkitLoader::new(toolkit, Vec::new()));
     }
 
-    Ok(Toolkit::default().clone())
+    let toolkit = Toolkit::default().clone();
+    let (user_files, trust) = match ProgramDirs::detect() {
+        Some(dirs) => {
+            let path = dirs.user_toolkit_file();
+            debug!("using user toolkit from {}", path.display());
+
+            // Trusted keys are only needed to verify shared toolkits.
+            let trust = if opts.toolkits.is_empty() {
+                TrustStore::default()
+            } else {
+                TrustStore::load(&dirs.trusted_keys_dir())?
+            };
+            (vec![path], trust)
+        }
+        None => {
+            debug!("could not find program directories; skipping user toolkit");
+            (Vec::new(), TrustStore::default())
+        }
+    };
+
+    Ok(ToolkitLoader::new(toolkit, user_files).with_shared_files(
+        opts.toolkits.clone(),
+        trust,
+        opts.allow_unsigned,
+    ))
 }
 
 fn init_logger() {
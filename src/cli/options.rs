@@ -1,5 +1,6 @@
 use bon::Builder;
 use clap::Parser;
+use std::path::PathBuf;
 
 use crate::cli::action::Action;
 
@@ -8,6 +9,20 @@ pub struct Options {
     #[clap(subcommand)]
     pub action: Option<Action<'static>>,
 
+    /// Loads an additional toolkit file shared by others.
+    ///
+    /// It must be signed by a trusted publisher key with a detached
+    /// minisign signature next to it (`<PATH>.minisig`).
+    #[builder(default)]
+    #[clap(long = "toolkit", value_name = "PATH", global = true)]
+    pub toolkits: Vec<PathBuf>,
+
+    /// Loads shared toolkit files even if they are unsigned or their
+    /// signatures cannot be verified.
+    #[builder(default)]
+    #[clap(long, global = true)]
+    pub allow_unsigned: bool,
+
     /// **Development option**
     ///
     /// This allows to plug a custom toolkit without using the
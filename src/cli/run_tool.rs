@@ -1,10 +1,13 @@
 use anyhow::Context;
 use anyhow::{Result, bail};
 use console::Term;
+use dialoguer::Select;
+use dialoguer::theme::ColorfulTheme;
 use log::debug;
 use std::borrow::Cow;
 
 use crate::cli::ansi::*;
+use crate::cli::interrupted_as_none;
 use crate::env::Environment;
 use crate::process::{ProcessBuilder, ProcessError};
 use crate::registry::{ToolMetadata, ToolType};
@@ -24,6 +27,8 @@ pub fn run(env: &dyn Environment, stderr: &Term, tool: &ToolMetadata) -> Result<
     match tool.kind {
         ToolType::Executable => run_as_executable(env, tool),
         ToolType::Website => run_as_link(tool),
+        ToolType::Library => run_as_library(env, tool),
+        ToolType::Container => run_as_container(env, tool),
     }?;
     eprintln!();
 
@@ -41,14 +46,28 @@ fn run_as_link(tool: &ToolMetadata) -> Result<()> {
     stdin.read_line(&mut String::new())?;
 
     let url = tool.url.as_ref().expect("url must be present in link tool");
-    assert!(url.starts_with("https://") || url.starts_with("http://"));
-    opener::open(url).context("failed to redirect to a link")?;
+    open_link(url)
+}
 
-    Ok(())
+/// An action that can be performed from the details of an executable tool.
+enum ExecutableAction<'t> {
+    Run,
+    RunWithForm,
+    OpenLink(&'t str),
 }
 
-fn run_as_executable(env: &dyn Environment, tool: &ToolMetadata) -> Result<()> {
+fn print_links_and_examples(tool: &ToolMetadata) {
     eprintln!();
+    if let Some(homepage) = tool.homepage.as_ref() {
+        eprintln!("{BOLD}{GRAY}Homepage:{GRAY:#}{BOLD:#} {homepage}");
+    }
+    if let Some(docs) = tool.docs.as_ref() {
+        eprintln!("{BOLD}{GRAY}Documentation:{GRAY:#}{BOLD:#} {docs}");
+    }
+    if tool.homepage.is_some() || tool.docs.is_some() {
+        eprintln!();
+    }
+
     if !tool.examples.is_empty() {
         eprintln!("{BOLD}{GRAY}Examples:{GRAY:#}{BOLD:#}");
         for example in tool.examples.iter() {
@@ -56,14 +75,198 @@ fn run_as_executable(env: &dyn Environment, tool: &ToolMetadata) -> Result<()> {
         }
         eprintln!();
     }
+}
+
+/// Shows the environment variables and the working directory
+/// the tool runs with, after expanding their values.
+fn print_environment(tool: &ToolMetadata) {
+    if !tool.env.is_empty() {
+        eprintln!("{BOLD}{GRAY}Environment:{GRAY:#}{BOLD:#}");
+        match tool.effective_env() {
+            Ok(vars) => {
+                for (key, value) in vars {
+                    eprintln!("{GRAY}-{GRAY:#} {key}={YELLOW}{value}{YELLOW:#}");
+                }
+            }
+            Err(error) => eprintln!("{RED}{error:#}{RED:#}"),
+        }
+        eprintln!();
+    }
+
+    if tool.cwd.is_some() {
+        match tool.effective_cwd() {
+            Ok(Some(cwd)) => {
+                eprintln!(
+                    "{BOLD}{GRAY}Working directory:{GRAY:#}{BOLD:#} {}",
+                    cwd.display()
+                );
+            }
+            Ok(None) => {}
+            Err(error) => eprintln!("{RED}{error:#}{RED:#}"),
+        }
+        eprintln!();
+    }
+}
+
+fn run_as_library(env: &dyn Environment, tool: &ToolMetadata) -> Result<()> {
+    let library = tool
+        .library
+        .as_ref()
+        .expect("library must be present in library tool");
+
+    print_links_and_examples(tool);
+    eprintln!(
+        "{BOLD}{GRAY}Import it in {}:{GRAY:#}{BOLD:#} {YELLOW}{}{YELLOW:#}",
+        library.language.as_display_name(),
+        library.import_statement()
+    );
+    eprintln!();
+
+    let Some(interpreter) = env.find_tool_executable(tool)? else {
+        bail!(
+            "{} cannot import {}. Did you forget to install this library?",
+            library.interpreter(),
+            library.module
+        )
+    };
+
+    let run_label = format!(
+        "▶️ Start {} with {} imported",
+        library.interpreter(),
+        library.module
+    );
+    match prompt_executable_action(tool, run_label, false)? {
+        Some(ExecutableAction::Run | ExecutableAction::RunWithForm) => {}
+        Some(ExecutableAction::OpenLink(url)) => return open_link(url),
+        None => return Ok(()),
+    }
+
+    let mut builder = ProcessBuilder::new(interpreter);
+    builder.args(&library.interactive_args());
+    spawn_and_wait(&builder)
+}
+
+fn run_as_executable(env: &dyn Environment, tool: &ToolMetadata) -> Result<()> {
+    print_links_and_examples(tool);
+    print_environment(tool);
+
+    let use_form = match prompt_executable_action(tool, format!("▶️ Run {}", tool.name), true)?
+    {
+        Some(ExecutableAction::Run) => false,
+        Some(ExecutableAction::RunWithForm) => true,
+        Some(ExecutableAction::OpenLink(url)) => return open_link(url),
+        None => return Ok(()),
+    };
+
+    let Some(args) = read_or_fill_arguments(tool, &tool.command, use_form)? else {
+        return Ok(());
+    };
+
+    let Some(cmd) = env.find_tool_executable(tool)? else {
+        bail!(
+            "I cannot run {} for you. Did you forget to install this tool?",
+            tool.command
+        )
+    };
+
+    eprintln!();
+
+    let mut builder = ProcessBuilder::new(cmd);
+    builder.args(&args);
+    for (key, value) in tool.effective_env()? {
+        builder.env(&key, value);
+    }
+    if let Some(cwd) = tool.effective_cwd()? {
+        builder.cwd(cwd);
+    }
 
+    if use_form && !crate::cli::form::confirm_command(&builder)? {
+        return Ok(());
+    }
+    spawn_and_wait(&builder)
+}
+
+fn run_as_container(env: &dyn Environment, tool: &ToolMetadata) -> Result<()> {
+    let container = tool
+        .container
+        .as_ref()
+        .expect("container must be present in container tool");
+
+    print_links_and_examples(tool);
+    eprintln!("{BOLD}{GRAY}Image:{GRAY:#}{BOLD:#} {}", container.image);
+    eprintln!();
+
+    let Some((runtime, exec)) = env.container_runtime() else {
+        bail!(
+            "I cannot run {} for you without Docker or Podman installed.",
+            tool.name
+        )
+    };
+
+    if env.find_tool_executable(tool)?.is_none() {
+        bail!(
+            "{} image is not found in {}. Did you forget to install this tool?",
+            container.image,
+            runtime.as_display_name()
+        )
+    }
+
+    let use_form = match prompt_executable_action(tool, format!("▶️ Run {}", tool.name), true)?
+    {
+        Some(ExecutableAction::Run) => false,
+        Some(ExecutableAction::RunWithForm) => true,
+        Some(ExecutableAction::OpenLink(url)) => return open_link(url),
+        None => return Ok(()),
+    };
+
+    let mut prefix = vec![container.image.as_str()];
+    prefix.extend(container.args.iter().map(String::as_str));
+    let Some(args) = read_or_fill_arguments(tool, &prefix.join(" "), use_form)? else {
+        return Ok(());
+    };
+
+    let workdir = std::env::current_dir().context("could not get the current directory")?;
+    eprintln!();
+
+    let builder = runtime.run_command(&exec, container, &workdir, &args);
+    if use_form && !crate::cli::form::confirm_command(&builder)? {
+        return Ok(());
+    }
+    spawn_and_wait(&builder)
+}
+
+/// Asks the user for the arguments to run the tool with, either by
+/// filling in the tool's options from a form or by typing them.
+///
+/// It returns `None` if the user aborted the prompt.
+fn read_or_fill_arguments(
+    tool: &ToolMetadata,
+    prefix: &str,
+    use_form: bool,
+) -> Result<Option<Vec<String>>> {
+    if use_form {
+        crate::cli::form::fill_options(tool)
+    } else {
+        read_arguments(tool, prefix)
+    }
+}
+
+/// Asks the user for the arguments to run the tool with.
+///
+/// It returns `None` if the user aborted the prompt.
+fn read_arguments(tool: &ToolMetadata, prefix: &str) -> Result<Option<Vec<String>>> {
     eprintln!(
         "{BOLD}Please enter the arguments for {} to run \
         (press CTRL+C to abort):{BOLD:#}",
         tool.name
     );
 
-    if let Ok(path) = std::env::current_dir() {
+    // Relative paths are resolved from the tool's own working directory.
+    if tool.kind == ToolType::Executable
+        && let Ok(Some(cwd)) = tool.effective_cwd()
+    {
+        eprintln!("{GRAY}{} runs from: {}{GRAY:#}", tool.name, cwd.display());
+    } else if let Ok(path) = std::env::current_dir() {
         eprintln!(
             "{GRAY}Your current directory is at: {}{GRAY:#}",
             path.display()
@@ -71,7 +274,7 @@ fn run_as_executable(env: &dyn Environment, tool: &ToolMetadata) -> Result<()> {
     }
 
     eprintln!();
-    eprint!("{} ", tool.command);
+    eprint!("{prefix} ");
 
     let mut args = String::new();
     let stdin = std::io::stdin();
@@ -80,31 +283,24 @@ fn run_as_executable(env: &dyn Environment, tool: &ToolMetadata) -> Result<()> {
         Ok(..) => {}
         Err(inner) if inner.kind() == std::io::ErrorKind::Interrupted => {
             debug!("got interrupted");
-            return Ok(());
+            return Ok(None);
         }
         Err(error) => return Err(error.into()),
     };
 
-    // Then we can create a brand new process to do this YAY
-    let args = args.trim().to_string();
-    let args = args.split(" ").collect::<Vec<_>>();
-    let Some(cmd) = env.find_tool_executable(tool)? else {
-        bail!(
-            "I cannot run {} for you. Did you forget to install this tool?",
-            tool.command
-        )
-    };
-
-    eprintln!();
-
-    let mut builder = ProcessBuilder::new(cmd);
-    builder.args(&args);
+    let args = args.trim();
+    if args.is_empty() {
+        return Ok(Some(Vec::new()));
+    }
+    Ok(Some(args.split(' ').map(String::from).collect()))
+}
 
+fn spawn_and_wait(builder: &ProcessBuilder) -> Result<()> {
     eprintln!("{GRAY}{builder}{GRAY:#}");
     let child = builder
         .build_command()
         .spawn()
-        .with_context(|| ProcessError::could_not_execute(&builder))?;
+        .with_context(|| ProcessError::could_not_execute(builder))?;
 
     let output = child.wait_with_output()?;
     if !output.status.success() {
@@ -119,6 +315,62 @@ fn run_as_executable(env: &dyn Environment, tool: &ToolMetadata) -> Result<()> {
     Ok(())
 }
 
+/// Asks the user whether to run the tool or to open one of its links.
+///
+/// If `with_form` is set and the tool describes its options, the user
+/// can also choose to build the arguments from a form.
+///
+/// It skips the prompt if there is nothing else to choose from.
+fn prompt_executable_action(
+    tool: &ToolMetadata,
+    run_label: String,
+    with_form: bool,
+) -> Result<Option<ExecutableAction<'_>>> {
+    let mut actions = vec![(run_label, ExecutableAction::Run)];
+    if with_form && !tool.options.is_empty() {
+        actions.push((
+            "📝 Build the arguments from a form".into(),
+            ExecutableAction::RunWithForm,
+        ));
+    }
+    if let Some(docs) = tool.docs.as_deref() {
+        actions.push((
+            "📖 Open documentation".into(),
+            ExecutableAction::OpenLink(docs),
+        ));
+    }
+    if let Some(homepage) = tool.homepage.as_deref() {
+        actions.push((
+            "🏠 Open homepage".into(),
+            ExecutableAction::OpenLink(homepage),
+        ));
+    }
+
+    if actions.len() == 1 {
+        return Ok(actions.pop().map(|(_, action)| action));
+    }
+
+    let items = actions.iter().map(|(name, _)| name).collect::<Vec<_>>();
+    let idx = Select::with_theme(&ColorfulTheme::default())
+        .with_prompt("What do you want to do?")
+        .default(0)
+        .items(&items)
+        .interact()
+        .map(Some)
+        .or_else(interrupted_as_none)
+        .context("failed to prompt choice")?;
+
+    Ok(idx.and_then(|idx| actions.into_iter().nth(idx).map(|(_, action)| action)))
+}
+
+fn open_link(url: &str) -> Result<()> {
+    if !url.starts_with("https://") && !url.starts_with("http://") {
+        bail!("{url:?} is not a valid web link");
+    }
+    opener::open(url).context("failed to redirect to a link")?;
+    Ok(())
+}
+
 #[must_use]
 fn wrap_text<'s>(content: &'s str, term: &Term) -> Vec<Cow<'s, str>> {
     const PREFERRED_WIDTH: u16 = 100;
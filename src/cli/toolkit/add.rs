@@ -0,0 +1,219 @@
+use anstream::eprintln;
+use anyhow::{Context, Result, bail};
+use console::Term;
+use dialoguer::theme::ColorfulTheme;
+use dialoguer::{Confirm, Input};
+use log::debug;
+use std::collections::BTreeMap;
+
+use crate::cli::ansi::*;
+use crate::cli::interrupted_as_none;
+use crate::env::Environment;
+use crate::registry::{ToolMetadata, Toolkit, UserToolkit};
+
+/// Outcome of looking up the package that provides a tool.
+#[cfg(feature = "auto-install-tools")]
+enum PackageChoice {
+    Use(String),
+    Skip,
+    Cancelled,
+}
+
+pub fn run(
+    env: &dyn Environment,
+    stderr: &Term,
+    toolkit: &Toolkit,
+    user_toolkit: &UserToolkit,
+    command: &str,
+) -> Result<()> {
+    if !env.is_live() {
+        bail!("Mock environments are prohibited to run this action");
+    }
+
+    let command = command.trim();
+    if command.is_empty() || command.chars().any(char::is_whitespace) {
+        bail!("{command:?} is not a valid command name");
+    }
+
+    stderr.clear_screen()?;
+    eprintln!("{BOLD}Adding {command:?} into your toolkit{BOLD:#}");
+    eprintln!(
+        "{GRAY}Your toolkit file is located at: {}{GRAY:#}",
+        user_toolkit.path().display()
+    );
+
+    if toolkit.find_tool(command).is_some() {
+        eprintln!(
+            "{YELLOW}⚠️ {command:?} already exists in the toolkit. \
+            Your entry will take its place.{YELLOW:#}"
+        );
+    }
+    eprintln!();
+
+    let theme = ColorfulTheme::default();
+    let Some(name) = prompt_text(&theme, "Name", Some(command), false)? else {
+        return cancelled();
+    };
+
+    let Some(description) = prompt_text(&theme, "Description", None, false)? else {
+        return cancelled();
+    };
+
+    eprintln!("{GRAY}Enter example usages one by one. Leave it empty to finish.{GRAY:#}");
+    let mut examples = Vec::new();
+    loop {
+        let Some(example) = prompt_text(&theme, "Example", None, true)? else {
+            return cancelled();
+        };
+        if example.is_empty() {
+            break;
+        }
+        examples.push(example);
+    }
+
+    #[allow(unused_mut)]
+    let mut tool = ToolMetadata::builder()
+        .name(name)
+        .command(command.to_string())
+        .description(description)
+        .examples(examples)
+        .build();
+
+    #[cfg(feature = "auto-install-tools")]
+    match prompt_package(env, &theme, command)? {
+        PackageChoice::Use(package) => {
+            let (pkg_manager, _) = env
+                .pkg_manager()
+                .expect("package manager should be present");
+
+            tool.packages
+                .insert(pkg_manager.as_registry_key().to_string(), package);
+        }
+        PackageChoice::Skip => {}
+        PackageChoice::Cancelled => return cancelled(),
+    }
+
+    eprintln!();
+    eprintln!("{BOLD}This entry will be added into your toolkit:{BOLD:#}");
+
+    let mut entry = BTreeMap::new();
+    entry.insert(command.to_string(), tool.to_yaml_mapping());
+    let preview = serde_yml::to_string(&entry).context("failed to serialize tool")?;
+    for line in preview.lines() {
+        eprintln!("{GRAY}{line}{GRAY:#}");
+    }
+    eprintln!();
+
+    match prompt_confirm(&theme, "Do you want to save this tool?")? {
+        Some(true) => {}
+        Some(false) | None => return cancelled(),
+    }
+
+    user_toolkit.add_tool(&tool)?;
+    eprintln!(
+        "✅ {GREEN}{BOLD}Added {} into {}{BOLD:#}{GREEN:#}",
+        tool.name,
+        user_toolkit.path().display()
+    );
+
+    Ok(())
+}
+
+/// Suggests and confirms which package from the detected package
+/// manager provides the tool.
+#[cfg(feature = "auto-install-tools")]
+fn prompt_package(
+    env: &dyn Environment,
+    theme: &ColorfulTheme,
+    command: &str,
+) -> Result<PackageChoice> {
+    let Some((pkg_manager, exec)) = env.pkg_manager() else {
+        debug!("no package manager detected; skipping package lookup");
+        return Ok(PackageChoice::Skip);
+    };
+
+    let display_name = pkg_manager.as_display_name();
+    let mut candidate = command.to_string();
+    loop {
+        eprintln!("⏳ {GRAY}Looking up {candidate:?} from {display_name} repositories...{GRAY:#}");
+
+        let info = pkg_manager
+            .query_package(&exec, &candidate)
+            .with_context(|| format!("failed to query {display_name} for {candidate:?}"))?;
+
+        let accepted = match info {
+            Some(info) => {
+                eprint!("{GREEN}✅ Found package {:?}", info.name);
+                if let Some(summary) = info.summary {
+                    eprint!(": {summary}");
+                }
+                eprintln!("{GREEN:#}");
+
+                let question =
+                    format!("Install this tool with {display_name} package {candidate:?}?");
+                match prompt_confirm(theme, &question)? {
+                    Some(accepted) => accepted,
+                    None => return Ok(PackageChoice::Cancelled),
+                }
+            }
+            None => {
+                eprintln!(
+                    "{YELLOW}❌ {display_name} could not find a package \
+                    named {candidate:?}{YELLOW:#}"
+                );
+                false
+            }
+        };
+
+        if accepted {
+            return Ok(PackageChoice::Use(candidate));
+        }
+
+        let prompt = format!("{display_name} package name (leave it empty to skip)");
+        let Some(next) = prompt_text(theme, &prompt, None, true)? else {
+            return Ok(PackageChoice::Cancelled);
+        };
+
+        if next.is_empty() {
+            return Ok(PackageChoice::Skip);
+        }
+        candidate = next;
+    }
+}
+
+fn cancelled() -> Result<()> {
+    debug!("got interrupted");
+    eprintln!("{GRAY}Cancelled. Your toolkit file is left untouched.{GRAY:#}");
+    Ok(())
+}
+
+fn prompt_text(
+    theme: &ColorfulTheme,
+    prompt: &str,
+    default: Option<&str>,
+    allow_empty: bool,
+) -> Result<Option<String>> {
+    let mut input = Input::<String>::with_theme(theme)
+        .with_prompt(prompt)
+        .allow_empty(allow_empty);
+
+    if let Some(default) = default {
+        input = input.default(default.to_string());
+    }
+
+    input
+        .interact_text()
+        .map(|v| Some(v.trim().to_string()))
+        .or_else(interrupted_as_none)
+        .context("failed to prompt response")
+}
+
+fn prompt_confirm(theme: &ColorfulTheme, prompt: &str) -> Result<Option<bool>> {
+    Confirm::with_theme(theme)
+        .with_prompt(prompt)
+        .default(true)
+        .interact()
+        .map(Some)
+        .or_else(interrupted_as_none)
+        .context("failed to prompt response")
+}
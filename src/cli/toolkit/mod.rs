@@ -0,0 +1,76 @@
+use anyhow::{Context, Result};
+use clap::Subcommand;
+use console::Term;
+use std::path::PathBuf;
+
+use crate::env::Environment;
+use crate::registry::{Toolkit, UserToolkit};
+use crate::util::ProgramDirs;
+
+pub mod add;
+pub mod trust;
+#[cfg(feature = "auto-install-tools")]
+pub mod verify_packages;
+
+/// Actions that manage the user's own toolkit.
+#[derive(Debug, PartialEq, Eq, Subcommand)]
+pub enum ToolkitAction {
+    /// Interactively adds a new tool into your own toolkit file.
+    Add {
+        /// The command used to run the tool (e.g. `binwalk`).
+        command: String,
+    },
+
+    /// Trusts a publisher key to sign shared toolkit files.
+    Trust {
+        /// Path to the publisher's minisign public key file.
+        key: PathBuf,
+
+        /// Name to remember the key by. It defaults to the key's file name.
+        #[clap(long)]
+        name: Option<String>,
+    },
+
+    /// Checks whether the packages of every tool exist in the repositories
+    /// of the detected package manager.
+    #[cfg(feature = "auto-install-tools")]
+    #[clap(name = "verify-packages")]
+    VerifyPackages {
+        /// Endpoint of the AUR RPC interface used to look up AUR packages.
+        #[clap(long, default_value = crate::pkg::DEFAULT_AUR_RPC_URL)]
+        aur_rpc_url: String,
+    },
+}
+
+pub fn run(
+    action: ToolkitAction,
+    env: &dyn Environment,
+    stderr: &Term,
+    toolkit: &Toolkit,
+) -> Result<()> {
+    match action {
+        ToolkitAction::Add { command } => {
+            self::add::run(env, stderr, toolkit, &user_toolkit()?, &command)
+        }
+        ToolkitAction::Trust { key, name } => self::trust::run(&program_dirs()?, &key, name),
+        #[cfg(feature = "auto-install-tools")]
+        ToolkitAction::VerifyPackages { aur_rpc_url } => {
+            self::verify_packages::run(env, stderr, toolkit, &aur_rpc_url)
+        }
+    }
+}
+
+/// Gets the user's own toolkit file from the program directories.
+pub fn user_toolkit() -> Result<UserToolkit> {
+    Ok(UserToolkit::new(program_dirs()?.user_toolkit_file()))
+}
+
+fn program_dirs() -> Result<ProgramDirs> {
+    ProgramDirs::detect().with_context(|| {
+        format!(
+            "could not find the configuration directory for ctftools. \
+            Please set {} environment variable instead.",
+            ProgramDirs::HOME_ENV_VAR
+        )
+    })
+}
@@ -0,0 +1,27 @@
+use anstream::eprintln;
+use anyhow::{Context, Result};
+use std::path::Path;
+
+use crate::cli::ansi::*;
+use crate::registry::TrustStore;
+use crate::util::ProgramDirs;
+
+pub fn run(dirs: &ProgramDirs, key: &Path, name: Option<String>) -> Result<()> {
+    let name = match name {
+        Some(name) => name,
+        None => key
+            .file_stem()
+            .and_then(|v| v.to_str())
+            .with_context(|| format!("could not get the key name from {}", key.display()))?
+            .to_string(),
+    };
+
+    let contents = std::fs::read_to_string(key)
+        .with_context(|| format!("could not read {}", key.display()))?;
+
+    let path = TrustStore::install_key(&dirs.trusted_keys_dir(), &name, &contents)?;
+    eprintln!("✅ {GREEN}{BOLD}Trusted {name:?} to sign shared toolkits{BOLD:#}{GREEN:#}");
+    eprintln!("{GRAY}The key is saved at: {}{GRAY:#}", path.display());
+
+    Ok(())
+}
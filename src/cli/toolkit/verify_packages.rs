@@ -0,0 +1,69 @@
+use anstream::{eprintln, println};
+use anyhow::{Context, Result, bail};
+use console::Term;
+
+use crate::cli::TermExt;
+use crate::cli::ansi::*;
+use crate::env::Environment;
+use crate::install::PackageOrigin;
+use crate::install::verify::{PackageStatus, verify_packages};
+use crate::registry::Toolkit;
+
+pub fn run(
+    env: &dyn Environment,
+    stderr: &Term,
+    toolkit: &Toolkit,
+    aur_rpc_url: &str,
+) -> Result<()> {
+    let Some((pkg_manager, exec)) = env.pkg_manager() else {
+        bail!("Could not find a supported package manager to verify the packages with");
+    };
+    let display_name = pkg_manager.as_display_name();
+
+    stderr.hide_cursor()?;
+    eprintln!("⏳ {BOLD}Looking up the packages of all tools from {display_name}...{BOLD:#}");
+
+    let results = verify_packages(pkg_manager, &exec, toolkit.tools(), aur_rpc_url);
+    stderr.show_cursor()?;
+    let results = results.context("failed to verify the packages of the toolkit")?;
+    stderr.clear_lines(1)?;
+
+    let divider = "=".repeat(25);
+    eprintln!("{BOLD}{divider} {display_name} Packages {divider}{BOLD:#}");
+
+    let mut missing = 0usize;
+    for result in results.iter() {
+        let name = &result.tool.name;
+        let Some((package, origin)) = result.package.as_ref() else {
+            println!("* {GRAY}➖ {name} (no package, uses downloads instead){GRAY:#}");
+            continue;
+        };
+
+        let package = match origin {
+            PackageOrigin::Repository => package.clone(),
+            PackageOrigin::AUR => format!("{package} (AUR)"),
+        };
+
+        match result.status {
+            PackageStatus::Found => println!("* {GREEN}✅ {name} → {package}{GREEN:#}"),
+            PackageStatus::Missing => {
+                missing += 1;
+                println!("* {RED}❌ {name} → {package} (not found){RED:#}");
+            }
+            PackageStatus::Unmapped => {
+                println!("* {GRAY}➖ {name} (no package mapped){GRAY:#}");
+            }
+        }
+    }
+
+    eprintln!();
+    if missing > 0 {
+        println!(
+            "{RED}{BOLD}{missing} tool(s) would fail to install with {display_name}.{BOLD:#}{RED:#}"
+        );
+        bail!("{missing} package(s) could not be found from {display_name}");
+    }
+
+    println!("{GREEN}{BOLD}All packages can be installed with {display_name}.{BOLD:#}{GREEN:#}");
+    Ok(())
+}
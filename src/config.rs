@@ -0,0 +1,280 @@
+use anyhow::{Context, Result, bail};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::time::Duration;
+
+/// The user's configuration of ctftools, read from the
+/// [configuration file](crate::util::ProgramDirs::config_file).
+///
+/// Every setting is optional and falls back to its default value.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    /// Settings of the files downloaded to install tools.
+    pub downloads: DownloadConfig,
+
+    /// Named lists of tools, such as the tools to bundle for an
+    /// on-site CTF, where each tool is referred to by its command.
+    pub profiles: BTreeMap<String, Vec<String>>,
+
+    /// Settings of the packages installed from the Arch User Repository.
+    pub aur: AurConfig,
+
+    /// Settings of the packages installed with universal package
+    /// managers, such as Flatpak or Nix.
+    pub universal_packages: UniversalPackageConfig,
+
+    /// Keys of the installers to try first when installing a tool, such
+    /// as `flatpak` or `dnf`, in order of preference. Installers that are
+    /// not listed are tried after them in their default order.
+    pub installers: Vec<String>,
+}
+
+/// Settings of the packages installed with universal package managers
+/// (Nix, Flatpak, Snap and Homebrew on Linux).
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct UniversalPackageConfig {
+    /// Whether packages are installed for the current user or for every
+    /// user of the system. It is ignored by the package managers that
+    /// only support one of them, such as Snap.
+    pub scope: PackageScope,
+}
+
+/// Who a package is installed for.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PackageScope {
+    /// Only the current user, which needs no elevated privileges.
+    #[default]
+    User,
+
+    /// Every user of the system, which needs elevated privileges.
+    System,
+}
+
+/// Settings of the packages installed from the Arch User Repository (AUR)
+/// when there is no AUR helper to install them with.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct AurConfig {
+    /// URL of the AUR, where the git repository of each package
+    /// is cloned from at `<base_url>/<package>.git`.
+    pub base_url: String,
+}
+
+impl Default for AurConfig {
+    fn default() -> Self {
+        Self {
+            base_url: "https://aur.archlinux.org".to_string(),
+        }
+    }
+}
+
+impl AurConfig {
+    /// Gets the URL of the git repository of an AUR package.
+    #[must_use]
+    pub fn clone_url(&self, package: &str) -> String {
+        format!("{}/{package}.git", self.base_url.trim_end_matches('/'))
+    }
+}
+
+/// Settings of the files downloaded to install tools.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct DownloadConfig {
+    /// Refuses to install tools from downloads that have no
+    /// checksum or signature to verify them against.
+    pub require_checksums: bool,
+
+    /// How many times a download from the same URL is retried after
+    /// a transient failure, such as a timeout or a server error.
+    pub retries: u32,
+
+    /// Seconds to wait for a connection to the server.
+    pub connect_timeout: u64,
+
+    /// Seconds to wait for the server to send more data.
+    pub read_timeout: u64,
+}
+
+impl Default for DownloadConfig {
+    fn default() -> Self {
+        Self {
+            require_checksums: false,
+            retries: 3,
+            connect_timeout: 30,
+            read_timeout: 60,
+        }
+    }
+}
+
+impl DownloadConfig {
+    /// Time to wait for a connection to the server.
+    #[must_use]
+    pub fn connect_timeout(&self) -> Duration {
+        Duration::from_secs(self.connect_timeout)
+    }
+
+    /// Time to wait for the server to send more data.
+    #[must_use]
+    pub fn read_timeout(&self) -> Duration {
+        Duration::from_secs(self.read_timeout)
+    }
+}
+
+impl Config {
+    /// Loads the configuration from a YAML file.
+    ///
+    /// Returns the default configuration if the file does not exist.
+    pub fn load(path: &Path) -> Result<Self> {
+        let contents = match std::fs::read_to_string(path) {
+            Ok(contents) => contents,
+            Err(error) if error.kind() == std::io::ErrorKind::NotFound => {
+                return Ok(Self::default());
+            }
+            Err(error) => {
+                return Err(error).with_context(|| format!("could not read {}", path.display()));
+            }
+        };
+
+        Self::from_yaml(&contents)
+            .with_context(|| format!("could not load configuration from {}", path.display()))
+    }
+
+    /// Parses the configuration from YAML. An empty document
+    /// is the default configuration.
+    pub fn from_yaml(contents: &str) -> Result<Self> {
+        if contents.trim().is_empty() {
+            return Ok(Self::default());
+        }
+
+        let config: Self = serde_yml::from_str(contents)?;
+
+        // A zero timeout would fail every download right away.
+        if config.downloads.connect_timeout == 0 {
+            bail!("downloads.connect_timeout must be greater than 0");
+        }
+        if config.downloads.read_timeout == 0 {
+            bail!("downloads.read_timeout must be greater than 0");
+        }
+
+        #[cfg(feature = "auto-install-tools")]
+        {
+            use crate::pkg::Installer;
+
+            let is_known =
+                |key: &String| Installer::orderable().any(|v| v.as_registry_key() == key);
+            if let Some(key) = config.installers.iter().find(|v| !is_known(v)) {
+                let keys = Installer::orderable()
+                    .map(|v| v.as_registry_key())
+                    .collect::<Vec<_>>();
+                bail!(
+                    "unknown installer {key:?} in installers (expected one of: {})",
+                    keys.join(", ")
+                );
+            }
+        }
+        Ok(config)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use maplit::btreemap;
+    use pretty_assertions::assert_eq;
+    use std::time::Duration;
+    use tempdir::TempDir;
+
+    use super::{AurConfig, Config, DownloadConfig, PackageScope};
+
+    #[test]
+    fn test_load_missing_file() {
+        let dir = TempDir::new("ctftools_test").unwrap();
+        let config = Config::load(&dir.path().join("config.yml")).unwrap();
+        assert_eq!(config, Config::default());
+    }
+
+    #[test]
+    fn test_load_config() {
+        let dir = TempDir::new("ctftools_test").unwrap();
+        let path = dir.path().join("config.yml");
+
+        std::fs::write(&path, "# nothing here yet\n").unwrap();
+        assert_eq!(Config::load(&path).unwrap(), Config::default());
+
+        std::fs::write(&path, "downloads:\n  require_checksums: true\n").unwrap();
+        assert_eq!(
+            Config::load(&path).unwrap(),
+            Config {
+                downloads: DownloadConfig {
+                    require_checksums: true,
+                    ..DownloadConfig::default()
+                },
+                ..Config::default()
+            }
+        );
+
+        std::fs::write(
+            &path,
+            "downloads:\n  retries: 0\n  connect_timeout: 5\n  read_timeout: 10\n",
+        )
+        .unwrap();
+        let config = Config::load(&path).unwrap();
+        assert_eq!(config.downloads.retries, 0);
+        assert_eq!(config.downloads.connect_timeout(), Duration::from_secs(5));
+        assert_eq!(config.downloads.read_timeout(), Duration::from_secs(10));
+        assert!(!config.downloads.require_checksums);
+
+        std::fs::write(&path, "profiles:\n  finals: [ghidra, nmap]\n").unwrap();
+        assert_eq!(
+            Config::load(&path).unwrap().profiles,
+            btreemap! {
+                "finals".to_string() => vec!["ghidra".to_string(), "nmap".to_string()],
+            }
+        );
+
+        std::fs::write(&path, "aur:\n  base_url: https://aur.example.com/\n").unwrap();
+        let config = Config::load(&path).unwrap();
+        assert_eq!(
+            config.aur.clone_url("paru-bin"),
+            "https://aur.example.com/paru-bin.git"
+        );
+
+        std::fs::write(&path, "universal_packages:\n  scope: system\n").unwrap();
+        let config = Config::load(&path).unwrap();
+        assert_eq!(config.universal_packages.scope, PackageScope::System);
+
+        std::fs::write(&path, "installers: [flatpak, dnf]\n").unwrap();
+        let config = Config::load(&path).unwrap();
+        assert_eq!(config.installers, ["flatpak", "dnf"]);
+
+        // AUR helpers and container runtimes cannot be ordered.
+        #[cfg(feature = "auto-install-tools")]
+        for unknown in ["flatpack", "aur", "docker"] {
+            std::fs::write(&path, format!("installers: [{unknown}, dnf]\n")).unwrap();
+            let error = Config::load(&path).unwrap_err();
+            assert!(
+                format!("{error:#}").contains(&format!("unknown installer \"{unknown}\"")),
+                "{error:#}"
+            );
+        }
+
+        std::fs::write(&path, "downloads:\n  require_checksum: true\n").unwrap();
+        assert!(Config::load(&path).is_err());
+
+        for invalid in ["connect_timeout: 0", "read_timeout: 0"] {
+            std::fs::write(&path, format!("downloads:\n  {invalid}\n")).unwrap();
+            assert!(Config::load(&path).is_err(), "{invalid}");
+        }
+    }
+
+    #[test]
+    fn test_aur_clone_url() {
+        assert_eq!(
+            AurConfig::default().clone_url("yay"),
+            "https://aur.archlinux.org/yay.git"
+        );
+    }
+}
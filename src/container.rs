@@ -0,0 +1,132 @@
+use anyhow::Result;
+use std::path::{Path, PathBuf};
+
+use crate::process::ProcessBuilder;
+use crate::registry::ToolContainerMetadata;
+use crate::util::find_first_match;
+
+/// The directory inside the container where the user's current
+/// directory is mounted when running a container tool.
+pub const CONTAINER_WORKDIR: &str = "/work";
+
+/// Represents a container runtime used to run
+/// [container tools](crate::registry::ToolType::Container).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ContainerRuntime {
+    Docker,
+    Podman,
+}
+
+impl ContainerRuntime {
+    /// Detects the system's preferred container runtime.
+    ///
+    /// Returns `Ok(Some((ContainerRuntime, PathBuf)))` if a container
+    /// runtime is found, where the `PathBuf` is the full path to its executable.
+    ///
+    /// Returns `Ok(None)` if no supported container runtime is detected.
+    ///
+    /// Detection is performed based on the available binaries in the system PATH.
+    pub fn detect() -> Result<Option<(Self, PathBuf)>> {
+        find_first_match(&[("docker", Self::Docker), ("podman", Self::Podman)])
+    }
+
+    /// Returns a human-friendly name for this container runtime
+    /// suitable for UI display.
+    #[must_use]
+    pub fn as_display_name(&self) -> &'static str {
+        match self {
+            Self::Docker => "Docker",
+            Self::Podman => "Podman",
+        }
+    }
+
+    /// Builds a command that exits successfully only if
+    /// the image exists locally.
+    #[must_use]
+    pub fn image_inspect_command(&self, exec: &Path, image: &str) -> ProcessBuilder {
+        let mut builder = ProcessBuilder::new(exec);
+        builder.args(&["image", "inspect", image]);
+        builder
+    }
+
+    /// Builds a command that runs a container tool interactively
+    /// with `workdir` mounted at [`CONTAINER_WORKDIR`].
+    ///
+    /// The container is removed once it exits.
+    #[must_use]
+    pub fn run_command<T: AsRef<str>>(
+        &self,
+        exec: &Path,
+        container: &ToolContainerMetadata,
+        workdir: &Path,
+        args: &[T],
+    ) -> ProcessBuilder {
+        let mut volume = workdir.as_os_str().to_os_string();
+        volume.push(":");
+        volume.push(CONTAINER_WORKDIR);
+
+        let mut builder = ProcessBuilder::new(exec);
+        builder.args(&["run", "--rm", "-it", "-v"]);
+        builder.arg(volume);
+        builder.args(&["-w", CONTAINER_WORKDIR, &container.image]);
+        builder.args(&container.args);
+        for arg in args {
+            builder.arg(arg.as_ref());
+        }
+        builder
+    }
+
+    /// Checks whether the image of a container tool exists locally.
+    pub fn image_exists(&self, exec: &Path, image: &str) -> Result<bool> {
+        let output = self.image_inspect_command(exec, image).output()?;
+        Ok(output.status.success())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+    use std::path::Path;
+
+    use crate::container::ContainerRuntime;
+    use crate::registry::ToolContainerMetadata;
+
+    #[test]
+    fn test_run_command() {
+        let container = ToolContainerMetadata::builder()
+            .image("sagemath/sagemath:9.5".into())
+            .args(vec!["sage".into()])
+            .build();
+
+        let builder = ContainerRuntime::Podman.run_command(
+            Path::new("podman"),
+            &container,
+            Path::new("/home/ctf"),
+            &["solve.sage"],
+        );
+
+        assert_eq!(
+            builder.to_string(),
+            "podman run --rm -it -v '/home/ctf:/work' -w /work 'sagemath/sagemath:9.5' sage solve.sage"
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_image_exists_with_fake_docker() {
+        use crate::testing::write_script;
+        use tempdir::TempDir;
+
+        // This fake docker only knows the `sage:9.5` image.
+        let dir = TempDir::new("ctftools_test").unwrap();
+        let docker = write_script(
+            dir.path(),
+            "docker",
+            "[ \"$1 $2\" = \"image inspect\" ] && [ \"$3\" = \"sage:9.5\" ]\n",
+        );
+
+        let runtime = ContainerRuntime::Docker;
+        assert!(runtime.image_exists(&docker, "sage:9.5").unwrap());
+        assert!(!runtime.image_exists(&docker, "sage:10.0").unwrap());
+    }
+}
@@ -1,38 +1,153 @@
-use anyhow::Result;
+use anyhow::{Context, Result};
 use cfg_if::cfg_if;
+use log::debug;
 use std::path::PathBuf;
 
+use crate::config::Config;
+use crate::container::ContainerRuntime;
 use crate::env::Environment;
-use crate::registry::ToolMetadata;
-use crate::util::which_opt;
+use crate::process::ProcessBuilder;
+use crate::registry::{ToolLibraryMetadata, ToolMetadata, ToolType};
+use crate::util::{ProgramDirs, which_opt};
 
 cfg_if! {
     if #[cfg(feature = "auto-install-tools")] {
-        use crate::install::live::{perform_task_via_download, perform_task_via_pkg_manager};
+        use anyhow::bail;
+        use crate::install::live::{
+            perform_task_via_aur, perform_task_via_download, perform_task_via_local_download,
+            perform_task_via_package_files, perform_task_via_pkg_manager, perform_task_via_source,
+        };
         use crate::install::{InstallProgress, InstallTask};
-        use crate::pkg::{AurHelper, PackageManager};
+        use crate::pkg::{
+            AurHelper, LanguagePackageManager, PackageManager, UniversalPackageManager,
+        };
+        use std::collections::HashSet;
+        use std::sync::OnceLock;
     }
 }
 
 #[derive(Debug)]
 pub struct LiveEnvironment {
+    config: Config,
+
+    /// Why the user's configuration could not be loaded, in which
+    /// case the default configuration is used and nothing is installed.
+    config_error: Option<anyhow::Error>,
+    container_runtime: Option<(ContainerRuntime, PathBuf)>,
+
+    /// Directories where language package managers (and ctftools itself)
+    /// put the executables they install for the user. They may not be in `PATH`.
+    user_bin_dirs: Vec<PathBuf>,
+    #[cfg(feature = "auto-install-tools")]
+    program_dirs: Option<ProgramDirs>,
+    /// Every system package manager detected, where
+    /// the first one is the preferred one.
     #[cfg(feature = "auto-install-tools")]
-    pkg_manager: Option<WithPath<PackageManager>>,
+    pkg_managers: Vec<WithPath<PackageManager>>,
     #[cfg(feature = "auto-install-tools")]
     aur_helper: Option<WithPath<AurHelper>>,
+    #[cfg(feature = "auto-install-tools")]
+    language_pkg_managers: Vec<WithPath<LanguagePackageManager>>,
+
+    /// The bin directory of RubyGems, which is only looked
+    /// up once it is needed since it has to run `gem`.
+    #[cfg(feature = "auto-install-tools")]
+    gem_bin_dir: OnceLock<Option<PathBuf>>,
+    #[cfg(feature = "auto-install-tools")]
+    universal_pkg_managers: Vec<WithPath<UniversalPackageManager>>,
 }
 
 impl LiveEnvironment {
     /// Creates a new [`LiveEnvironment`] where it detects available
-    /// system package managers and AUR helper (if the user installed
-    /// Arch Linux or has an AUR helper binary present)
+    /// system package managers, AUR helper (if the user installed
+    /// Arch Linux or has an AUR helper binary present), language
+    /// and universal package managers and container runtime.
+    ///
+    /// The user's configuration is loaded as well. If it is malformed,
+    /// the default configuration is used, the error is kept in
+    /// [`config_error`](LiveEnvironment::config_error) and installing
+    /// tools is refused.
     pub fn new() -> Result<Self> {
-        Ok(Self {
+        let program_dirs = ProgramDirs::detect();
+        let (config, config_error) = match program_dirs.as_ref() {
+            Some(dirs) => match Config::load(&dirs.config_file()) {
+                Ok(config) => (config, None),
+                Err(error) => (Config::default(), Some(error)),
+            },
+            None => (Config::default(), None),
+        };
+
+        let env = Self {
+            config,
+            config_error,
+            container_runtime: ContainerRuntime::detect()?,
+            user_bin_dirs: Vec::new(),
             #[cfg(feature = "auto-install-tools")]
-            pkg_manager: PackageManager::detect()?.map(Into::into),
+            program_dirs,
+            #[cfg(feature = "auto-install-tools")]
+            pkg_managers: PackageManager::detect_all()?
+                .into_iter()
+                .map(Into::into)
+                .collect(),
             #[cfg(feature = "auto-install-tools")]
             aur_helper: AurHelper::detect()?.map(Into::into),
-        })
+            #[cfg(feature = "auto-install-tools")]
+            language_pkg_managers: LanguagePackageManager::detect_all()?
+                .into_iter()
+                .map(Into::into)
+                .collect(),
+            #[cfg(feature = "auto-install-tools")]
+            gem_bin_dir: OnceLock::new(),
+            #[cfg(feature = "auto-install-tools")]
+            universal_pkg_managers: UniversalPackageManager::detect_all(
+                dirs::home_dir().as_deref(),
+            )?
+            .into_iter()
+            .map(Into::into)
+            .collect(),
+        };
+
+        #[cfg(feature = "auto-install-tools")]
+        let env = env.with_detected_bin_dirs();
+
+        Ok(env)
+    }
+
+    /// Adds the bin directories of our own prefix and of the detected language
+    /// package managers, except for RubyGems whose directory is looked up lazily.
+    #[cfg(feature = "auto-install-tools")]
+    fn with_detected_bin_dirs(mut self) -> Self {
+        // Tools built from source or extracted from archives
+        // are exposed in our own bin directory.
+        if let Some(dir) = self.program_dirs.as_ref().and_then(ProgramDirs::bin_dir) {
+            self.add_user_bin_dir(dir);
+        }
+
+        let Some(home) = dirs::home_dir() else {
+            return self;
+        };
+
+        let pkg_managers = self.language_pkg_managers.clone().into_iter();
+        for pm in pkg_managers.filter(|pm| pm.inner != LanguagePackageManager::Gem) {
+            match pm.user_bin_dir(&pm.path, &home) {
+                Ok(Some(dir)) => self.add_user_bin_dir(dir),
+                Ok(None) => {}
+                Err(error) => debug!(
+                    "could not find the bin directory of {}: {error:#}",
+                    pm.as_display_name()
+                ),
+            }
+        }
+        self
+    }
+
+    /// Adds a directory to look up executables installed for the user
+    /// by language package managers (e.g. `~/.cargo/bin`).
+    pub fn add_user_bin_dir(&mut self, dir: PathBuf) {
+        if !self.user_bin_dirs.contains(&dir) {
+            debug!("looking up tools from {} as well", dir.display());
+            self.user_bin_dirs.push(dir);
+        }
     }
 
     /// Creates a new [`LiveEnvironment`] with a package manager present.
@@ -42,20 +157,57 @@ impl LiveEnvironment {
     #[must_use]
     pub fn with_pkg_manager(pm: PackageManager, path: PathBuf) -> Self {
         Self {
-            pkg_manager: Some(WithPath { inner: pm, path }),
+            config: Config::default(),
+            config_error: None,
+            container_runtime: None,
+            user_bin_dirs: Vec::new(),
+            program_dirs: None,
+            pkg_managers: vec![WithPath { inner: pm, path }],
             aur_helper: None,
+            language_pkg_managers: Vec::new(),
+            gem_bin_dir: OnceLock::new(),
+            universal_pkg_managers: Vec::new(),
         }
     }
 
     /// Creates a new [`LiveEnvironment`] with no package manager present.
-    #[cfg(feature = "auto-install-tools")]
+    ///
+    /// Nothing is detected from the system.
     #[must_use]
     pub fn without_pkg_manager() -> Self {
         Self {
-            pkg_manager: None,
+            config: Config::default(),
+            config_error: None,
+            container_runtime: None,
+            user_bin_dirs: Vec::new(),
+            #[cfg(feature = "auto-install-tools")]
+            program_dirs: None,
+            #[cfg(feature = "auto-install-tools")]
+            pkg_managers: Vec::new(),
+            #[cfg(feature = "auto-install-tools")]
             aur_helper: None,
+            #[cfg(feature = "auto-install-tools")]
+            language_pkg_managers: Vec::new(),
+            #[cfg(feature = "auto-install-tools")]
+            gem_bin_dir: OnceLock::new(),
+            #[cfg(feature = "auto-install-tools")]
+            universal_pkg_managers: Vec::new(),
         }
     }
+
+    /// Replaces the container runtime of this environment.
+    #[must_use]
+    pub fn with_container_runtime(mut self, runtime: ContainerRuntime, path: PathBuf) -> Self {
+        self.container_runtime = Some((runtime, path));
+        self
+    }
+
+    /// Gets the error that prevented the user's configuration from
+    /// being loaded, if any.
+    #[must_use]
+    pub fn config_error(&self) -> Option<&anyhow::Error> {
+        self.config_error.as_ref()
+    }
 }
 
 impl Environment for LiveEnvironment {
@@ -71,9 +223,30 @@ impl Environment for LiveEnvironment {
         crate::util::supports_privilege_escalation()
     }
 
+    fn home_dir(&self) -> Option<PathBuf> {
+        dirs::home_dir()
+    }
+
+    fn config(&self) -> &Config {
+        &self.config
+    }
+
+    fn container_runtime(&self) -> Option<(ContainerRuntime, PathBuf)> {
+        self.container_runtime.clone()
+    }
+
     #[cfg(feature = "auto-install-tools")]
     fn pkg_manager(&self) -> Option<(PackageManager, PathBuf)> {
-        self.pkg_manager.as_ref().cloned().map(WithPath::into_inner)
+        self.pkg_managers.first().cloned().map(WithPath::into_inner)
+    }
+
+    #[cfg(feature = "auto-install-tools")]
+    fn pkg_managers(&self) -> Vec<(PackageManager, PathBuf)> {
+        self.pkg_managers
+            .iter()
+            .cloned()
+            .map(WithPath::into_inner)
+            .collect()
     }
 
     #[cfg(feature = "auto-install-tools")]
@@ -81,21 +254,93 @@ impl Environment for LiveEnvironment {
         self.aur_helper.as_ref().cloned().map(WithPath::into_inner)
     }
 
+    #[cfg(feature = "auto-install-tools")]
+    fn language_pkg_managers(&self) -> Vec<(LanguagePackageManager, PathBuf)> {
+        self.language_pkg_managers
+            .iter()
+            .cloned()
+            .map(WithPath::into_inner)
+            .collect()
+    }
+
+    #[cfg(feature = "auto-install-tools")]
+    fn universal_pkg_managers(&self) -> Vec<(UniversalPackageManager, PathBuf)> {
+        self.universal_pkg_managers
+            .iter()
+            .cloned()
+            .map(WithPath::into_inner)
+            .collect()
+    }
+
+    #[cfg(feature = "auto-install-tools")]
+    fn installed_packages(&self) -> Result<HashSet<String>> {
+        match self.pkg_managers.first() {
+            Some(pm) => pm.installed_packages(&pm.path),
+            None => Ok(HashSet::new()),
+        }
+    }
+
+    #[cfg(feature = "auto-install-tools")]
+    fn program_dirs(&self) -> Option<ProgramDirs> {
+        self.program_dirs.clone()
+    }
+
     /// Attempts to locate the executable for a specific tool
     /// described by [`ToolMetadata`]
     ///
     /// The lookup strategy for [`LiveEnvironment`] is:
     /// 1. Try to find the command on the system `PATH`.
-    /// 2. On Windows, also check any additional executable paths
+    /// 2. Check the directories where language package managers
+    ///    and ctftools itself install executables for the user.
+    /// 3. Check the directories where universal package managers expose
+    ///    their packages (e.g. `/var/lib/flatpak/exports/bin` or `/snap/bin`).
+    /// 4. On Windows, also check any additional executable paths
     ///    associated with the tool's metadata.
+    ///
+    /// Library tools are found by running an import probe with
+    /// their interpreter instead, while container tools are found
+    /// by inspecting their image with the container runtime.
     fn find_tool_executable(&self, tool: &ToolMetadata) -> Result<Option<PathBuf>> {
+        if tool.kind == ToolType::Library
+            && let Some(library) = tool.library.as_ref()
+        {
+            return probe_library(library);
+        }
+
+        if tool.kind == ToolType::Container
+            && let Some(container) = tool.container.as_ref()
+        {
+            let Some((runtime, exec)) = self.container_runtime.as_ref() else {
+                return Ok(None);
+            };
+
+            let exists = runtime
+                .image_exists(exec, &container.image)
+                .with_context(|| format!("failed to inspect {:?} image", container.image))?;
+
+            return Ok(exists.then(|| exec.clone()));
+        }
+
         // There are ways we can find the tool executable either:
         // 1. By using the `which` operation (from PATH environment variable)
         if let Some(path) = which_opt(&tool.command)? {
             return Ok(Some(path));
         }
 
-        // 2. Checking tool's associated executable (if the operating system is running on Windows)
+        // 2. From the directories where language package managers install
+        //    executables for the user, in case they are not in PATH.
+        if let Some(path) = self.find_in_user_bin_dirs(&tool.command)? {
+            return Ok(Some(path));
+        }
+
+        // 3. From the directories where universal package managers (e.g. Flatpak)
+        //    expose the executables of their packages, in case they are not in PATH.
+        #[cfg(feature = "auto-install-tools")]
+        if let Some(path) = self.find_in_universal_bin_dirs(tool) {
+            return Ok(Some(path));
+        }
+
+        // 4. Checking tool's associated executable (if the operating system is running on Windows)
         #[cfg(target_os = "windows")]
         for path in tool.windows.exec_paths.iter() {
             use anyhow::Context;
@@ -115,18 +360,113 @@ impl Environment for LiveEnvironment {
     fn run_install_task(
         &self,
         task: &InstallTask,
+        confirm: &mut dyn FnMut(&str, &str) -> Result<bool>,
         progress_handler: &mut dyn FnMut(InstallProgress),
     ) -> Result<()> {
+        // Defaults such as requiring checksums may be less strict than
+        // what the user configured, so nothing is installed with them.
+        if let Some(error) = self.config_error.as_ref() {
+            bail!("tools cannot be installed until your configuration is fixed: {error:#}");
+        }
+
         match task {
             InstallTask::PackageManager { .. } => {
                 perform_task_via_pkg_manager(self, task, progress_handler)
             }
             InstallTask::Download { .. } => perform_task_via_download(self, task, progress_handler),
-            InstallTask::AUR { .. } => todo!(),
+            InstallTask::LocalDownload { .. } => {
+                perform_task_via_local_download(self, task, progress_handler)
+            }
+            InstallTask::PackageFiles { .. } => {
+                perform_task_via_package_files(self, task, progress_handler)
+            }
+            InstallTask::Source { .. } => perform_task_via_source(task, progress_handler),
+            InstallTask::AUR { .. } => perform_task_via_aur(self, task, confirm, progress_handler),
         }
     }
 }
 
+impl LiveEnvironment {
+    #[cfg(feature = "auto-install-tools")]
+    fn find_in_universal_bin_dirs(&self, tool: &ToolMetadata) -> Option<PathBuf> {
+        let home = self.home_dir();
+        for pm in self.universal_pkg_managers.iter() {
+            let package = pm.resolve_package(&tool.packages);
+            let names = pm.exported_names(&tool.command, package);
+
+            for dir in pm.bin_dirs(home.as_deref()) {
+                if let Some(path) = names.iter().map(|v| dir.join(v)).find(|v| v.is_file()) {
+                    return Some(path);
+                }
+            }
+        }
+        None
+    }
+
+    fn find_in_user_bin_dirs(&self, command: &str) -> Result<Option<PathBuf>> {
+        let dirs = self.user_bin_dirs.iter();
+        #[cfg(feature = "auto-install-tools")]
+        let dirs = dirs.chain(
+            self.gem_bin_dir()
+                .filter(|dir| !self.user_bin_dirs.contains(dir)),
+        );
+
+        let dirs = dirs.collect::<Vec<_>>();
+        if dirs.is_empty() {
+            return Ok(None);
+        }
+
+        let paths =
+            std::env::join_paths(dirs).context("could not join the user bin directories")?;
+
+        let cwd = std::env::current_dir().context("could not get the current directory")?;
+        match which::which_in(command, Some(paths), cwd) {
+            Ok(path) => Ok(Some(path)),
+            Err(which::Error::CannotFindBinaryPath) => Ok(None),
+            Err(error) => Err(error.into()),
+        }
+    }
+
+    /// Gets the directory where RubyGems puts the executables of
+    /// the gems installed for the user, if RubyGems is available.
+    #[cfg(feature = "auto-install-tools")]
+    fn gem_bin_dir(&self) -> Option<&PathBuf> {
+        self.gem_bin_dir
+            .get_or_init(|| {
+                let gem = self
+                    .language_pkg_managers
+                    .iter()
+                    .find(|pm| pm.inner == LanguagePackageManager::Gem)?;
+
+                let home = dirs::home_dir()?;
+                gem.user_bin_dir(&gem.path, &home)
+                    .inspect_err(|error| {
+                        debug!("could not find the bin directory of RubyGems: {error:#}")
+                    })
+                    .ok()
+                    .flatten()
+            })
+            .as_ref()
+    }
+}
+
+/// Finds the interpreter of a library and checks whether it can import the library.
+fn probe_library(library: &ToolLibraryMetadata) -> Result<Option<PathBuf>> {
+    let Some(interpreter) = which_opt(library.interpreter())? else {
+        return Ok(None);
+    };
+
+    let mut builder = ProcessBuilder::new(&interpreter);
+    builder.args(&library.import_probe_args());
+
+    debug!("probing library: {builder}");
+    let output = builder
+        .output()
+        .with_context(|| format!("failed to probe {:?} library", library.module))?;
+
+    Ok(output.status.success().then_some(interpreter))
+}
+
 cfg_if! {
     if #[cfg(feature = "auto-install-tools")] {
         #[derive(Clone)]
@@ -208,3 +548,122 @@ mod tests {
         });
     }
 }
+
+#[cfg(all(test, unix))]
+mod unix_tests {
+    use pretty_assertions::assert_eq;
+    use tempdir::TempDir;
+
+    use crate::container::ContainerRuntime;
+    use crate::env::live::probe_library;
+    use crate::env::{Environment, LiveEnvironment};
+    use crate::registry::{
+        LibraryLanguage, ToolContainerMetadata, ToolLibraryMetadata, ToolMetadata, ToolType,
+    };
+    use crate::testing::write_script;
+
+    #[test]
+    fn test_probe_library_with_fake_interpreter() {
+        // This interpreter can only import the `pwn` module.
+        let dir = TempDir::new("ctftools_test").unwrap();
+        let interpreter = write_script(
+            dir.path(),
+            "python3",
+            "[ \"$1\" = \"-c\" ] && [ \"$2\" = \"import pwn\" ]\n",
+        );
+
+        let library = |module: &str| {
+            ToolLibraryMetadata::builder()
+                .language(LibraryLanguage::Python)
+                .module(module.to_string())
+                .interpreter(interpreter.to_string_lossy().to_string())
+                .build()
+        };
+
+        assert_eq!(
+            probe_library(&library("pwn")).unwrap(),
+            Some(interpreter.clone())
+        );
+        assert_eq!(probe_library(&library("Crypto")).unwrap(), None);
+
+        let missing_interpreter = ToolLibraryMetadata::builder()
+            .language(LibraryLanguage::Ruby)
+            .module("pwn".into())
+            .interpreter(dir.path().join("ruby").to_string_lossy().to_string())
+            .build();
+        assert_eq!(probe_library(&missing_interpreter).unwrap(), None);
+    }
+
+    #[test]
+    fn test_find_container_tool_with_fake_docker() {
+        // This fake docker only knows the `sage:9.5` image.
+        let dir = TempDir::new("ctftools_test").unwrap();
+        let docker = write_script(
+            dir.path(),
+            "docker",
+            "[ \"$1 $2\" = \"image inspect\" ] && [ \"$3\" = \"sage:9.5\" ]\n",
+        );
+
+        let tool = |image: &str| {
+            ToolMetadata::builder()
+                .name("sage".into())
+                .command("sage".into())
+                .kind(ToolType::Container)
+                .container(ToolContainerMetadata::builder().image(image.into()).build())
+                .build()
+        };
+
+        let env = LiveEnvironment::without_pkg_manager()
+            .with_container_runtime(ContainerRuntime::Docker, docker.clone());
+
+        assert_eq!(
+            env.find_tool_executable(&tool("sage:9.5")).unwrap(),
+            Some(docker)
+        );
+        assert_eq!(env.find_tool_executable(&tool("sage:10.0")).unwrap(), None);
+    }
+
+    #[test]
+    fn test_find_tool_in_user_bin_dirs() {
+        let dir = TempDir::new("ctftools_test").unwrap();
+        let bin = dir.path().join("bin");
+        std::fs::create_dir(&bin).unwrap();
+
+        let exec = write_script(&bin, "ctftools-test-rustscan", "");
+
+        let tool = ToolMetadata::builder()
+            .name("rustscan".into())
+            .command("ctftools-test-rustscan".into())
+            .build();
+
+        let mut env = LiveEnvironment::without_pkg_manager();
+        assert_eq!(env.find_tool_executable(&tool).unwrap(), None);
+
+        env.add_user_bin_dir(bin);
+        assert_eq!(env.find_tool_executable(&tool).unwrap(), Some(exec));
+    }
+
+    #[cfg(feature = "auto-install-tools")]
+    #[test]
+    fn test_refuse_installs_with_malformed_config() {
+        use crate::install::InstallTask;
+        use crate::registry::{DownloadFileFormat, ToolDownloadInstructions};
+
+        let mut env = LiveEnvironment::without_pkg_manager();
+        env.config_error = Some(anyhow::anyhow!("invalid type: sequence"));
+
+        let task = InstallTask::Download {
+            instructions: ToolDownloadInstructions::builder()
+                .url("http://127.0.0.1:9/foo".into())
+                .format(DownloadFileFormat::Executable)
+                .build(),
+            command: "foo".into(),
+            tool_name: "foo".into(),
+        };
+
+        let error = env
+            .run_install_task(&task, &mut |_, _| Ok(true), &mut |_| {})
+            .unwrap_err();
+        assert!(error.to_string().contains("configuration"), "{error:#}");
+    }
+}
@@ -4,28 +4,74 @@ use dashmap::DashMap;
 use std::collections::HashMap;
 use std::path::PathBuf;
 
+#[cfg(feature = "auto-install-tools")]
+use anyhow::bail;
+#[cfg(feature = "auto-install-tools")]
+use std::collections::HashSet;
+#[cfg(feature = "auto-install-tools")]
+use std::sync::Mutex;
 #[cfg(feature = "auto-install-tools")]
 use std::time::Duration;
 
+use crate::config::Config;
+use crate::container::ContainerRuntime;
 use crate::env::Environment;
 use crate::registry::ToolMetadata;
 
 #[cfg(feature = "auto-install-tools")]
 use crate::install::{InstallProgress, InstallTask};
 #[cfg(feature = "auto-install-tools")]
-use crate::pkg::{AurHelper, PackageManager};
+use crate::pkg::{AurHelper, LanguagePackageManager, PackageManager, UniversalPackageManager};
+#[cfg(feature = "auto-install-tools")]
+use crate::util::ProgramDirs;
 
 #[derive(Debug, Builder)]
 pub struct MockEnvironment {
+    container_runtime: Option<ContainerRuntime>,
     #[cfg(feature = "auto-install-tools")]
     pkg_manager: Option<PackageManager>,
+    /// Package managers that are available along with the
+    /// preferred [`pkg_manager`](Self::pkg_manager).
+    #[cfg(feature = "auto-install-tools")]
+    #[builder(default)]
+    other_pkg_managers: Vec<PackageManager>,
     #[cfg(feature = "auto-install-tools")]
     aur_helper: Option<AurHelper>,
+    #[cfg(feature = "auto-install-tools")]
+    #[builder(default)]
+    language_pkg_managers: Vec<LanguagePackageManager>,
+    #[cfg(feature = "auto-install-tools")]
+    #[builder(default)]
+    universal_pkg_managers: Vec<UniversalPackageManager>,
+    #[cfg(feature = "auto-install-tools")]
+    #[builder(default)]
+    installed_packages: HashSet<String>,
+
+    /// Packages that fail to install, along with every
+    /// install task that has any of them in its arguments.
+    #[cfg(feature = "auto-install-tools")]
+    #[builder(default)]
+    failing_packages: HashSet<String>,
+
+    /// Tool names of every install task that was run, in order.
+    #[cfg(feature = "auto-install-tools")]
+    #[builder(skip)]
+    run_tasks: Mutex<Vec<String>>,
 
     #[builder(default)]
     #[builder(setters(vis = "", name = installed_tools_internal))]
     installed_tools: DashMap<String, PathBuf>,
 
+    #[builder(default = PathBuf::from("/home/ctf"))]
+    home_dir: PathBuf,
+
+    #[builder(default)]
+    config: Config,
+
+    #[cfg(feature = "auto-install-tools")]
+    #[builder(default = ProgramDirs::from_root("/home/ctf/.ctftools"))]
+    program_dirs: ProgramDirs,
+
     #[builder(default = true)]
     running_in_elevation: bool,
 
@@ -42,16 +88,63 @@ impl Environment for MockEnvironment {
         self.supports_privilege_escalation
     }
 
+    fn home_dir(&self) -> Option<PathBuf> {
+        Some(self.home_dir.clone())
+    }
+
+    fn config(&self) -> &Config {
+        &self.config
+    }
+
+    fn container_runtime(&self) -> Option<(ContainerRuntime, PathBuf)> {
+        self.container_runtime.map(|rt| (rt, PathBuf::from("")))
+    }
+
     #[cfg(feature = "auto-install-tools")]
     fn pkg_manager(&self) -> Option<(PackageManager, PathBuf)> {
         self.pkg_manager.map(|pm| (pm, PathBuf::from("")))
     }
 
+    #[cfg(feature = "auto-install-tools")]
+    fn pkg_managers(&self) -> Vec<(PackageManager, PathBuf)> {
+        self.pkg_manager
+            .iter()
+            .chain(self.other_pkg_managers.iter())
+            .map(|pm| (*pm, PathBuf::from("")))
+            .collect()
+    }
+
     #[cfg(feature = "auto-install-tools")]
     fn aur_helper(&self) -> Option<(AurHelper, PathBuf)> {
         self.aur_helper.map(|pm| (pm, PathBuf::from("")))
     }
 
+    #[cfg(feature = "auto-install-tools")]
+    fn language_pkg_managers(&self) -> Vec<(LanguagePackageManager, PathBuf)> {
+        self.language_pkg_managers
+            .iter()
+            .map(|pm| (*pm, PathBuf::from("")))
+            .collect()
+    }
+
+    #[cfg(feature = "auto-install-tools")]
+    fn universal_pkg_managers(&self) -> Vec<(UniversalPackageManager, PathBuf)> {
+        self.universal_pkg_managers
+            .iter()
+            .map(|pm| (*pm, PathBuf::from("")))
+            .collect()
+    }
+
+    #[cfg(feature = "auto-install-tools")]
+    fn installed_packages(&self) -> Result<HashSet<String>> {
+        Ok(self.installed_packages.clone())
+    }
+
+    #[cfg(feature = "auto-install-tools")]
+    fn program_dirs(&self) -> Option<ProgramDirs> {
+        Some(self.program_dirs.clone())
+    }
+
     fn find_tool_executable(&self, tool: &ToolMetadata) -> Result<Option<PathBuf>> {
         Ok(self.installed_tools.get(&tool.command).map(|v| v.clone()))
     }
@@ -60,8 +153,22 @@ impl Environment for MockEnvironment {
     fn run_install_task(
         &self,
         task: &InstallTask,
+        _confirm: &mut dyn FnMut(&str, &str) -> Result<bool>,
         progress_handler: &mut dyn FnMut(InstallProgress),
     ) -> Result<()> {
+        self.run_tasks
+            .lock()
+            .unwrap()
+            .push(task.tool_name().to_string());
+
+        if let InstallTask::PackageManager { arguments, .. } = task
+            && let Some(package) = arguments
+                .iter()
+                .find(|v| self.failing_packages.contains(*v))
+        {
+            bail!("could not install {package}");
+        }
+
         let tool_name = task.tool_name().to_string();
         self.installed_tools
             .insert(tool_name.clone(), PathBuf::new());
@@ -74,6 +181,15 @@ impl Environment for MockEnvironment {
     }
 }
 
+impl MockEnvironment {
+    /// Gets the tool names of every install task that was run, in order.
+    #[cfg(feature = "auto-install-tools")]
+    #[must_use]
+    pub fn run_tasks(&self) -> Vec<String> {
+        self.run_tasks.lock().unwrap().clone()
+    }
+}
+
 impl<S: mock_environment_builder::State> MockEnvironmentBuilder<S> {
     pub fn installed_tools(
         self,
@@ -92,13 +208,21 @@ impl<S: mock_environment_builder::State> MockEnvironmentBuilder<S> {
 
 #[cfg(test)]
 mod tests {
-    use crate::env::{Environment, MockEnvironment};
+    use crate::env::{DiscoveredTool, DiscoverySource, Environment, MockEnvironment};
     use crate::registry::{ToolMetadata, Toolkit};
 
+    #[cfg(feature = "auto-install-tools")]
+    use crate::container::ContainerRuntime;
+    #[cfg(feature = "auto-install-tools")]
+    use crate::install::InstallTaskError;
     #[cfg(feature = "auto-install-tools")]
     use crate::install::{InstallPlanResult, InstallTask};
     #[cfg(feature = "auto-install-tools")]
-    use crate::pkg::{AurHelper, PackageManager};
+    use crate::pkg::{
+        AurHelper, Installer, LanguagePackageManager, PackageManager, UniversalPackageManager,
+    };
+    #[cfg(feature = "auto-install-tools")]
+    use crate::registry::{LibraryLanguage, ToolContainerMetadata, ToolLibraryMetadata, ToolType};
 
     use maplit::hashmap;
     use pretty_assertions::assert_eq;
@@ -191,6 +315,7 @@ mod tests {
                     .map(|s| s.to_string())
                     .collect::<Vec<_>>(),
                 sudo: true,
+                installer: Installer::System(PackageManager::Pacman),
                 tool_name: "tool".to_string(),
             })
         );
@@ -223,6 +348,7 @@ mod tests {
                     .map(|s| s.to_string())
                     .collect::<Vec<_>>(),
                 sudo: true,
+                installer: Installer::System(PackageManager::Pacman),
                 tool_name: "tool".to_string(),
             })
         );
@@ -241,6 +367,7 @@ mod tests {
                     .map(|s| s.to_string())
                     .collect::<Vec<_>>(),
                 sudo: true,
+                installer: Installer::System(PackageManager::APT),
                 tool_name: "tool".to_string(),
             })
         );
@@ -259,6 +386,7 @@ mod tests {
                     .map(|s| s.to_string())
                     .collect::<Vec<_>>(),
                 sudo: false,
+                installer: Installer::System(PackageManager::Chocolatey),
                 tool_name: "tool".to_string(),
             })
         );
@@ -290,6 +418,7 @@ mod tests {
                     .map(|s| s.to_string())
                     .collect::<Vec<_>>(),
                 sudo: false,
+                installer: Installer::Aur(AurHelper::Paru),
                 tool_name: "tool".to_string(),
             })
         );
@@ -318,8 +447,605 @@ mod tests {
                     .map(|s| s.to_string())
                     .collect::<Vec<_>>(),
                 sudo: true,
+                installer: Installer::System(PackageManager::Pacman),
                 tool_name: "tool".to_string(),
             })
         );
     }
+
+    #[cfg(feature = "auto-install-tools")]
+    #[test]
+    fn test_plan_install_library_tool() {
+        let tool = ToolMetadata::builder()
+            .name("pwntools".into())
+            .command("pwntools".into())
+            .kind(ToolType::Library)
+            .library(
+                ToolLibraryMetadata::builder()
+                    .language(LibraryLanguage::Python)
+                    .module("pwn".into())
+                    .build(),
+            )
+            .packages(hashmap! {
+                "pip".to_string() => "pwntools".to_string(),
+                "apt".to_string() => "python3-pwntools".to_string(),
+            })
+            .build();
+
+        // Test case: the library is installed with pip even if
+        // there's a system package manager.
+        let env = MockEnvironment::builder()
+            .pkg_manager(PackageManager::APT)
+            .language_pkg_managers(vec![
+                LanguagePackageManager::Gem,
+                LanguagePackageManager::Pip,
+            ])
+            .build();
+
+        assert_eq!(
+            env.plan_install_tool(&tool),
+            InstallPlanResult::Task(InstallTask::PackageManager {
+                exec: PathBuf::from(""),
+                arguments: ["install", "--user", "pwntools"]
+                    .iter()
+                    .map(|s| s.to_string())
+                    .collect::<Vec<_>>(),
+                sudo: false,
+                installer: Installer::Language(LanguagePackageManager::Pip),
+                tool_name: "pwntools".to_string(),
+            })
+        );
+
+        // Test case: fall back to the system package manager without pip.
+        // pipx cannot install libraries and Cargo is for other languages.
+        let env = MockEnvironment::builder()
+            .pkg_manager(PackageManager::APT)
+            .language_pkg_managers(vec![
+                LanguagePackageManager::Pipx,
+                LanguagePackageManager::Cargo,
+                LanguagePackageManager::Gem,
+            ])
+            .build();
+
+        assert_eq!(
+            env.plan_install_tool(&tool),
+            InstallPlanResult::Task(InstallTask::PackageManager {
+                exec: PathBuf::from(""),
+                arguments: ["install", "-y", "python3-pwntools"]
+                    .iter()
+                    .map(|s| s.to_string())
+                    .collect::<Vec<_>>(),
+                sudo: true,
+                installer: Installer::System(PackageManager::APT),
+                tool_name: "pwntools".to_string(),
+            })
+        );
+
+        // Test case: nothing can install the library
+        let env = MockEnvironment::builder().build();
+        assert_eq!(
+            env.plan_install_tool(&tool),
+            InstallPlanResult::CannotInstall(
+                &tool,
+                InstallTaskError::CannotInstallTool {
+                    tool_name: "pwntools".to_string()
+                }
+            )
+        );
+    }
+
+    #[cfg(feature = "auto-install-tools")]
+    #[test]
+    fn test_plan_install_container_tool() {
+        let tool = ToolMetadata::builder()
+            .name("sage".into())
+            .command("sage".into())
+            .kind(ToolType::Container)
+            .container(
+                ToolContainerMetadata::builder()
+                    .image("sagemath/sagemath:9.5".into())
+                    .build(),
+            )
+            .build();
+
+        let env = MockEnvironment::builder()
+            .container_runtime(ContainerRuntime::Podman)
+            .build();
+
+        assert_eq!(
+            env.plan_install_tool(&tool),
+            InstallPlanResult::Task(InstallTask::PackageManager {
+                exec: PathBuf::from(""),
+                arguments: vec!["pull".to_string(), "sagemath/sagemath:9.5".to_string()],
+                sudo: false,
+                installer: Installer::Container(ContainerRuntime::Podman),
+                tool_name: "sage".to_string(),
+            })
+        );
+
+        let env = MockEnvironment::builder()
+            .pkg_manager(PackageManager::APT)
+            .build();
+
+        assert_eq!(
+            env.plan_install_tool(&tool),
+            InstallPlanResult::CannotInstall(
+                &tool,
+                InstallTaskError::CannotInstallTool {
+                    tool_name: "sage".to_string()
+                }
+            )
+        );
+    }
+
+    #[cfg(feature = "auto-install-tools")]
+    #[test]
+    fn test_plan_install_tool_from_source() {
+        use crate::registry::{BuildSystem, ToolSourceMetadata};
+        use std::path::Path;
+
+        let source = ToolSourceMetadata::builder()
+            .git("https://github.com/RickdeJager/stegseek".into())
+            .build_system(BuildSystem::CMake)
+            .build();
+
+        let tool = ToolMetadata::builder()
+            .name("stegseek".into())
+            .command("stegseek".into())
+            .packages(hashmap! {
+                "pacman".into() => "stegseek".into(),
+            })
+            .source(source.clone())
+            .build();
+
+        // Packages are still preferred over building the tool from source.
+        let env = MockEnvironment::builder()
+            .pkg_manager(PackageManager::Pacman)
+            .build();
+
+        assert!(matches!(
+            env.plan_install_tool(&tool),
+            InstallPlanResult::Task(InstallTask::PackageManager { .. })
+        ));
+
+        let env = MockEnvironment::builder()
+            .pkg_manager(PackageManager::APT)
+            .build();
+
+        let InstallPlanResult::Task(InstallTask::Source {
+            source: planned_source,
+            cache_dir,
+            prefix,
+            tool_name,
+        }) = env.plan_install_tool(&tool)
+        else {
+            panic!("expected the tool to be built from source");
+        };
+
+        assert_eq!(planned_source, source);
+        assert_eq!(
+            cache_dir.parent(),
+            Some(Path::new("/home/ctf/.ctftools/cache/sources"))
+        );
+        assert_eq!(prefix, PathBuf::from("/home/ctf/.ctftools/data/prefix"));
+        assert_eq!(tool_name, "stegseek");
+    }
+
+    #[cfg(feature = "auto-install-tools")]
+    #[test]
+    fn test_plan_install_tool_requiring_checksums() {
+        use crate::config::{Config, DownloadConfig};
+        use crate::registry::{
+            DownloadFileFormat, ToolDownloadInstructions, ToolPlatformDownloads,
+        };
+
+        let download = |sha256: Option<String>| {
+            let instructions = ToolDownloadInstructions::builder()
+                .format(DownloadFileFormat::Binary)
+                .url("https://foo.local/tool".into())
+                .maybe_sha256(sha256)
+                .build();
+
+            ToolPlatformDownloads::builder()
+                .windows(instructions.clone())
+                .macos(instructions.clone())
+                .linux(instructions)
+                .build()
+        };
+
+        let unverified = ToolMetadata::builder()
+            .name("foo".into())
+            .command("foo".into())
+            .downloads(download(None))
+            .build();
+
+        let verified = ToolMetadata::builder()
+            .name("foo".into())
+            .command("foo".into())
+            .downloads(download(Some("0".repeat(64))))
+            .build();
+
+        // Unverified downloads are allowed by default.
+        let env = MockEnvironment::builder().build();
+        assert!(matches!(
+            env.plan_install_tool(&unverified),
+            InstallPlanResult::Task(InstallTask::Download { .. })
+        ));
+
+        let env = MockEnvironment::builder()
+            .config(Config {
+                downloads: DownloadConfig {
+                    require_checksums: true,
+                    ..DownloadConfig::default()
+                },
+                ..Config::default()
+            })
+            .build();
+
+        assert_eq!(
+            env.plan_install_tool(&unverified),
+            InstallPlanResult::CannotInstall(
+                &unverified,
+                InstallTaskError::UnverifiedDownload {
+                    tool_name: "foo".into()
+                }
+            )
+        );
+        assert!(matches!(
+            env.plan_install_tool(&verified),
+            InstallPlanResult::Task(InstallTask::Download { .. })
+        ));
+    }
+
+    #[test]
+    fn test_discover_tools() {
+        let catalog = Toolkit::new(vec![
+            ToolMetadata::builder()
+                .name("foo".into())
+                .command("foo".into())
+                .build(),
+            ToolMetadata::builder()
+                .name("gdb".into())
+                .command("gdb".into())
+                .build(),
+            ToolMetadata::builder()
+                .name("nmap".into())
+                .command("nmap".into())
+                .build(),
+        ]);
+
+        let env = MockEnvironment::builder()
+            .installed_tools(hashmap! {
+                "foo".to_string() => PathBuf::from("/usr/bin/foo"),
+                "gdb".to_string() => PathBuf::from("/usr/bin/gdb"),
+            })
+            .build();
+
+        // `foo` is already in the toolkit and `nmap` is not installed.
+        let discovered = env.discover_tools(&catalog, &SAMPLE_TOOLKIT).unwrap();
+        assert_eq!(
+            discovered,
+            [DiscoveredTool {
+                tool: &catalog.tools()[1],
+                source: DiscoverySource::Executable(PathBuf::from("/usr/bin/gdb")),
+            }]
+        );
+    }
+
+    #[cfg(feature = "auto-install-tools")]
+    #[test]
+    fn test_discover_tools_from_installed_packages() {
+        let catalog = Toolkit::new(vec![
+            ToolMetadata::builder()
+                .name("TShark".into())
+                .command("tshark".into())
+                .packages(hashmap! {
+                    "apt".to_string() => "tshark".to_string(),
+                    "pacman".to_string() => "wireshark-cli".to_string(),
+                })
+                .build(),
+        ]);
+
+        let env = MockEnvironment::builder()
+            .pkg_manager(PackageManager::Pacman)
+            .installed_packages(["wireshark-cli".to_string()].into())
+            .build();
+
+        let discovered = env.discover_tools(&catalog, &SAMPLE_TOOLKIT).unwrap();
+        assert_eq!(
+            discovered,
+            [DiscoveredTool {
+                tool: &catalog.tools()[0],
+                source: DiscoverySource::Package("wireshark-cli".to_string()),
+            }]
+        );
+
+        // Packages of other package managers do not count.
+        let env = MockEnvironment::builder()
+            .pkg_manager(PackageManager::DNF)
+            .installed_packages(["tshark".to_string()].into())
+            .build();
+
+        assert!(
+            env.discover_tools(&catalog, &SAMPLE_TOOLKIT)
+                .unwrap()
+                .is_empty()
+        );
+    }
+
+    #[cfg(feature = "auto-install-tools")]
+    #[test]
+    fn test_plan_install_from_language_ecosystems() {
+        let args = |args: &[&str]| args.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+        let tool = |command: &str, packages| {
+            ToolMetadata::builder()
+                .name(command.into())
+                .command(command.into())
+                .packages(packages)
+                .build()
+        };
+
+        let rustscan = tool(
+            "rustscan",
+            hashmap! { "cargo".to_string() => "rustscan".to_string() },
+        );
+        let ffuf = tool(
+            "ffuf",
+            hashmap! {
+                "pacman".to_string() => "ffuf".to_string(),
+                "go".to_string() => "github.com/ffuf/ffuf/v2".to_string(),
+            },
+        );
+        let ropgadget = tool(
+            "ROPgadget",
+            hashmap! { "pip".to_string() => "ropgadget".to_string() },
+        );
+
+        let env = MockEnvironment::builder()
+            .pkg_manager(PackageManager::APT)
+            .language_pkg_managers(vec![
+                LanguagePackageManager::Pipx,
+                LanguagePackageManager::Pip,
+                LanguagePackageManager::Cargo,
+                LanguagePackageManager::Go,
+            ])
+            .build();
+
+        // Tools that the system package manager cannot provide
+        // are installed with their language package manager.
+        assert_eq!(
+            env.plan_install_tool(&rustscan),
+            InstallPlanResult::Task(InstallTask::PackageManager {
+                exec: PathBuf::from(""),
+                arguments: args(&["install", "rustscan"]),
+                sudo: false,
+                installer: Installer::Language(LanguagePackageManager::Cargo),
+                tool_name: "rustscan".to_string(),
+            })
+        );
+        assert_eq!(
+            env.plan_install_tool(&ffuf),
+            InstallPlanResult::Task(InstallTask::PackageManager {
+                exec: PathBuf::from(""),
+                arguments: args(&["install", "github.com/ffuf/ffuf/v2@latest"]),
+                sudo: false,
+                installer: Installer::Language(LanguagePackageManager::Go),
+                tool_name: "ffuf".to_string(),
+            })
+        );
+
+        // pipx is preferred for Python applications.
+        assert_eq!(
+            env.plan_install_tool(&ropgadget),
+            InstallPlanResult::Task(InstallTask::PackageManager {
+                exec: PathBuf::from(""),
+                arguments: args(&["install", "ropgadget"]),
+                sudo: false,
+                installer: Installer::Language(LanguagePackageManager::Pipx),
+                tool_name: "ROPgadget".to_string(),
+            })
+        );
+
+        // The system package manager is still preferred if it has the tool.
+        let env = MockEnvironment::builder()
+            .pkg_manager(PackageManager::Pacman)
+            .language_pkg_managers(vec![LanguagePackageManager::Go])
+            .build();
+
+        assert_eq!(
+            env.plan_install_tool(&ffuf),
+            InstallPlanResult::Task(InstallTask::PackageManager {
+                exec: PathBuf::from(""),
+                arguments: args(&["-S", "--noconfirm", "ffuf"]),
+                sudo: true,
+                installer: Installer::System(PackageManager::Pacman),
+                tool_name: "ffuf".to_string(),
+            })
+        );
+    }
+
+    #[cfg(feature = "auto-install-tools")]
+    #[test]
+    fn test_plan_install_from_universal_pkg_managers() {
+        let args = |args: &[&str]| args.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+        let wireshark = ToolMetadata::builder()
+            .name("Wireshark".into())
+            .command("wireshark".into())
+            .packages(hashmap! {
+                "pacman".to_string() => "wireshark-qt".to_string(),
+                "flatpak".to_string() => "org.wireshark.Wireshark".to_string(),
+                "snap".to_string() => "wireshark".to_string(),
+                "pip".to_string() => "wireshark".to_string(),
+            })
+            .build();
+
+        // They are preferred over language package managers, in the order
+        // that they are detected.
+        let env = MockEnvironment::builder()
+            .pkg_manager(PackageManager::APT)
+            .universal_pkg_managers(vec![
+                UniversalPackageManager::Nix,
+                UniversalPackageManager::Flatpak,
+                UniversalPackageManager::Snap,
+            ])
+            .language_pkg_managers(vec![LanguagePackageManager::Pip])
+            .build();
+
+        assert_eq!(
+            env.plan_install_tool(&wireshark),
+            InstallPlanResult::Task(InstallTask::PackageManager {
+                exec: PathBuf::from(""),
+                arguments: args(&[
+                    "install",
+                    "--user",
+                    "--noninteractive",
+                    "flathub",
+                    "org.wireshark.Wireshark"
+                ]),
+                sudo: false,
+                installer: Installer::Universal(UniversalPackageManager::Flatpak),
+                tool_name: "Wireshark".to_string(),
+            })
+        );
+
+        // Snaps can only be installed for the whole system.
+        let env = MockEnvironment::builder()
+            .universal_pkg_managers(vec![UniversalPackageManager::Snap])
+            .build();
+
+        assert_eq!(
+            env.plan_install_tool(&wireshark),
+            InstallPlanResult::Task(InstallTask::PackageManager {
+                exec: PathBuf::from(""),
+                arguments: args(&["install", "wireshark"]),
+                sudo: true,
+                installer: Installer::Universal(UniversalPackageManager::Snap),
+                tool_name: "Wireshark".to_string(),
+            })
+        );
+
+        // The system package manager is still preferred if it has the tool.
+        let env = MockEnvironment::builder()
+            .pkg_manager(PackageManager::Pacman)
+            .universal_pkg_managers(vec![UniversalPackageManager::Flatpak])
+            .build();
+
+        assert_eq!(
+            env.plan_install_tool(&wireshark),
+            InstallPlanResult::Task(InstallTask::PackageManager {
+                exec: PathBuf::from(""),
+                arguments: args(&["-S", "--noconfirm", "wireshark-qt"]),
+                sudo: true,
+                installer: Installer::System(PackageManager::Pacman),
+                tool_name: "Wireshark".to_string(),
+            })
+        );
+    }
+
+    #[cfg(feature = "auto-install-tools")]
+    #[test]
+    fn test_plan_install_by_installer_priority() {
+        use crate::config::Config;
+
+        let installer =
+            |env: &MockEnvironment, tool: &ToolMetadata| match env.plan_install_tool(tool) {
+                InstallPlanResult::Task(InstallTask::PackageManager { installer, .. }) => {
+                    Some(installer)
+                }
+                _ => None,
+            };
+        let config = |installers: &[&str]| Config {
+            installers: installers.iter().map(|v| v.to_string()).collect(),
+            ..Config::default()
+        };
+
+        let wireshark = ToolMetadata::builder()
+            .name("Wireshark".into())
+            .command("wireshark".into())
+            .packages(hashmap! {
+                "dnf".to_string() => "wireshark".to_string(),
+                "flatpak".to_string() => "org.wireshark.Wireshark".to_string(),
+                "homebrew".to_string() => "wireshark".to_string(),
+            })
+            .build();
+
+        // A Fedora system with Flatpak and Homebrew installed
+        // prefers DNF unless the user says otherwise.
+        let fedora = |config: Config| {
+            MockEnvironment::builder()
+                .pkg_manager(PackageManager::DNF)
+                .universal_pkg_managers(vec![
+                    UniversalPackageManager::Flatpak,
+                    UniversalPackageManager::Linuxbrew,
+                ])
+                .config(config)
+                .build()
+        };
+
+        let env = fedora(Config::default());
+        assert_eq!(
+            installer(&env, &wireshark),
+            Some(Installer::System(PackageManager::DNF))
+        );
+
+        let env = fedora(config(&["linuxbrew", "flatpak"]));
+        assert_eq!(
+            installer(&env, &wireshark),
+            Some(Installer::Universal(UniversalPackageManager::Linuxbrew))
+        );
+
+        // Installers without a package for the tool are skipped.
+        let env = fedora(config(&["nix", "flatpak"]));
+        assert_eq!(
+            installer(&env, &wireshark),
+            Some(Installer::Universal(UniversalPackageManager::Flatpak))
+        );
+
+        // Every system package manager can be used, not only the preferred one.
+        let nmap = ToolMetadata::builder()
+            .name("Nmap".into())
+            .command("nmap".into())
+            .packages(hashmap! {
+                "winget".to_string() => "Insecure.Nmap".to_string(),
+            })
+            .build();
+
+        let env = MockEnvironment::builder()
+            .pkg_manager(PackageManager::Chocolatey)
+            .other_pkg_managers(vec![PackageManager::WinGet])
+            .build();
+
+        assert_eq!(
+            installer(&env, &nmap),
+            Some(Installer::System(PackageManager::WinGet))
+        );
+
+        // Libraries still prefer the package manager of their language.
+        let pwntools = ToolMetadata::builder()
+            .name("pwntools".into())
+            .command("pwntools".into())
+            .kind(ToolType::Library)
+            .library(
+                ToolLibraryMetadata::builder()
+                    .language(LibraryLanguage::Python)
+                    .module("pwn".into())
+                    .build(),
+            )
+            .packages(hashmap! {
+                "pip".to_string() => "pwntools".to_string(),
+                "apt".to_string() => "python3-pwntools".to_string(),
+            })
+            .build();
+
+        let env = MockEnvironment::builder()
+            .pkg_manager(PackageManager::APT)
+            .language_pkg_managers(vec![LanguagePackageManager::Pip])
+            .config(config(&["apt"]))
+            .build();
+
+        assert_eq!(
+            installer(&env, &pwntools),
+            Some(Installer::Language(LanguagePackageManager::Pip))
+        );
+    }
 }
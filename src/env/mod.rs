@@ -1,14 +1,24 @@
 use anyhow::Result;
 use std::path::PathBuf;
 
+#[cfg(feature = "auto-install-tools")]
+use crate::install::resolve_package;
 #[cfg(feature = "auto-install-tools")]
 use crate::install::{InstallPlanResult, InstallProgress, InstallTask, InstallTaskError};
 #[cfg(feature = "auto-install-tools")]
-use crate::pkg::{AurHelper, PackageManager};
+use crate::pkg::{
+    AurHelper, Installer, LanguagePackageManager, PackageManager, UniversalPackageManager,
+};
 #[cfg(feature = "auto-install-tools")]
 use log::debug;
+#[cfg(feature = "auto-install-tools")]
+use std::collections::HashSet;
 
+use crate::config::Config;
+use crate::container::ContainerRuntime;
 use crate::registry::{ToolMetadata, ToolType, Toolkit};
+#[cfg(feature = "auto-install-tools")]
+use crate::util::ProgramDirs;
 
 pub mod live;
 pub mod mock;
@@ -16,6 +26,26 @@ pub mod mock;
 pub use self::live::LiveEnvironment;
 pub use self::mock::MockEnvironment;
 
+/// A tool from a catalog that is found in the environment.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DiscoveredTool<'c> {
+    pub tool: &'c ToolMetadata,
+    pub source: DiscoverySource,
+}
+
+/// Describes how a [discovered tool](DiscoveredTool) is found.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DiscoverySource {
+    /// The tool's executable (or the interpreter that can import
+    /// a library tool) is found at this path.
+    Executable(PathBuf),
+
+    /// The package that provides the tool is installed with
+    /// the environment's package manager.
+    #[cfg(feature = "auto-install-tools")]
+    Package(String),
+}
+
 /// Defines an abstraction over the host system's environment.
 ///
 /// This trait allows for querying system properties like available package managers
@@ -38,6 +68,14 @@ pub trait Environment: std::fmt::Debug {
     #[must_use]
     fn supports_privilege_escalation(&self) -> bool;
 
+    /// Gets the home directory of the current user, if there is any.
+    #[must_use]
+    fn home_dir(&self) -> Option<PathBuf>;
+
+    /// Gets the user's [configuration](Config) of ctftools.
+    #[must_use]
+    fn config(&self) -> &Config;
+
     /// Gets the current [package manager] along with its binary path of the environment.
     ///
     /// [package manager]: PackageManager
@@ -45,6 +83,16 @@ pub trait Environment: std::fmt::Debug {
     #[must_use]
     fn pkg_manager(&self) -> Option<(PackageManager, PathBuf)>;
 
+    /// Gets every available [package manager] along with its binary path
+    /// of the environment, starting with the [current one](Self::pkg_manager).
+    ///
+    /// [package manager]: PackageManager
+    #[cfg(feature = "auto-install-tools")]
+    #[must_use]
+    fn pkg_managers(&self) -> Vec<(PackageManager, PathBuf)> {
+        self.pkg_manager().into_iter().collect()
+    }
+
     /// Gets the current [AUR helper] along with its binary path of the environment.
     ///
     /// This is only relevant on Arch Linux systems.
@@ -54,6 +102,66 @@ pub trait Environment: std::fmt::Debug {
     #[must_use]
     fn aur_helper(&self) -> Option<(AurHelper, PathBuf)>;
 
+    /// Gets the current [container runtime] along with its binary path of the environment.
+    ///
+    /// [container runtime]: ContainerRuntime
+    #[must_use]
+    fn container_runtime(&self) -> Option<(ContainerRuntime, PathBuf)>;
+
+    /// Gets every available [language package manager] along with
+    /// its binary path of the environment.
+    ///
+    /// [language package manager]: LanguagePackageManager
+    #[cfg(feature = "auto-install-tools")]
+    #[must_use]
+    fn language_pkg_managers(&self) -> Vec<(LanguagePackageManager, PathBuf)>;
+
+    /// Gets every available [universal package manager] along with
+    /// its binary path of the environment.
+    ///
+    /// [universal package manager]: UniversalPackageManager
+    #[cfg(feature = "auto-install-tools")]
+    #[must_use]
+    fn universal_pkg_managers(&self) -> Vec<(UniversalPackageManager, PathBuf)>;
+
+    /// Gets every available [installer] along with its binary path
+    /// of the environment, in the order that they are tried to
+    /// install a tool with.
+    ///
+    /// System package managers come first, then universal package
+    /// managers and language package managers. The installers listed
+    /// in the user's [configuration](Config::installers) are moved
+    /// before the others.
+    ///
+    /// [installer]: Installer
+    #[cfg(feature = "auto-install-tools")]
+    #[must_use]
+    fn installers(&self) -> Vec<(Installer, PathBuf)> {
+        let system = self.pkg_managers().into_iter();
+        let universal = self.universal_pkg_managers().into_iter();
+        let language = self.language_pkg_managers().into_iter();
+
+        let mut installers = system
+            .map(|(pm, path)| (Installer::System(pm), path))
+            .chain(universal.map(|(pm, path)| (Installer::Universal(pm), path)))
+            .chain(language.map(|(pm, path)| (Installer::Language(pm), path)))
+            .collect::<Vec<_>>();
+
+        Installer::sort_by_priority(&mut installers, &self.config().installers);
+        installers
+    }
+
+    /// Lists the names of every package installed with
+    /// the environment's [package manager](PackageManager).
+    #[cfg(feature = "auto-install-tools")]
+    fn installed_packages(&self) -> Result<HashSet<String>>;
+
+    /// Gets the directories where ctftools stores its own files,
+    /// such as the tools built from source.
+    #[cfg(feature = "auto-install-tools")]
+    #[must_use]
+    fn program_dirs(&self) -> Option<ProgramDirs>;
+
     /// Checks which tools in a [`Toolkit`] are installed in the environment.
     ///
     /// It returns a vector of tuples, where each tuple contains:
@@ -69,7 +177,7 @@ pub trait Environment: std::fmt::Debug {
         let iter = toolkit
             .tools()
             .iter()
-            .filter(|v| matches!(v.kind, ToolType::Executable));
+            .filter(|v| !matches!(v.kind, ToolType::Website));
 
         iter.map(|tool| {
             let installed = self.find_tool_executable(tool)?.is_some();
@@ -78,11 +186,60 @@ pub trait Environment: std::fmt::Debug {
         .collect()
     }
 
+    /// Finds tools from a `catalog` that are installed in the environment
+    /// but are not part of the given `toolkit` yet.
+    ///
+    /// A tool is discovered if its executable can be found, or if the
+    /// package that provides it is already installed with the
+    /// environment's package manager.
+    fn discover_tools<'c>(
+        &self,
+        catalog: &'c Toolkit,
+        toolkit: &Toolkit,
+    ) -> Result<Vec<DiscoveredTool<'c>>> {
+        #[cfg(feature = "auto-install-tools")]
+        let installed_packages = match self.pkg_manager() {
+            Some((pkg_manager, _)) => Some((pkg_manager, self.installed_packages()?)),
+            None => None,
+        };
+
+        let mut discovered = Vec::new();
+        for tool in catalog.tools() {
+            if tool.kind == ToolType::Website || toolkit.find_tool(&tool.command).is_some() {
+                continue;
+            }
+
+            if let Some(path) = self.find_tool_executable(tool)? {
+                discovered.push(DiscoveredTool {
+                    tool,
+                    source: DiscoverySource::Executable(path),
+                });
+                continue;
+            }
+
+            #[cfg(feature = "auto-install-tools")]
+            if let Some((pkg_manager, packages)) = installed_packages.as_ref()
+                && let Some((package, _)) = resolve_package(*pkg_manager, tool)
+                && packages.contains(package)
+            {
+                discovered.push(DiscoveredTool {
+                    tool,
+                    source: DiscoverySource::Package(package.to_string()),
+                });
+            }
+        }
+        Ok(discovered)
+    }
+
     /// Attempts to locate the executable for a specific tool.
     ///
     /// Lookup strategies may differ depending on the true value
     /// based on [environment] trait.
     ///
+    /// For [library tools](ToolType::Library), this returns the interpreter
+    /// that can import the library. For [container tools](ToolType::Container),
+    /// this returns the container runtime if the image exists locally.
+    ///
     /// Implementations of this method define the strategy for finding a tool,
     /// such as checking the system's `PATH` or other well-known locations.
     fn find_tool_executable(&self, tool: &ToolMetadata) -> Result<Option<PathBuf>>;
@@ -127,53 +284,163 @@ pub trait Environment: std::fmt::Debug {
     /// Creates an installation plan for a single tool.
     ///
     /// This is the core planning logic, which attempts to create an [`InstallTask`]
-    /// by first checking for a package manager and then falling back to direct
-    /// downloads if necessary.
+    /// with the first of the [installers](Self::installers) that provides the
+    /// tool and then falling back to direct downloads if necessary. The chosen
+    /// installer is recorded in the task.
+    ///
+    /// Library tools are installed with the package manager of their
+    /// language (e.g. pip for Python) whenever possible, otherwise only with
+    /// the system package manager. Container tools are installed by pulling
+    /// their image.
+    ///
+    /// Building the tool from source is the last resort.
     #[cfg(feature = "auto-install-tools")]
     fn plan_install_tool<'t>(&self, tool: &'t ToolMetadata) -> InstallPlanResult<'t> {
-        if let Some((pkg_manager, path_to_pkgm)) = self.pkg_manager().clone() {
-            match InstallTask::from_package_manager(pkg_manager, path_to_pkgm, tool) {
-                Ok(mut task) => {
-                    // If it's an AUR task, try to refine it with the AUR helper.
-                    if pkg_manager == PackageManager::Pacman
-                        && matches!(task, InstallTask::AUR { .. })
-                        && let Some((aur_helper, path_to_arh)) = self.aur_helper().clone()
-                        && let InstallTask::AUR {
-                            package_name,
-                            tool_name,
-                        } = task
-                    {
-                        task =
-                            InstallTask::from_aur(aur_helper, path_to_arh, package_name, tool_name);
-                    }
-                    return InstallPlanResult::Task(task);
+        if tool.kind == ToolType::Container {
+            let Some(runtime) = self.container_runtime() else {
+                return InstallPlanResult::CannotInstall(
+                    tool,
+                    InstallTaskError::CannotInstallTool {
+                        tool_name: tool.name.clone(),
+                    },
+                );
+            };
+
+            let (runtime, path_to_runtime) = runtime;
+            return match InstallTask::from_container_runtime(runtime, path_to_runtime, tool) {
+                Ok(task) => InstallPlanResult::Task(task),
+                Err(e) => InstallPlanResult::CannotInstall(tool, e),
+            };
+        }
+
+        let library_language = tool
+            .library
+            .as_ref()
+            .filter(|_| tool.kind == ToolType::Library)
+            .map(|v| v.language);
+
+        let home = self.home_dir();
+        let mut installers = self.installers();
+        if home.is_none() {
+            debug!("could not find the home directory; skipping language package managers");
+            installers.retain(|(installer, _)| !matches!(installer, Installer::Language(..)));
+        }
+
+        // Libraries can only be installed with the package manager
+        // of their language so it must be preferred over the others.
+        if let Some(language) = library_language {
+            installers.sort_by_key(|(installer, _)| {
+                !matches!(installer, Installer::Language(pm) if pm.language() == Some(language))
+            });
+        }
+
+        let scope = self.config().universal_packages.scope;
+        for (installer, path) in installers {
+            let result = match installer {
+                Installer::System(pkg_manager) => {
+                    plan_with_pkg_manager(self, pkg_manager, path, tool)
+                }
+                Installer::Universal(..) if library_language.is_some() => continue,
+                Installer::Universal(pkg_manager) => {
+                    InstallTask::from_universal_pkg_manager(pkg_manager, path, scope, tool)
+                }
+                Installer::Language(pkg_manager)
+                    if library_language.is_some() && pkg_manager.language() != library_language =>
+                {
+                    continue;
                 }
-                Err(e @ InstallTaskError::PackageNotFound { .. }) => {
-                    // This isn't a fatal error; we can try other methods.
+                Installer::Language(pkg_manager) => match home.as_deref() {
+                    Some(home) => {
+                        InstallTask::from_language_pkg_manager(pkg_manager, path, home, tool)
+                    }
+                    None => continue,
+                },
+                Installer::Aur(..) | Installer::Container(..) => continue,
+            };
+
+            match result {
+                Ok(task) => {
                     debug!(
-                        "package not found for {}: {e}, trying downloads.",
+                        "chose {} to install {}",
+                        installer.as_display_name(),
                         tool.name
                     );
+                    return InstallPlanResult::Task(task);
                 }
-                Err(e) => return InstallPlanResult::CannotInstall(tool, e),
-            };
+                // This isn't a fatal error; we can try other methods.
+                Err(e) => debug!("{e}; trying other methods."),
+            }
         }
 
-        // Fallback to downloads
-        match InstallTask::from_downloads(tool) {
+        // Fallback to downloads, then to building the tool from source.
+        let require_checksums = self.config().downloads.require_checksums;
+        let result = InstallTask::from_downloads(tool)
+            .and_then(|task| match &task {
+                InstallTask::Download { instructions, .. }
+                    if require_checksums && !instructions.is_verifiable() =>
+                {
+                    Err(InstallTaskError::UnverifiedDownload {
+                        tool_name: tool.name.clone(),
+                    })
+                }
+                _ => Ok(task),
+            })
+            .or_else(|e| match self.program_dirs() {
+                Some(dirs) if tool.source.is_some() => InstallTask::from_source(&dirs, tool),
+                _ => Err(e),
+            });
+
+        match result {
             Ok(task) => InstallPlanResult::Task(task),
             Err(e) => InstallPlanResult::CannotInstall(tool, e),
         }
     }
 
+    /// Runs an install task while reporting its progress to `progress_handler`.
+    ///
+    /// `confirm` is called with the name of a build script (e.g. `PKGBUILD`)
+    /// and the tool name once the script is [reviewed], and the script is
+    /// only run if it returns `true`.
+    ///
+    /// [reviewed]: InstallProgress::BuildScript
     #[cfg(feature = "auto-install-tools")]
     fn run_install_task(
         &self,
         task: &InstallTask,
+        confirm: &mut dyn FnMut(&str, &str) -> Result<bool>,
         progress_handler: &mut dyn FnMut(InstallProgress),
     ) -> Result<()>;
 }
 
+/// Plans to install a tool with the system's package manager.
+///
+/// AUR packages are installed with the environment's
+/// AUR helper if there is any.
+#[cfg(feature = "auto-install-tools")]
+fn plan_with_pkg_manager<E: Environment + ?Sized>(
+    env: &E,
+    pkg_manager: PackageManager,
+    path_to_pkgm: PathBuf,
+    tool: &ToolMetadata,
+) -> Result<InstallTask, InstallTaskError> {
+    let task = InstallTask::from_package_manager(pkg_manager, path_to_pkgm, tool)?;
+    if pkg_manager == PackageManager::Pacman
+        && let Some((aur_helper, path_to_arh)) = env.aur_helper()
+        && let InstallTask::AUR {
+            package_name,
+            tool_name,
+        } = task
+    {
+        return Ok(InstallTask::from_aur(
+            aur_helper,
+            path_to_arh,
+            package_name,
+            tool_name,
+        ));
+    }
+    Ok(task)
+}
+
 #[cfg(test)]
 mod tests {
     use crate::env::Environment;
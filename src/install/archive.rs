@@ -0,0 +1,424 @@
+use anyhow::{Context, Result, bail};
+use log::debug;
+use std::collections::VecDeque;
+use std::fs::File;
+use std::io::{BufReader, BufWriter, Read};
+use std::path::{Path, PathBuf};
+use tempdir::TempDir;
+
+use crate::registry::{DownloadFileFormat, is_valid_command};
+
+/// Gets the directory inside `tools_dir` where the tool with the given
+/// command is installed into.
+///
+/// The directory is replaced whenever the tool is installed again, so
+/// the command must be a [valid command](is_valid_command).
+pub fn tool_dir(tools_dir: &Path, command: &str) -> Result<PathBuf> {
+    if !is_valid_command(command) {
+        bail!("{command:?} cannot be used as the directory of a tool");
+    }
+    Ok(tools_dir.join(command))
+}
+
+/// Extracts a downloaded archive into `dest`, replacing
+/// whatever was extracted there before.
+///
+/// `dest` must be the directory of a tool directly inside `tools_dir`.
+pub fn extract_archive(
+    path: &Path,
+    format: &DownloadFileFormat,
+    tools_dir: &Path,
+    dest: &Path,
+) -> Result<()> {
+    let is_tool_dir = dest.parent() == Some(tools_dir)
+        && dest
+            .file_name()
+            .and_then(|v| v.to_str())
+            .is_some_and(is_valid_command);
+
+    if !is_tool_dir {
+        bail!(
+            "refusing to extract into {} since it is not a tool directory in {}",
+            dest.display(),
+            tools_dir.display()
+        );
+    }
+
+    if dest.exists() {
+        std::fs::remove_dir_all(dest)
+            .with_context(|| format!("could not remove {}", dest.display()))?;
+    }
+    std::fs::create_dir_all(dest)
+        .with_context(|| format!("could not create {}", dest.display()))?;
+
+    debug!("extracting {} into {}", path.display(), dest.display());
+    let file = File::open(path).with_context(|| format!("could not open {}", path.display()))?;
+    let reader = BufReader::new(file);
+
+    match format {
+        DownloadFileFormat::ZIP => {
+            let mut archive = zip::ZipArchive::new(reader).context("invalid zip archive")?;
+            archive
+                .extract(dest)
+                .context("could not extract zip archive")?;
+        }
+        DownloadFileFormat::TarGz => unpack_tar(flate2::bufread::GzDecoder::new(reader), dest)?,
+        DownloadFileFormat::TarXz => {
+            // The xz decoder cannot be read from, so the tar archive is
+            // decompressed into a temporary file first. It must not be put
+            // next to the downloaded file as it may be in the download cache.
+            let temp_dir = TempDir::new("ctftools_extract")?;
+            let tar_path = temp_dir.path().join("archive.tar");
+            let mut tar_file = BufWriter::new(
+                File::create(&tar_path)
+                    .with_context(|| format!("could not create {}", tar_path.display()))?,
+            );
+            lzma_rs::xz_decompress(&mut { reader }, &mut tar_file)
+                .map_err(|error| anyhow::anyhow!("invalid xz archive: {error}"))?;
+            drop(tar_file);
+
+            let tar_file = File::open(&tar_path)
+                .with_context(|| format!("could not open {}", tar_path.display()))?;
+            unpack_tar(BufReader::new(tar_file), dest)?;
+            temp_dir.close()?;
+        }
+        DownloadFileFormat::TarZst => {
+            let decoder = ruzstd::decoding::StreamingDecoder::new(reader)
+                .map_err(|error| anyhow::anyhow!("invalid zstd archive: {error}"))?;
+            unpack_tar(decoder, dest)?;
+        }
+        DownloadFileFormat::Executable
+        | DownloadFileFormat::AppImage
+        | DownloadFileFormat::Binary
+        | DownloadFileFormat::Deb
+        | DownloadFileFormat::Rpm => bail!("{} is not an archive", path.display()),
+    }
+
+    Ok(())
+}
+
+fn unpack_tar<R: Read>(reader: R, dest: &Path) -> Result<()> {
+    let mut archive = tar::Archive::new(reader);
+    archive.set_preserve_permissions(true);
+    archive
+        .unpack(dest)
+        .context("could not extract tar archive")
+}
+
+/// Finds the executables to expose from an extracted archive at `root`.
+///
+/// Each of the `bin` paths is relative to the root of the archive, or to
+/// its only top-level directory. If `bin` is empty, it looks for the
+/// shallowest file named after the tool's `command` instead.
+pub fn find_entrypoints(root: &Path, bin: &[String], command: &str) -> Result<Vec<PathBuf>> {
+    if bin.is_empty() {
+        return match find_file_named(root, command)? {
+            Some(path) => Ok(vec![path]),
+            None => bail!("could not find {command:?} in the extracted archive"),
+        };
+    }
+
+    // Archives usually put everything in a directory named after
+    // the release (e.g. `tool-1.0/bin/tool`).
+    let mut entries = std::fs::read_dir(root)
+        .with_context(|| format!("could not read {}", root.display()))?
+        .collect::<std::io::Result<Vec<_>>>()?;
+
+    let nested_root = match entries.pop() {
+        Some(entry) if entries.is_empty() && entry.file_type()?.is_dir() => Some(entry.path()),
+        _ => None,
+    };
+
+    bin.iter()
+        .map(|bin| {
+            let candidates = [
+                Some(root.join(bin)),
+                nested_root.as_ref().map(|v| v.join(bin)),
+            ];
+            candidates
+                .into_iter()
+                .flatten()
+                .find(|path| is_file_inside(root, path))
+                .with_context(|| format!("could not find {bin:?} in the extracted archive"))
+        })
+        .collect()
+}
+
+/// Checks whether `path` is a regular file rather than a symbolic link,
+/// so an archive cannot expose files from outside of it with one.
+fn is_regular_file(path: &Path) -> bool {
+    std::fs::symlink_metadata(path).is_ok_and(|v| v.is_file())
+}
+
+/// Checks whether `path` is a regular file that stays inside `root`
+/// even if one of its parent directories is a symbolic link.
+fn is_file_inside(root: &Path, path: &Path) -> bool {
+    let (Ok(root), Ok(canonical)) = (root.canonicalize(), path.canonicalize()) else {
+        return false;
+    };
+    is_regular_file(path) && canonical.starts_with(root)
+}
+
+/// Looks for the shallowest file named after the command,
+/// including its `.exe` variant in Windows.
+fn find_file_named(root: &Path, command: &str) -> Result<Option<PathBuf>> {
+    let names = if cfg!(windows) {
+        vec![command.to_string(), format!("{command}.exe")]
+    } else {
+        vec![command.to_string()]
+    };
+
+    let mut queue = VecDeque::from([root.to_path_buf()]);
+    while let Some(dir) = queue.pop_front() {
+        let mut entries = std::fs::read_dir(&dir)
+            .with_context(|| format!("could not read {}", dir.display()))?
+            .collect::<std::io::Result<Vec<_>>>()?;
+
+        entries.sort_by_key(|v| v.file_name());
+        for entry in entries {
+            let file_type = entry.file_type()?;
+            if file_type.is_dir() {
+                queue.push_back(entry.path());
+            } else if file_type.is_file() && names.iter().any(|v| entry.file_name() == v.as_str()) {
+                return Ok(Some(entry.path()));
+            }
+        }
+    }
+
+    Ok(None)
+}
+
+/// Makes an extracted file executable and exposes it in `bin_dir`
+/// as `name` so it can be found like any other installed tool.
+///
+/// In Windows, a `.cmd` script that runs the file is created
+/// instead since creating symbolic links needs extra privileges.
+pub fn expose_executable(path: &Path, bin_dir: &Path, name: &str) -> Result<PathBuf> {
+    if !is_regular_file(path) {
+        bail!("{} is not a regular file", path.display());
+    }
+
+    std::fs::create_dir_all(bin_dir)
+        .with_context(|| format!("could not create {}", bin_dir.display()))?;
+
+    cfg_if::cfg_if! {
+        if #[cfg(unix)] {
+            use std::os::unix::fs::PermissionsExt;
+
+            let mut permissions = std::fs::symlink_metadata(path)?.permissions();
+            permissions.set_mode(permissions.mode() | 0o755);
+            std::fs::set_permissions(path, permissions)
+                .with_context(|| format!("could not make {} executable", path.display()))?;
+
+            let link = bin_dir.join(name);
+            if link.symlink_metadata().is_ok() {
+                std::fs::remove_file(&link)
+                    .with_context(|| format!("could not replace {}", link.display()))?;
+            }
+            std::os::unix::fs::symlink(path, &link)
+                .with_context(|| format!("could not link {}", link.display()))?;
+        } else {
+            let name = name.strip_suffix(".exe").unwrap_or(name);
+            let link = bin_dir.join(format!("{name}.cmd"));
+            std::fs::write(&link, format!("@\"{}\" %*\r\n", path.display()))
+                .with_context(|| format!("could not create {}", link.display()))?;
+        }
+    }
+
+    debug!("exposed {} at {}", path.display(), link.display());
+    Ok(link)
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+    use std::io::Write;
+    use std::path::Path;
+    use tempdir::TempDir;
+
+    use super::{expose_executable, extract_archive, find_entrypoints, tool_dir};
+    use crate::registry::DownloadFileFormat;
+
+    const SCRIPT: &[u8] = b"#!/bin/sh\necho hello\n";
+
+    fn tar_bytes() -> Vec<u8> {
+        let mut builder = tar::Builder::new(Vec::new());
+        for (path, mode) in [("tool-1.0/bin/tool", 0o755), ("tool-1.0/README", 0o644)] {
+            let mut header = tar::Header::new_gnu();
+            header.set_size(SCRIPT.len() as u64);
+            header.set_mode(mode);
+            header.set_cksum();
+            builder.append_data(&mut header, path, SCRIPT).unwrap();
+        }
+        builder.into_inner().unwrap()
+    }
+
+    fn write_archive(dir: &Path, format: &DownloadFileFormat) -> std::path::PathBuf {
+        let path = dir.join(format!("downloaded.{}", format.extension().unwrap()));
+        let tar = tar_bytes();
+
+        let bytes = match format {
+            DownloadFileFormat::ZIP => {
+                let mut writer = zip::ZipWriter::new(std::io::Cursor::new(Vec::new()));
+                let options = zip::write::SimpleFileOptions::default().unix_permissions(0o755);
+                writer.start_file("tool-1.0/bin/tool", options).unwrap();
+                writer.write_all(SCRIPT).unwrap();
+                writer.start_file("tool-1.0/README", options).unwrap();
+                writer.write_all(SCRIPT).unwrap();
+                writer.finish().unwrap().into_inner()
+            }
+            DownloadFileFormat::TarGz => {
+                let mut encoder =
+                    flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::fast());
+                encoder.write_all(&tar).unwrap();
+                encoder.finish().unwrap()
+            }
+            DownloadFileFormat::TarXz => {
+                let mut output = Vec::new();
+                lzma_rs::xz_compress(&mut tar.as_slice(), &mut output).unwrap();
+                output
+            }
+            DownloadFileFormat::TarZst => {
+                let mut output = Vec::new();
+                ruzstd::encoding::compress(
+                    tar.as_slice(),
+                    &mut output,
+                    ruzstd::encoding::CompressionLevel::Fastest,
+                );
+                output
+            }
+            _ => unreachable!(),
+        };
+
+        std::fs::write(&path, bytes).unwrap();
+        path
+    }
+
+    #[test]
+    fn test_extract_archives() {
+        let formats = [
+            DownloadFileFormat::ZIP,
+            DownloadFileFormat::TarGz,
+            DownloadFileFormat::TarXz,
+            DownloadFileFormat::TarZst,
+        ];
+
+        for format in formats {
+            let dir = TempDir::new("ctftools_test").unwrap();
+            let archive = write_archive(dir.path(), &format);
+            let dest = dir.path().join("tools").join("tool");
+
+            // Leftovers from the previous installation must be removed.
+            std::fs::create_dir_all(&dest).unwrap();
+            std::fs::write(dest.join("stale"), "").unwrap();
+
+            extract_archive(&archive, &format, &dir.path().join("tools"), &dest).unwrap();
+            assert!(!dest.join("stale").exists(), "{format:?}");
+            assert_eq!(
+                std::fs::read(dest.join("tool-1.0/bin/tool")).unwrap(),
+                SCRIPT,
+                "{format:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_find_entrypoints() {
+        let dir = TempDir::new("ctftools_test").unwrap();
+        let archive = write_archive(dir.path(), &DownloadFileFormat::TarGz);
+        let dest = dir.path().join("tool");
+        extract_archive(&archive, &DownloadFileFormat::TarGz, dir.path(), &dest).unwrap();
+
+        let expected = dest.join("tool-1.0/bin/tool");
+        assert_eq!(
+            find_entrypoints(&dest, &[], "tool").unwrap(),
+            std::slice::from_ref(&expected)
+        );
+        assert_eq!(
+            find_entrypoints(&dest, &["bin/tool".into()], "tool").unwrap(),
+            std::slice::from_ref(&expected)
+        );
+        assert_eq!(
+            find_entrypoints(&dest, &["tool-1.0/bin/tool".into()], "tool").unwrap(),
+            [expected]
+        );
+
+        assert!(find_entrypoints(&dest, &[], "other").is_err());
+        assert!(find_entrypoints(&dest, &["bin/other".into()], "tool").is_err());
+    }
+
+    #[test]
+    fn test_extract_outside_of_tools_dir() {
+        let dir = TempDir::new("ctftools_test").unwrap();
+        let archive = write_archive(dir.path(), &DownloadFileFormat::TarGz);
+        let tools_dir = dir.path().join("tools");
+
+        // The destination is removed before extracting, so nothing
+        // else than the directory of a tool may be passed in.
+        for dest in [
+            dir.path().to_path_buf(),
+            tools_dir.clone(),
+            tools_dir.join("tool").join("nested"),
+            tools_dir.join(".."),
+        ] {
+            std::fs::create_dir_all(&dest).unwrap();
+            let result = extract_archive(&archive, &DownloadFileFormat::TarGz, &tools_dir, &dest);
+            assert!(result.is_err(), "{}", dest.display());
+            assert!(dest.exists(), "{}", dest.display());
+        }
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_symlinked_entrypoints() {
+        let dir = TempDir::new("ctftools_test").unwrap();
+        let outside = dir.path().join("outside");
+        std::fs::write(&outside, SCRIPT).unwrap();
+
+        let dest = dir.path().join("tool");
+        std::fs::create_dir_all(dest.join("bin")).unwrap();
+        std::os::unix::fs::symlink(&outside, dest.join("bin").join("tool")).unwrap();
+
+        assert!(find_entrypoints(&dest, &["bin/tool".into()], "tool").is_err());
+
+        // Symbolic links to directories outside of the archive are refused too.
+        std::os::unix::fs::symlink(dir.path(), dest.join("up")).unwrap();
+        assert!(find_entrypoints(&dest, &["up/outside".into()], "tool").is_err());
+        assert!(find_entrypoints(&dest, &[], "tool").is_err());
+        assert!(
+            expose_executable(&dest.join("bin/tool"), &dir.path().join("bin"), "tool").is_err()
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_expose_executable() {
+        use crate::process::ProcessBuilder;
+
+        let dir = TempDir::new("ctftools_test").unwrap();
+        let file = dir.path().join("tool");
+        std::fs::write(&file, SCRIPT).unwrap();
+
+        let bin_dir = dir.path().join("bin");
+        let link = expose_executable(&file, &bin_dir, "my-tool").unwrap();
+        assert_eq!(link, bin_dir.join("my-tool"));
+
+        // Exposing it again should replace the previous link.
+        let link = expose_executable(&file, &bin_dir, "my-tool").unwrap();
+
+        let output = ProcessBuilder::new(link).exec_with_output().unwrap();
+        assert_eq!(String::from_utf8_lossy(&output.stdout), "hello\n");
+    }
+
+    #[test]
+    fn test_tool_dir() {
+        let tools_dir = Path::new("/home/ctf/.ctftools/data/tools");
+        assert_eq!(
+            tool_dir(tools_dir, "ghidra").unwrap(),
+            tools_dir.join("ghidra")
+        );
+        assert!(tool_dir(tools_dir, "..").is_err());
+        assert!(tool_dir(tools_dir, "../../home").is_err());
+        assert!(tool_dir(tools_dir, "").is_err());
+    }
+}
@@ -0,0 +1,656 @@
+use anyhow::{Context, Result, bail};
+use serde::{Deserialize, Serialize};
+use std::fs::File;
+use std::path::{Component, Path, PathBuf};
+
+use crate::env::Environment;
+use crate::install::checksum::sha256_of_file;
+use crate::install::{InstallPlanResult, InstallTask, PackageFile};
+use crate::registry::{ToolDownloadInstructions, ToolMetadata, Toolkit, is_valid_command};
+
+/// Name of the manifest file at the root of an installation bundle.
+pub const MANIFEST_FILE: &str = "manifest.yml";
+
+/// Version of the manifest format written by this version of ctftools.
+pub const MANIFEST_VERSION: u32 = 1;
+
+/// Describes the tools in an installation bundle, a tar archive with
+/// every file needed to install them without network access.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct BundleManifest {
+    /// Version of the manifest format.
+    pub version: u32,
+
+    /// Operating system that the tools are bundled for,
+    /// named the same way as [`std::env::consts::OS`].
+    pub os: String,
+
+    /// CPU architecture that the tools are bundled for,
+    /// named the same way as [`std::env::consts::ARCH`].
+    pub arch: String,
+
+    /// Tools in the bundle, in the order they are installed.
+    #[serde(default)]
+    pub tools: Vec<BundledTool>,
+}
+
+/// A tool in an installation bundle along with its files.
+///
+/// It is installed either from a [download](BundledDownload)
+/// or from package files, but never from both.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct BundledTool {
+    /// The command used to run the tool.
+    pub command: String,
+
+    /// The name of the tool.
+    pub name: String,
+
+    /// The file downloaded to install the tool.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub download: Option<BundledDownload>,
+
+    /// The package files (e.g. `.deb` or `.rpm`) that provide
+    /// the tool, along with their dependencies.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub packages: Vec<BundledPackage>,
+}
+
+/// A package file of a [bundled tool](BundledTool).
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct BundledPackage {
+    /// Path to the package file.
+    pub file: String,
+
+    /// The sha256 checksum of the package file, so it can
+    /// be checked for corruption before it is installed.
+    pub sha256: String,
+}
+
+/// A file downloaded to install a [bundled tool](BundledTool).
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct BundledDownload {
+    /// Path to the downloaded file.
+    pub file: String,
+
+    /// Path to the detached signature of the file, if it has one.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub signature: Option<String>,
+
+    /// Instructions that the file was downloaded with. They must be the
+    /// same as the ones in the toolkit the tool is installed with.
+    pub instructions: ToolDownloadInstructions,
+}
+
+/// Puts the files of tools into a directory and writes
+/// them as an installation bundle along with its manifest.
+///
+/// Paths in the manifest are relative to the root of the bundle.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BundleWriter {
+    dir: PathBuf,
+    manifest: BundleManifest,
+}
+
+impl BundleWriter {
+    /// Creates a [`BundleWriter`] that puts the files of tools for
+    /// the given operating system and architecture into `dir`.
+    #[must_use]
+    pub fn new<T: Into<PathBuf>>(dir: T, os: &str, arch: &str) -> Self {
+        Self {
+            dir: dir.into(),
+            manifest: BundleManifest {
+                version: MANIFEST_VERSION,
+                os: os.to_string(),
+                arch: arch.to_string(),
+                tools: Vec::new(),
+            },
+        }
+    }
+
+    /// The manifest of the tools added to the bundle so far.
+    #[must_use]
+    pub fn manifest(&self) -> &BundleManifest {
+        &self.manifest
+    }
+
+    /// Gets the path where the file of a tool is downloaded into.
+    pub fn download_path(
+        &self,
+        tool: &ToolMetadata,
+        instructions: &ToolDownloadInstructions,
+    ) -> Result<PathBuf> {
+        let dir = self.dir.join("downloads");
+        std::fs::create_dir_all(&dir)
+            .with_context(|| format!("could not create {}", dir.display()))?;
+
+        Ok(match instructions.format.extension() {
+            Some(extension) => dir.join(format!("{}.{extension}", tool.command)),
+            None => dir.join(&tool.command),
+        })
+    }
+
+    /// Gets the directory where the package files of a tool are downloaded into.
+    #[must_use]
+    pub fn packages_dir(&self, tool: &ToolMetadata) -> PathBuf {
+        self.dir.join("packages").join(&tool.command)
+    }
+
+    /// Adds a tool whose file has been downloaded into its
+    /// [download path](BundleWriter::download_path), along with the
+    /// contents of its detached signature if it has one.
+    pub fn add_download(
+        &mut self,
+        tool: &ToolMetadata,
+        instructions: &ToolDownloadInstructions,
+        signature: Option<&str>,
+    ) -> Result<()> {
+        let path = self.download_path(tool, instructions)?;
+        let signature = match signature {
+            Some(contents) => {
+                let signature_path = path.with_file_name(format!("{}.sig", tool.command));
+                std::fs::write(&signature_path, contents)
+                    .with_context(|| format!("could not write {}", signature_path.display()))?;
+                Some(self.relative_path(&signature_path)?)
+            }
+            None => None,
+        };
+
+        self.manifest.tools.push(BundledTool {
+            command: tool.command.clone(),
+            name: tool.name.clone(),
+            download: Some(BundledDownload {
+                file: self.relative_path(&path)?,
+                signature,
+                instructions: instructions.clone(),
+            }),
+            packages: Vec::new(),
+        });
+        Ok(())
+    }
+
+    /// Adds a tool whose package files have been downloaded
+    /// into its [packages directory](BundleWriter::packages_dir).
+    pub fn add_packages(&mut self, tool: &ToolMetadata, files: &[PathBuf]) -> Result<()> {
+        let packages = files
+            .iter()
+            .map(|v| {
+                Ok(BundledPackage {
+                    file: self.relative_path(v)?,
+                    sha256: sha256_of_file(v)?,
+                })
+            })
+            .collect::<Result<_>>()?;
+
+        self.manifest.tools.push(BundledTool {
+            command: tool.command.clone(),
+            name: tool.name.clone(),
+            download: None,
+            packages,
+        });
+        Ok(())
+    }
+
+    /// Writes the bundle as a tar archive into `output`.
+    ///
+    /// Only the files listed in the manifest are written.
+    pub fn write(&self, output: &Path) -> Result<()> {
+        let manifest =
+            serde_yml::to_string(&self.manifest).context("failed to serialize manifest")?;
+        let manifest_path = self.dir.join(MANIFEST_FILE);
+        std::fs::write(&manifest_path, manifest)
+            .with_context(|| format!("could not write {}", manifest_path.display()))?;
+
+        let file = File::create(output)
+            .with_context(|| format!("could not create {}", output.display()))?;
+        let mut archive = tar::Builder::new(file);
+
+        let mut paths = vec![MANIFEST_FILE];
+        for tool in self.manifest.tools.iter() {
+            if let Some(download) = tool.download.as_ref() {
+                paths.push(&download.file);
+                paths.extend(download.signature.as_deref());
+            }
+            paths.extend(tool.packages.iter().map(|v| v.file.as_str()));
+        }
+
+        for path in paths {
+            archive
+                .append_path_with_name(self.dir.join(path), path)
+                .with_context(|| format!("could not add {path} to the bundle"))?;
+        }
+
+        archive
+            .into_inner()
+            .and_then(|v| v.sync_all())
+            .with_context(|| format!("could not write {}", output.display()))
+    }
+
+    fn relative_path(&self, path: &Path) -> Result<String> {
+        let relative = path
+            .strip_prefix(&self.dir)
+            .with_context(|| format!("{} is not in the bundle", path.display()))?;
+
+        // Paths are always separated with slashes to be portable.
+        let components: Vec<_> = relative
+            .components()
+            .map(|v| v.as_os_str().to_string_lossy())
+            .collect();
+        Ok(components.join("/"))
+    }
+}
+
+/// An installation bundle extracted into a directory.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Bundle {
+    dir: PathBuf,
+    manifest: BundleManifest,
+}
+
+impl Bundle {
+    /// Extracts the installation bundle at `path` into `dir`
+    /// and checks whether every file in its manifest exists.
+    pub fn extract(path: &Path, dir: &Path) -> Result<Self> {
+        let file =
+            File::open(path).with_context(|| format!("could not open {}", path.display()))?;
+        tar::Archive::new(file)
+            .unpack(dir)
+            .with_context(|| format!("could not extract {}", path.display()))?;
+
+        let manifest_path = dir.join(MANIFEST_FILE);
+        let contents = std::fs::read_to_string(&manifest_path)
+            .with_context(|| format!("{} has no manifest", path.display()))?;
+        let manifest: BundleManifest =
+            serde_yml::from_str(&contents).context("failed to deserialize bundle manifest")?;
+
+        let bundle = Self {
+            dir: dir.to_path_buf(),
+            manifest,
+        };
+        bundle
+            .validate()
+            .with_context(|| format!("{} is not a valid bundle", path.display()))?;
+        Ok(bundle)
+    }
+
+    /// The manifest of the bundle.
+    #[must_use]
+    pub fn manifest(&self) -> &BundleManifest {
+        &self.manifest
+    }
+
+    /// Checks whether the tools in the bundle can be installed on
+    /// a system with the given operating system and architecture.
+    pub fn check_platform(&self, os: &str, arch: &str) -> Result<()> {
+        let manifest = &self.manifest;
+        if manifest.os != os || manifest.arch != arch {
+            bail!(
+                "the bundle is made for {}/{} but this system is {os}/{arch}",
+                manifest.os,
+                manifest.arch
+            );
+        }
+        Ok(())
+    }
+
+    /// Creates the [`InstallTask`] that installs a bundled tool
+    /// from its files without network access.
+    ///
+    /// `metadata` is the tool in the toolkit with the same command. Its
+    /// download instructions must be the same as the bundled ones since
+    /// the file is verified with the toolkit's checksums and signature.
+    pub fn task_of(&self, tool: &BundledTool, metadata: &ToolMetadata) -> Result<InstallTask> {
+        if let Some(download) = tool.download.as_ref() {
+            let Some(instructions) = metadata
+                .downloads
+                .for_os(&self.manifest.os)
+                .filter(|v| **v == download.instructions)
+            else {
+                bail!(
+                    "the bundled download of {} does not match the one in the toolkit",
+                    metadata.name
+                );
+            };
+
+            let signature = match download.signature.as_deref() {
+                Some(path) => {
+                    let path = self.dir.join(path);
+                    let contents = std::fs::read_to_string(&path)
+                        .with_context(|| format!("could not read {}", path.display()))?;
+                    Some(contents)
+                }
+                None => None,
+            };
+
+            return Ok(InstallTask::LocalDownload {
+                path: self.dir.join(&download.file),
+                signature,
+                instructions: Box::new(instructions.clone()),
+                command: metadata.command.clone(),
+                tool_name: metadata.name.clone(),
+            });
+        }
+
+        let files = tool
+            .packages
+            .iter()
+            .map(|v| PackageFile {
+                path: self.dir.join(&v.file),
+                sha256: v.sha256.clone(),
+            })
+            .collect();
+
+        Ok(InstallTask::PackageFiles {
+            files,
+            tool_name: metadata.name.clone(),
+        })
+    }
+
+    /// Creates an installation plan for the bundled tools that are
+    /// not installed yet.
+    ///
+    /// Every bundled tool must be in the `toolkit`, which the bundle
+    /// is verified against.
+    pub fn plan_install_missing_tools<'t>(
+        &self,
+        env: &dyn Environment,
+        toolkit: &'t Toolkit,
+    ) -> Result<Vec<InstallPlanResult<'t>>> {
+        let mut outcomes = Vec::new();
+        for tool in self.manifest.tools.iter() {
+            let Some(metadata) = toolkit.find_tool(&tool.command) else {
+                bail!(
+                    "refusing to install {:?} from the bundle as it is not in the toolkit",
+                    tool.command
+                );
+            };
+            if env.find_tool_executable(metadata)?.is_some() {
+                continue;
+            }
+            outcomes.push(InstallPlanResult::Task(self.task_of(tool, metadata)?));
+        }
+        Ok(outcomes)
+    }
+
+    fn validate(&self) -> Result<()> {
+        if self.manifest.version != MANIFEST_VERSION {
+            bail!(
+                "unsupported manifest version {} (expected {MANIFEST_VERSION})",
+                self.manifest.version
+            );
+        }
+
+        for tool in self.manifest.tools.iter() {
+            if !is_valid_command(&tool.command) {
+                bail!("invalid tool command: {:?}", tool.command);
+            }
+
+            let mut paths = Vec::new();
+            match (tool.download.as_ref(), tool.packages.is_empty()) {
+                (Some(download), true) => {
+                    download.instructions.validate()?;
+                    paths.push(&download.file);
+                    paths.extend(download.signature.as_ref());
+                }
+                (None, false) => paths.extend(tool.packages.iter().map(|v| &v.file)),
+                _ => bail!(
+                    "{:?} must have either a download or package files",
+                    tool.command
+                ),
+            }
+
+            for path in paths {
+                let is_inside = Path::new(path)
+                    .components()
+                    .all(|v| matches!(v, Component::Normal(..)));
+
+                if path.is_empty() || !is_inside {
+                    bail!("invalid file path: {path:?}");
+                }
+                if !self.dir.join(path).is_file() {
+                    bail!("{path} is missing");
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use maplit::hashmap;
+    use pretty_assertions::assert_eq;
+    use std::path::{Path, PathBuf};
+    use tempdir::TempDir;
+
+    use super::{Bundle, BundleManifest, BundleWriter, MANIFEST_FILE};
+    use crate::env::MockEnvironment;
+    use crate::install::checksum::sha256_of_file;
+    use crate::install::{InstallPlanResult, InstallTask, PackageFile};
+    use crate::registry::{
+        DownloadFileFormat, ToolDownloadInstructions, ToolMetadata, ToolPlatformDownloads, Toolkit,
+    };
+
+    fn tool(command: &str) -> ToolMetadata {
+        ToolMetadata::builder()
+            .name(command.to_uppercase())
+            .command(command.into())
+            .build()
+    }
+
+    fn tool_with_download(command: &str, instructions: &ToolDownloadInstructions) -> ToolMetadata {
+        ToolMetadata::builder()
+            .name(command.to_uppercase())
+            .command(command.into())
+            .downloads(
+                ToolPlatformDownloads::builder()
+                    .linux(instructions.clone())
+                    .build(),
+            )
+            .build()
+    }
+
+    #[test]
+    fn test_write_and_extract_bundle() {
+        let dir = TempDir::new("ctftools_test").unwrap();
+        let instructions = ToolDownloadInstructions::builder()
+            .format(DownloadFileFormat::TarGz)
+            .url("https://foo.local/foo.tar.gz".into())
+            .sha256("0".repeat(64))
+            .build();
+        let (foo, bar) = (tool_with_download("foo", &instructions), tool("bar"));
+
+        let mut writer = BundleWriter::new(dir.path().join("staging"), "linux", "x86_64");
+        let path = writer.download_path(&foo, &instructions).unwrap();
+        std::fs::write(&path, b"foo").unwrap();
+        writer
+            .add_download(&foo, &instructions, Some("signature"))
+            .unwrap();
+
+        let packages_dir = writer.packages_dir(&bar);
+        std::fs::create_dir_all(packages_dir.join("partial")).unwrap();
+        let files = vec![packages_dir.join("a.deb"), packages_dir.join("b.deb")];
+        for file in files.iter() {
+            std::fs::write(file, b"package").unwrap();
+        }
+        writer.add_packages(&bar, &files).unwrap();
+
+        let output = dir.path().join("bundle.tar");
+        writer.write(&output).unwrap();
+
+        let extracted = dir.path().join("extracted");
+        let bundle = Bundle::extract(&output, &extracted).unwrap();
+        assert_eq!(bundle.manifest(), writer.manifest());
+
+        // Only the files in the manifest are bundled.
+        assert!(
+            !extracted
+                .join("packages")
+                .join("bar")
+                .join("partial")
+                .exists()
+        );
+
+        let [foo_entry, bar_entry] = &bundle.manifest().tools[..] else {
+            panic!("expected two bundled tools");
+        };
+        assert_eq!(
+            foo_entry.download.as_ref().unwrap().file,
+            "downloads/foo.tar.gz"
+        );
+        let package_files = bar_entry
+            .packages
+            .iter()
+            .map(|v| v.file.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(package_files, ["packages/bar/a.deb", "packages/bar/b.deb"]);
+        assert_eq!(
+            bar_entry.packages[0].sha256,
+            sha256_of_file(&files[0]).unwrap()
+        );
+
+        assert_eq!(
+            bundle.task_of(foo_entry, &foo).unwrap(),
+            InstallTask::LocalDownload {
+                path: extracted.join("downloads").join("foo.tar.gz"),
+                signature: Some("signature".into()),
+                instructions: Box::new(instructions),
+                command: "foo".into(),
+                tool_name: "FOO".into(),
+            }
+        );
+        assert_eq!(
+            bundle.task_of(bar_entry, &bar).unwrap(),
+            InstallTask::PackageFiles {
+                files: vec![
+                    PackageFile {
+                        path: extracted.join("packages/bar/a.deb"),
+                        sha256: bar_entry.packages[0].sha256.clone(),
+                    },
+                    PackageFile {
+                        path: extracted.join("packages/bar/b.deb"),
+                        sha256: bar_entry.packages[1].sha256.clone(),
+                    },
+                ],
+                tool_name: "BAR".into(),
+            }
+        );
+
+        // Installed tools are left out of the plan.
+        let env = MockEnvironment::builder()
+            .installed_tools(hashmap! {
+                "bar".to_string() => PathBuf::from("/usr/bin/bar"),
+            })
+            .build();
+        let toolkit = Toolkit::new(vec![foo, bar]);
+        let outcomes = bundle.plan_install_missing_tools(&env, &toolkit).unwrap();
+        assert!(matches!(
+            &outcomes[..],
+            [InstallPlanResult::Task(InstallTask::LocalDownload { tool_name, .. })]
+                if tool_name == "FOO"
+        ));
+
+        bundle.check_platform("linux", "x86_64").unwrap();
+        assert!(bundle.check_platform("linux", "aarch64").is_err());
+        assert!(bundle.check_platform("windows", "x86_64").is_err());
+    }
+
+    fn bundle_with_manifest(dir: &Path, manifest: &str) -> anyhow::Result<Bundle> {
+        let staging = dir.join("staging");
+        std::fs::create_dir_all(staging.join("downloads")).unwrap();
+        std::fs::write(staging.join("downloads").join("foo"), b"foo").unwrap();
+        std::fs::write(staging.join(MANIFEST_FILE), manifest).unwrap();
+
+        let output = dir.join("bundle.tar");
+        let mut archive = tar::Builder::new(std::fs::File::create(&output).unwrap());
+        archive.append_dir_all(".", &staging).unwrap();
+        archive.finish().unwrap();
+
+        Bundle::extract(&output, &dir.join("extracted"))
+    }
+
+    #[test]
+    fn test_extract_invalid_bundle() {
+        let manifest = |version: u32, file: &str| {
+            let manifest = format!(
+                "version: {version}\nos: linux\narch: x86_64\ntools:\n\
+                - command: foo\n  name: foo\n  download:\n    file: {file}\n    \
+                instructions:\n      format: binary\n      url: https://foo.local/foo\n"
+            );
+            serde_yml::from_str::<BundleManifest>(&manifest).unwrap();
+            manifest
+        };
+
+        let dir = TempDir::new("ctftools_test").unwrap();
+        bundle_with_manifest(dir.path(), &manifest(1, "downloads/foo")).unwrap();
+
+        for manifest in [
+            manifest(2, "downloads/foo"),
+            manifest(1, "downloads/missing"),
+            manifest(1, "../downloads/foo"),
+            manifest(1, "/etc/passwd"),
+            manifest(1, "downloads/foo").replace("command: foo", "command: ../foo"),
+            "version: 1\nos: linux\narch: x86_64\ntools:\n- command: foo\n  name: foo\n".into(),
+        ] {
+            let dir = TempDir::new("ctftools_test").unwrap();
+            assert!(
+                bundle_with_manifest(dir.path(), &manifest).is_err(),
+                "{manifest}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_plan_against_toolkit() {
+        let dir = TempDir::new("ctftools_test").unwrap();
+        let instructions = ToolDownloadInstructions::builder()
+            .format(DownloadFileFormat::Binary)
+            .url("https://foo.local/foo".into())
+            .sha256("0".repeat(64))
+            .build();
+        let foo = tool_with_download("foo", &instructions);
+
+        let mut writer = BundleWriter::new(dir.path().join("staging"), "linux", "x86_64");
+        let path = writer.download_path(&foo, &instructions).unwrap();
+        std::fs::write(&path, b"foo").unwrap();
+        writer.add_download(&foo, &instructions, None).unwrap();
+
+        let output = dir.path().join("bundle.tar");
+        writer.write(&output).unwrap();
+        let bundle = Bundle::extract(&output, &dir.path().join("extracted")).unwrap();
+        let env = MockEnvironment::builder().build();
+
+        let toolkit = Toolkit::new(vec![foo]);
+        let outcomes = bundle.plan_install_missing_tools(&env, &toolkit).unwrap();
+        assert_eq!(outcomes.len(), 1);
+
+        // Tools missing from the toolkit are refused.
+        let error = bundle
+            .plan_install_missing_tools(&env, &Toolkit::new(vec![tool("bar")]))
+            .unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "refusing to install \"foo\" from the bundle as it is not in the toolkit"
+        );
+
+        // So are downloads that differ from the toolkit.
+        let mut other = instructions.clone();
+        other.sha256 = Some("1".repeat(64));
+        for toolkit in [
+            Toolkit::new(vec![tool("foo")]),
+            Toolkit::new(vec![tool_with_download("foo", &other)]),
+        ] {
+            let error = bundle
+                .plan_install_missing_tools(&env, &toolkit)
+                .unwrap_err();
+            assert_eq!(
+                error.to_string(),
+                "the bundled download of FOO does not match the one in the toolkit"
+            );
+        }
+    }
+}
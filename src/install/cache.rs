@@ -0,0 +1,311 @@
+use anyhow::{Context, Result};
+use log::debug;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::path::{Path, PathBuf};
+
+use crate::install::checksum::to_hex;
+use crate::registry::ToolDownloadInstructions;
+
+/// File extension of the metadata kept next to each cached download.
+const METADATA_EXTENSION: &str = "yml";
+
+/// File extension of a download that has not been completed yet.
+const PARTIAL_EXTENSION: &str = "part";
+
+/// A persistent cache of downloaded files, usually located at
+/// [`ProgramDirs::downloads_cache_dir`](crate::util::ProgramDirs::downloads_cache_dir).
+///
+/// Only downloads with a checksum are cached. Files are keyed by their
+/// URL and checksum, so a cached file is reused as long as the toolkit
+/// expects the same contents from the same URL. Interrupted downloads
+/// are kept as partial files and resumed later.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DownloadCache {
+    dir: PathBuf,
+}
+
+/// A file in the [`DownloadCache`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CachedDownload {
+    /// URL where the file is downloaded from.
+    pub url: String,
+
+    /// Path to the cached file, or to the partial file if the
+    /// download has not been completed yet.
+    pub path: PathBuf,
+
+    /// Size of the cached file in bytes.
+    pub size: u64,
+
+    /// Whether the download has been completed and verified.
+    pub complete: bool,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+struct CacheMetadata {
+    url: String,
+    file_name: String,
+}
+
+impl DownloadCache {
+    /// Creates a [`DownloadCache`] stored in the given directory.
+    ///
+    /// The directory does not have to exist yet.
+    #[must_use]
+    pub fn new<T: Into<PathBuf>>(dir: T) -> Self {
+        Self { dir: dir.into() }
+    }
+
+    /// Directory where the cached files are stored.
+    #[must_use]
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Gets the path where a download is cached once it is complete.
+    ///
+    /// Returns `None` if the download has no checksum to key it by.
+    #[must_use]
+    pub fn path_of(&self, instructions: &ToolDownloadInstructions) -> Option<PathBuf> {
+        let checksum = instructions
+            .sha256
+            .as_deref()
+            .or(instructions.sha512.as_deref())?;
+
+        let mut hasher = Sha256::new();
+        hasher.update(instructions.url.as_bytes());
+        hasher.update(b"\n");
+        hasher.update(checksum.to_ascii_lowercase().as_bytes());
+
+        let key = to_hex(&hasher.finalize());
+
+        // Package files are installed by their extension, so it is kept.
+        let file_name = match instructions.format.extension() {
+            Some(extension) => format!("{key}.{extension}"),
+            None => key,
+        };
+        Some(self.dir.join(file_name))
+    }
+
+    /// Prepares the cache to download a file into `path`, as given by
+    /// [`DownloadCache::path_of`], and returns the path of its partial file.
+    pub fn begin(&self, instructions: &ToolDownloadInstructions, path: &Path) -> Result<PathBuf> {
+        std::fs::create_dir_all(&self.dir)
+            .with_context(|| format!("could not create {}", self.dir.display()))?;
+
+        let metadata = CacheMetadata {
+            url: instructions.url.clone(),
+            file_name: file_name_of(path),
+        };
+        let contents = serde_yml::to_string(&metadata).context("failed to serialize metadata")?;
+        let metadata_path = path.with_extension(METADATA_EXTENSION);
+        std::fs::write(&metadata_path, contents)
+            .with_context(|| format!("could not write {}", metadata_path.display()))?;
+
+        Ok(partial_path_of(path))
+    }
+
+    /// Marks the partial file of a download as complete.
+    pub fn complete(&self, path: &Path) -> Result<()> {
+        let partial = partial_path_of(path);
+        std::fs::rename(&partial, path)
+            .with_context(|| format!("could not move {}", partial.display()))?;
+
+        debug!("cached download at {}", path.display());
+        Ok(())
+    }
+
+    /// Removes a cached download along with its partial file and metadata.
+    pub fn remove(&self, path: &Path) -> Result<()> {
+        for path in [
+            path.to_path_buf(),
+            partial_path_of(path),
+            path.with_extension(METADATA_EXTENSION),
+        ] {
+            match std::fs::remove_file(&path) {
+                Ok(()) => {}
+                Err(error) if error.kind() == std::io::ErrorKind::NotFound => {}
+                Err(error) => {
+                    return Err(error)
+                        .with_context(|| format!("could not remove {}", path.display()));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Lists every cached download, including the partial ones.
+    pub fn list(&self) -> Result<Vec<CachedDownload>> {
+        let entries = match std::fs::read_dir(&self.dir) {
+            Ok(entries) => entries,
+            Err(error) if error.kind() == std::io::ErrorKind::NotFound => {
+                return Ok(Vec::new());
+            }
+            Err(error) => {
+                return Err(error)
+                    .with_context(|| format!("could not read {}", self.dir.display()));
+            }
+        };
+
+        let mut downloads = Vec::new();
+        for entry in entries {
+            let metadata_path = entry?.path();
+            if metadata_path
+                .extension()
+                .is_none_or(|v| v != METADATA_EXTENSION)
+            {
+                continue;
+            }
+
+            let contents = std::fs::read_to_string(&metadata_path)
+                .with_context(|| format!("could not read {}", metadata_path.display()))?;
+            let metadata: CacheMetadata = match serde_yml::from_str(&contents) {
+                Ok(metadata) => metadata,
+                Err(error) => {
+                    debug!("ignoring {}: {error}", metadata_path.display());
+                    continue;
+                }
+            };
+
+            let path = self.dir.join(&metadata.file_name);
+            let partial = partial_path_of(&path);
+            let (path, complete) = if path.is_file() {
+                (path, true)
+            } else if partial.is_file() {
+                (partial, false)
+            } else {
+                continue;
+            };
+
+            downloads.push(CachedDownload {
+                url: metadata.url,
+                size: std::fs::metadata(&path)?.len(),
+                path,
+                complete,
+            });
+        }
+
+        downloads.sort_by(|a, b| a.url.cmp(&b.url));
+        Ok(downloads)
+    }
+
+    /// Removes every cached download.
+    ///
+    /// Returns how many bytes have been freed.
+    pub fn clean(&self) -> Result<u64> {
+        let freed = self.list()?.iter().map(|v| v.size).sum();
+        match std::fs::remove_dir_all(&self.dir) {
+            Ok(()) => Ok(freed),
+            Err(error) if error.kind() == std::io::ErrorKind::NotFound => Ok(0),
+            Err(error) => {
+                Err(error).with_context(|| format!("could not remove {}", self.dir.display()))
+            }
+        }
+    }
+}
+
+fn file_name_of(path: &Path) -> String {
+    path.file_name()
+        .map(|v| v.to_string_lossy().into_owned())
+        .unwrap_or_default()
+}
+
+fn partial_path_of(path: &Path) -> PathBuf {
+    path.with_file_name(format!("{}.{PARTIAL_EXTENSION}", file_name_of(path)))
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+    use tempdir::TempDir;
+
+    use super::{CachedDownload, DownloadCache};
+    use crate::registry::{DownloadFileFormat, ToolDownloadInstructions};
+
+    fn instructions(url: &str, sha256: Option<&str>) -> ToolDownloadInstructions {
+        ToolDownloadInstructions::builder()
+            .format(DownloadFileFormat::Deb)
+            .url(url.into())
+            .maybe_sha256(sha256.map(String::from))
+            .build()
+    }
+
+    #[test]
+    fn test_path_of_download() {
+        let cache = DownloadCache::new("/cache");
+        let sha256 = "0".repeat(64);
+
+        assert_eq!(
+            cache.path_of(&instructions("https://foo.local/a", None)),
+            None
+        );
+
+        let path = cache
+            .path_of(&instructions("https://foo.local/a", Some(&sha256)))
+            .unwrap();
+        assert_eq!(path.extension().unwrap(), "deb");
+        assert!(path.starts_with("/cache"));
+
+        // The key is different if either the URL or the checksum changes.
+        assert_ne!(
+            cache.path_of(&instructions("https://foo.local/b", Some(&sha256))),
+            Some(path.clone())
+        );
+        assert_ne!(
+            cache.path_of(&instructions("https://foo.local/a", Some(&"1".repeat(64)))),
+            Some(path.clone())
+        );
+        assert_eq!(
+            cache.path_of(&instructions(
+                "https://foo.local/a",
+                Some(&sha256.to_uppercase())
+            )),
+            Some(path)
+        );
+    }
+
+    #[test]
+    fn test_cache_downloads() {
+        let dir = TempDir::new("ctftools_test").unwrap();
+        let cache = DownloadCache::new(dir.path().join("downloads"));
+        assert_eq!(cache.list().unwrap(), []);
+
+        let a = instructions("https://foo.local/a", Some(&"0".repeat(64)));
+        let b = instructions("https://foo.local/b", Some(&"1".repeat(64)));
+
+        let a_path = cache.path_of(&a).unwrap();
+        let a_partial = cache.begin(&a, &a_path).unwrap();
+        std::fs::write(&a_partial, b"hello\n").unwrap();
+        cache.complete(&a_path).unwrap();
+
+        let b_path = cache.path_of(&b).unwrap();
+        let b_partial = cache.begin(&b, &b_path).unwrap();
+        std::fs::write(&b_partial, b"hel").unwrap();
+
+        assert_eq!(
+            cache.list().unwrap(),
+            [
+                CachedDownload {
+                    url: "https://foo.local/a".into(),
+                    path: a_path.clone(),
+                    size: 6,
+                    complete: true,
+                },
+                CachedDownload {
+                    url: "https://foo.local/b".into(),
+                    path: b_partial,
+                    size: 3,
+                    complete: false,
+                },
+            ]
+        );
+
+        cache.remove(&a_path).unwrap();
+        assert_eq!(cache.list().unwrap().len(), 1);
+
+        assert_eq!(cache.clean().unwrap(), 3);
+        assert_eq!(cache.list().unwrap(), []);
+        assert_eq!(cache.clean().unwrap(), 0);
+    }
+}
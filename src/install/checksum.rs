@@ -0,0 +1,186 @@
+use anyhow::{Context, Result, bail};
+use sha2::{Digest, Sha256, Sha512};
+use std::fmt::Write;
+use std::fs::File;
+use std::io::Read;
+use std::path::Path;
+
+use crate::registry::ToolDownloadInstructions;
+
+/// Hashes a downloaded file while it is being written and compares
+/// it with the checksums given in its [download instructions].
+///
+/// [download instructions]: ToolDownloadInstructions
+#[derive(Debug)]
+pub struct ChecksumVerifier {
+    sha256: Option<(String, Sha256)>,
+    sha512: Option<(String, Sha512)>,
+}
+
+impl ChecksumVerifier {
+    /// Creates a [`ChecksumVerifier`] for every checksum
+    /// given in the download instructions.
+    #[must_use]
+    pub fn new(instructions: &ToolDownloadInstructions) -> Self {
+        Self {
+            sha256: instructions
+                .sha256
+                .as_ref()
+                .map(|v| (v.to_ascii_lowercase(), Sha256::new())),
+            sha512: instructions
+                .sha512
+                .as_ref()
+                .map(|v| (v.to_ascii_lowercase(), Sha512::new())),
+        }
+    }
+
+    /// Whether there is any checksum to verify the file against.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.sha256.is_none() && self.sha512.is_none()
+    }
+
+    /// Hashes the next chunk of the file.
+    pub fn update(&mut self, bytes: &[u8]) {
+        if let Some((_, hasher)) = self.sha256.as_mut() {
+            hasher.update(bytes);
+        }
+        if let Some((_, hasher)) = self.sha512.as_mut() {
+            hasher.update(bytes);
+        }
+    }
+
+    /// Hashes the whole contents of a file, such as the part of
+    /// a download that was received before it got interrupted.
+    pub fn update_from_file(&mut self, path: &Path) -> Result<()> {
+        read_chunks(path, |bytes| self.update(bytes))
+    }
+
+    /// Checks whether the hashed file matches every expected checksum.
+    pub fn verify(self, url: &str) -> Result<()> {
+        if let Some((expected, hasher)) = self.sha256 {
+            check(url, "sha256", &expected, &hasher.finalize())?;
+        }
+        if let Some((expected, hasher)) = self.sha512 {
+            check(url, "sha512", &expected, &hasher.finalize())?;
+        }
+        Ok(())
+    }
+}
+
+/// Computes the sha256 checksum of a file in lowercase hexadecimal.
+pub fn sha256_of_file(path: &Path) -> Result<String> {
+    let mut hasher = Sha256::new();
+    read_chunks(path, |bytes| hasher.update(bytes))?;
+    Ok(to_hex(&hasher.finalize()))
+}
+
+/// Checks whether a file matches its expected sha256 checksum.
+pub fn verify_file_sha256(path: &Path, expected: &str) -> Result<()> {
+    let mut hasher = Sha256::new();
+    read_chunks(path, |bytes| hasher.update(bytes))?;
+    check(
+        &path.display().to_string(),
+        "sha256",
+        &expected.to_ascii_lowercase(),
+        &hasher.finalize(),
+    )
+}
+
+/// Encodes a digest in lowercase hexadecimal.
+#[must_use]
+pub fn to_hex(digest: &[u8]) -> String {
+    digest.iter().fold(String::new(), |mut output, byte| {
+        _ = write!(output, "{byte:02x}");
+        output
+    })
+}
+
+fn read_chunks(path: &Path, mut f: impl FnMut(&[u8])) -> Result<()> {
+    let mut file =
+        File::open(path).with_context(|| format!("could not open {}", path.display()))?;
+
+    let mut buf = vec![0u8; 64 * 1024];
+    loop {
+        let len = file
+            .read(&mut buf)
+            .with_context(|| format!("could not read {}", path.display()))?;
+        if len == 0 {
+            break;
+        }
+        f(&buf[..len]);
+    }
+    Ok(())
+}
+
+fn check(url: &str, name: &str, expected: &str, digest: &[u8]) -> Result<()> {
+    let actual = to_hex(digest);
+
+    if actual != expected {
+        bail!("checksum mismatch for {url}: expected {name} {expected}, got {actual}");
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use tempdir::TempDir;
+
+    use super::{ChecksumVerifier, sha256_of_file, verify_file_sha256};
+    use crate::registry::{DownloadFileFormat, ToolDownloadInstructions};
+
+    // Digests of "hello\n".
+    const SHA256: &str = "5891b5b522d5df086d0ff0b110fbd9d21bb4fc7163af34d08286a2e846f6be03";
+    const SHA512: &str = "e7c22b994c59d9cf2b48e549b1e24666636045930d3da7c1acb299d1c3b7f931\
+                          f94aae41edda2c2b207a36e10f8bcb8d45223e54878f5b316e7ce3b6bc019629";
+
+    fn verifier(sha256: Option<&str>, sha512: Option<&str>) -> ChecksumVerifier {
+        let instructions = ToolDownloadInstructions::builder()
+            .format(DownloadFileFormat::Binary)
+            .url("https://foo.local/tool".into())
+            .maybe_sha256(sha256.map(String::from))
+            .maybe_sha512(sha512.map(String::from))
+            .build();
+
+        ChecksumVerifier::new(&instructions)
+    }
+
+    #[test]
+    fn test_verify_checksums() {
+        assert!(verifier(None, None).is_empty());
+
+        let mut verifier = self::verifier(Some(&SHA256.to_uppercase()), Some(SHA512));
+        verifier.update(b"hel");
+        verifier.update(b"lo\n");
+        verifier.verify("https://foo.local/tool").unwrap();
+    }
+
+    #[test]
+    fn test_verify_checksum_mismatch() {
+        let mut verifier = self::verifier(Some(SHA256), None);
+        verifier.update(b"goodbye\n");
+
+        let error = verifier.verify("https://foo.local/tool").unwrap_err();
+        assert!(
+            error.to_string().starts_with(&format!(
+                "checksum mismatch for https://foo.local/tool: expected sha256 {SHA256}, got "
+            )),
+            "{error}"
+        );
+
+        let mut verifier = self::verifier(Some(SHA256), Some(&"0".repeat(128)));
+        verifier.update(b"hello\n");
+        assert!(verifier.verify("https://foo.local/tool").is_err());
+    }
+
+    #[test]
+    fn test_file_sha256() {
+        let dir = TempDir::new("ctftools_test").unwrap();
+        let path = dir.path().join("hello.deb");
+        std::fs::write(&path, b"hello\n").unwrap();
+
+        assert_eq!(sha256_of_file(&path).unwrap(), SHA256);
+        verify_file_sha256(&path, &SHA256.to_uppercase()).unwrap();
+        assert!(verify_file_sha256(&path, &"0".repeat(64)).is_err());
+    }
+}
@@ -1,57 +1,339 @@
 use anyhow::{Context, Result, anyhow, bail};
 use cfg_if::cfg_if;
 use log::debug;
+use std::ffi::OsString;
 use std::path::{Path, PathBuf};
 use std::sync::mpsc;
-use std::time::Instant;
+use std::time::{Duration, Instant};
 use tempdir::TempDir;
 use tokio::io::AsyncWriteExt;
 
+use crate::config::DownloadConfig;
 use crate::env::Environment;
-use crate::install::{InstallProgress, InstallTask};
+use crate::install::cache::DownloadCache;
+use crate::install::checksum::{ChecksumVerifier, verify_file_sha256};
+use crate::install::retry::RetryPolicy;
+use crate::install::signature::verify_signature;
+use crate::install::{InstallProgress, InstallTask, archive};
 use crate::process::builder::LockedNotification;
 use crate::process::{ProcessBuilder, ProcessError};
-use crate::registry::DownloadFileFormat;
+use crate::registry::{BuildSystem, DownloadFileFormat, ToolDownloadInstructions};
+use crate::util::which_opt;
 
 /// Inner implementation of [`run_install_task`] function in [`Environment`]
 /// where the task must be [`InstallTask::Download`] in order to perform
 /// this function.
 ///
+/// Installers are run once they are downloaded. Archives are extracted
+/// into the managed tools directory instead and their executables are
+/// exposed in the [bin directory](crate::util::ProgramDirs::bin_dir).
+///
 /// If the variant is different than expected, it will panic.
 pub fn perform_task_via_download(
-    _env: &dyn Environment,
+    env: &dyn Environment,
     task: &InstallTask,
-    _progress_handler: &mut dyn FnMut(InstallProgress),
+    progress_handler: &mut dyn FnMut(InstallProgress),
 ) -> Result<()> {
     let InstallTask::Download {
         instructions,
+        command,
         tool_name,
     } = task
     else {
         panic!("expected task to be InstallTask::Download; got {task:?}")
     };
 
+    let start_time = Instant::now();
+
     // First, we'll add a temporary folder to capture the installer executables.
     let dir = TempDir::new("ctftools_download")?;
-    let downloaded_path = dir.path().join(match instructions.format {
-        DownloadFileFormat::Executable => {
-            if cfg!(windows) {
-                "downloaded.exe"
-            } else {
-                "downloaded.zip"
-            }
+
+    // Downloads with a checksum are kept in the cache instead, so they
+    // can be resumed if interrupted and reused when reinstalling the tool.
+    let cached = env.program_dirs().and_then(|dirs| {
+        let cache = DownloadCache::new(dirs.downloads_cache_dir()?);
+        let path = cache.path_of(instructions)?;
+        Some((cache, path))
+    });
+
+    let downloaded_path = match (cached.as_ref(), instructions.format.extension()) {
+        (Some((_, path)), _) => path.clone(),
+        (None, Some(extension)) => dir.path().join(format!("downloaded.{extension}")),
+        (None, None) => dir.path().join("downloaded"),
+    };
+
+    // Unverifiable downloads are only allowed if the policy permits.
+    check_verifiable(env, instructions, tool_name, progress_handler)?;
+
+    // Cached downloads are written into a partial file first and
+    // skipped entirely if they were already completed.
+    let target = match cached.as_ref() {
+        Some((cache, path)) if reuse_cached_download(cache, path, instructions)? => None,
+        Some((cache, path)) => Some(cache.begin(instructions, path)?),
+        None => Some(downloaded_path.clone()),
+    };
+
+    let resume = cached.is_some();
+    let signature_contents = fetch_download(
+        env,
+        instructions,
+        target.clone(),
+        resume,
+        tool_name,
+        progress_handler,
+    )?;
+
+    if let Some((cache, path)) = cached.as_ref()
+        && target.is_some()
+    {
+        cache.complete(path)?;
+    }
+
+    // Installers must never run if they are not signed by their publisher.
+    if let Some(signature) = instructions.signature.as_ref()
+        && let Some(contents) = signature_contents
+        && let Err(error) = verify_signature(&downloaded_path, signature, &contents)
+    {
+        if let Some((cache, path)) = cached.as_ref() {
+            cache.remove(path)?;
+        }
+        dir.close()?;
+        return Err(error.context(format!("refusing to install {tool_name}")));
+    }
+
+    // Once the download is complete, let's open the file. shall we?
+    install_downloaded_file(
+        env,
+        &downloaded_path,
+        instructions,
+        command,
+        tool_name,
+        progress_handler,
+    )?;
+    dir.close()?;
+
+    // Report success.
+    progress_handler(InstallProgress::Success {
+        elapsed: start_time.elapsed(),
+        tool_name: tool_name.clone(),
+    });
+
+    Ok(())
+}
+
+/// Inner implementation of [`run_install_task`] function in [`Environment`]
+/// where the task must be [`InstallTask::LocalDownload`] in order to perform
+/// this function.
+///
+/// The file is verified with its checksums and signature again before it
+/// is installed the same way as [`perform_task_via_download`] does, so
+/// no network access is needed.
+///
+/// If the variant is different than expected, it will panic.
+pub fn perform_task_via_local_download(
+    env: &dyn Environment,
+    task: &InstallTask,
+    progress_handler: &mut dyn FnMut(InstallProgress),
+) -> Result<()> {
+    let InstallTask::LocalDownload {
+        path,
+        signature,
+        instructions,
+        command,
+        tool_name,
+    } = task
+    else {
+        panic!("expected task to be InstallTask::LocalDownload; got {task:?}")
+    };
+
+    let start_time = Instant::now();
+    check_verifiable(env, instructions, tool_name, progress_handler)?;
+    verify_local_download(path, signature.as_deref(), instructions)
+        .with_context(|| format!("refusing to install {tool_name}"))?;
+
+    install_downloaded_file(
+        env,
+        path,
+        instructions,
+        command,
+        tool_name,
+        progress_handler,
+    )?;
+
+    // Report success.
+    progress_handler(InstallProgress::Success {
+        elapsed: start_time.elapsed(),
+        tool_name: tool_name.clone(),
+    });
+
+    Ok(())
+}
+
+/// Inner implementation of [`run_install_task`] function in [`Environment`]
+/// where the task must be [`InstallTask::PackageFiles`] in order to perform
+/// this function.
+///
+/// The package files are verified with their checksums before they are
+/// installed.
+///
+/// If the variant is different than expected, it will panic.
+pub fn perform_task_via_package_files(
+    env: &dyn Environment,
+    task: &InstallTask,
+    progress_handler: &mut dyn FnMut(InstallProgress),
+) -> Result<()> {
+    let InstallTask::PackageFiles { files, tool_name } = task else {
+        panic!("expected task to be InstallTask::PackageFiles; got {task:?}")
+    };
+
+    let start_time = Instant::now();
+    for file in files.iter() {
+        verify_file_sha256(&file.path, &file.sha256)
+            .with_context(|| format!("refusing to install {tool_name}"))?;
+    }
+
+    let paths = files.iter().map(|v| v.path.clone()).collect::<Vec<_>>();
+    install_package_files(env, &paths, tool_name, progress_handler)?;
+
+    // Report success.
+    progress_handler(InstallProgress::Success {
+        elapsed: start_time.elapsed(),
+        tool_name: tool_name.clone(),
+    });
+
+    Ok(())
+}
+
+/// Downloads a file into `path` without installing it, such as to put
+/// it into an [installation bundle](crate::install::bundle).
+///
+/// The file is verified with its checksums and signature the same way as
+/// [`perform_task_via_download`] does. Returns the contents of its
+/// detached signature, if any, so it can be verified again later.
+pub fn download_file(
+    env: &dyn Environment,
+    instructions: &ToolDownloadInstructions,
+    path: &Path,
+    tool_name: &str,
+    progress_handler: &mut dyn FnMut(InstallProgress),
+) -> Result<Option<String>> {
+    check_verifiable(env, instructions, tool_name, progress_handler)?;
+
+    let target = Some(path.to_path_buf());
+    let signature_contents = fetch_download(
+        env,
+        instructions,
+        target,
+        false,
+        tool_name,
+        progress_handler,
+    )?;
+
+    if let Some(signature) = instructions.signature.as_ref()
+        && let Some(contents) = signature_contents.as_deref()
+        && let Err(error) = verify_signature(path, signature, contents)
+    {
+        std::fs::remove_file(path)
+            .with_context(|| format!("could not remove {}", path.display()))?;
+        return Err(error);
+    }
+
+    Ok(signature_contents)
+}
+
+/// Downloads the package files of a package, along with its dependencies
+/// that are not installed yet, into `dir` with the environment's package
+/// manager without installing them.
+///
+/// Returns the paths to the downloaded package files.
+pub fn download_package_files(
+    env: &dyn Environment,
+    package: &str,
+    dir: &Path,
+    tool_name: &str,
+    progress_handler: &mut dyn FnMut(InstallProgress),
+) -> Result<Vec<PathBuf>> {
+    let Some((pkg_manager, exec)) = env.pkg_manager() else {
+        bail!("a package manager is required to download the package of {tool_name}");
+    };
+    let Some(builder) = pkg_manager.download_only_command(&exec, package, dir) else {
+        bail!(
+            "{} cannot download package files",
+            pkg_manager.as_display_name()
+        );
+    };
+
+    // APT expects a directory for incomplete downloads in its archives directory.
+    let partial = dir.join("partial");
+    std::fs::create_dir_all(&partial)
+        .with_context(|| format!("could not create {}", partial.display()))?;
+
+    let needs_privilege = pkg_manager.download_needs_privilege();
+    run_install_command(env, builder, needs_privilege, tool_name, progress_handler)?;
+
+    let mut files = Vec::new();
+    for entry in std::fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.is_file() && path.extension().is_some_and(|v| v == "deb" || v == "rpm") {
+            files.push(path);
         }
-        DownloadFileFormat::ZIP => "downloaded.zip",
+    }
+
+    if files.is_empty() {
+        bail!("{package} package was not downloaded");
+    }
+
+    files.sort();
+    Ok(files)
+}
+
+/// Refuses to download a file that has no checksum or signature to
+/// verify it with if the configuration requires one. Otherwise, it is
+/// reported as [`InstallProgress::UnverifiedDownload`].
+fn check_verifiable(
+    env: &dyn Environment,
+    instructions: &ToolDownloadInstructions,
+    tool_name: &str,
+    progress_handler: &mut dyn FnMut(InstallProgress),
+) -> Result<()> {
+    if instructions.is_verifiable() {
+        return Ok(());
+    }
+
+    if env.config().downloads.require_checksums {
+        bail!(
+            "refusing to install {tool_name} from {} without a checksum or signature",
+            instructions.url
+        );
+    }
+    progress_handler(InstallProgress::UnverifiedDownload {
+        url: instructions.url.clone(),
+        tool_name: tool_name.to_string(),
     });
+    Ok(())
+}
 
+/// Downloads a file into `target`, if set, from the first of its URLs
+/// that works, along with its detached signature if it has any.
+///
+/// The download is done in a worker thread while its progress
+/// is reported to `progress_handler`.
+fn fetch_download(
+    env: &dyn Environment,
+    instructions: &ToolDownloadInstructions,
+    target: Option<PathBuf>,
+    resume: bool,
+    tool_name: &str,
+    progress_handler: &mut dyn FnMut(InstallProgress),
+) -> Result<Option<String>> {
     // Unfortunately, this part requires a bit of an async action but we have
     // our channel to send progress messages in the async thread.
-    let (tx, _rx) = mpsc::channel::<InstallProgress>();
-    let tool_name = tool_name.clone();
-    let url = instructions.url.clone();
+    let (tx, rx) = mpsc::channel::<InstallProgress>();
+    let config = env.config().downloads.clone();
 
     let handle = std::thread::spawn({
-        let downloaded_path = downloaded_path.clone();
+        let tool_name = tool_name.to_string();
+        let instructions = instructions.clone();
         move || {
             let rt = tokio::runtime::Builder::new_current_thread()
                 .thread_name(format!("ctftools-download-worker-{tool_name}"))
@@ -60,24 +342,225 @@ pub fn perform_task_via_download(
                 .build()
                 .expect("failed to build tokio runtime for download worker");
 
-            rt.block_on(download_file_from_url(&tx, downloaded_path, url))
+            let client = http_client(&config)?;
+            let policy = RetryPolicy::new(&config);
+            let urls: Vec<&str> = instructions.urls().collect();
+
+            rt.block_on(async {
+                if let Some(target) = target {
+                    policy
+                        .run(&urls, async |url| {
+                            let verifier = ChecksumVerifier::new(&instructions);
+                            let path = target.clone();
+                            download_file_from_url(
+                                &tx, &client, path, url, &tool_name, verifier, resume,
+                            )
+                            .await
+                        })
+                        .await?;
+                }
+                match instructions.signature.as_ref() {
+                    Some(signature) => policy
+                        .run(&[&signature.url], async |url| {
+                            download_text_from_url(&client, url).await
+                        })
+                        .await
+                        .map(Some),
+                    None => Ok(None),
+                }
+            })
         }
     });
 
+    // The channel is closed once the worker is done with it.
+    for progress in rx {
+        progress_handler(progress);
+    }
+
     handle
         .join()
         .map_err(|_| anyhow!("failed to spawn download worker"))?
-        .context("failed to download file")?;
+        .context("failed to download file")
+}
 
-    // Once the download is complete, let's open the file. shall we?
+/// Verifies a file that was downloaded beforehand with
+/// its checksums and its detached signature, if any.
+fn verify_local_download(
+    path: &Path,
+    signature_contents: Option<&str>,
+    instructions: &ToolDownloadInstructions,
+) -> Result<()> {
+    if instructions.has_checksum() {
+        let mut verifier = ChecksumVerifier::new(instructions);
+        verifier.update_from_file(path)?;
+        verifier.verify(&instructions.url)?;
+    }
+
+    if let Some(signature) = instructions.signature.as_ref() {
+        let Some(contents) = signature_contents else {
+            bail!("the signature from {} is missing", signature.url);
+        };
+        verify_signature(path, signature, contents)?;
+    }
+
+    Ok(())
+}
+
+/// Installs a downloaded file by its format. Installers are run, while
+/// other files are placed into the managed tools directory.
+fn install_downloaded_file(
+    env: &dyn Environment,
+    path: &Path,
+    instructions: &ToolDownloadInstructions,
+    command: &str,
+    tool_name: &str,
+    progress_handler: &mut dyn FnMut(InstallProgress),
+) -> Result<()> {
     match instructions.format {
         DownloadFileFormat::Executable => {
-            try_open_executable(&downloaded_path)?;
+            set_executable(path)?;
+            try_open_executable(path)?;
+        }
+        DownloadFileFormat::AppImage | DownloadFileFormat::Binary => {
+            let (tools_dir, bin_dir) = install_dirs(env, tool_name)?;
+            install_binary(&tools_dir, &bin_dir, path, &instructions.format, command)?;
+        }
+        DownloadFileFormat::Deb | DownloadFileFormat::Rpm => {
+            install_package_files(env, &[path.to_path_buf()], tool_name, progress_handler)?;
+        }
+        DownloadFileFormat::ZIP
+        | DownloadFileFormat::TarGz
+        | DownloadFileFormat::TarXz
+        | DownloadFileFormat::TarZst => {
+            let (tools_dir, bin_dir) = install_dirs(env, tool_name)?;
+            let dest = archive::tool_dir(&tools_dir, command)?;
+            archive::extract_archive(path, &instructions.format, &tools_dir, &dest)?;
+
+            // The first executable is exposed under the tool's command
+            // so it can always be found by `find_tool_executable`.
+            let entrypoints = archive::find_entrypoints(&dest, &instructions.bin, command)?;
+            for (idx, entrypoint) in entrypoints.iter().enumerate() {
+                let name = match entrypoint.file_name() {
+                    Some(name) if idx > 0 => name.to_string_lossy(),
+                    _ => command.into(),
+                };
+                archive::expose_executable(entrypoint, &bin_dir, &name)?;
+            }
         }
-        DownloadFileFormat::ZIP => todo!(),
     }
+    Ok(())
+}
 
-    dir.close()?;
+/// Checks whether a completed download in the cache can be reused.
+///
+/// The cached file is verified again since it could have been modified
+/// after it was downloaded. It is removed from the cache if it fails.
+fn reuse_cached_download(
+    cache: &DownloadCache,
+    path: &Path,
+    instructions: &ToolDownloadInstructions,
+) -> Result<bool> {
+    if !path.is_file() {
+        return Ok(false);
+    }
+
+    let mut verifier = ChecksumVerifier::new(instructions);
+    verifier.update_from_file(path)?;
+    match verifier.verify(&instructions.url) {
+        Ok(()) => {
+            debug!("reusing cached download at {}", path.display());
+            Ok(true)
+        }
+        Err(error) => {
+            debug!("discarding cached download: {error:#}");
+            cache.remove(path)?;
+            Ok(false)
+        }
+    }
+}
+
+/// Gets the directories where a downloaded tool is placed into and exposed from.
+fn install_dirs(env: &dyn Environment, tool_name: &str) -> Result<(PathBuf, PathBuf)> {
+    env.program_dirs()
+        .and_then(|dirs| Some((dirs.tools_dir()?, dirs.bin_dir()?)))
+        .with_context(|| format!("could not find the directory to install {tool_name} into"))
+}
+
+/// Places a downloaded AppImage or standalone executable into the
+/// managed tools directory and exposes it under the tool's command.
+fn install_binary(
+    tools_dir: &Path,
+    bin_dir: &Path,
+    path: &Path,
+    format: &DownloadFileFormat,
+    command: &str,
+) -> Result<PathBuf> {
+    let dest_dir = archive::tool_dir(tools_dir, command)?;
+    if dest_dir.exists() {
+        std::fs::remove_dir_all(&dest_dir)
+            .with_context(|| format!("could not remove {}", dest_dir.display()))?;
+    }
+    std::fs::create_dir_all(&dest_dir)
+        .with_context(|| format!("could not create {}", dest_dir.display()))?;
+
+    let dest = match format.extension() {
+        Some(extension) => dest_dir.join(format!("{command}.{extension}")),
+        None => dest_dir.join(command),
+    };
+
+    // The downloaded file may live in another file system, so it is copied.
+    std::fs::copy(path, &dest).with_context(|| format!("could not copy to {}", dest.display()))?;
+    archive::expose_executable(&dest, bin_dir, command)
+}
+
+/// Installs downloaded `.deb` or `.rpm` packages with the environment's
+/// package manager if it supports the files, or with `dpkg` or `rpm` otherwise.
+fn install_package_files(
+    env: &dyn Environment,
+    files: &[PathBuf],
+    tool_name: &str,
+    progress_handler: &mut dyn FnMut(InstallProgress),
+) -> Result<()> {
+    let builder = env
+        .pkg_manager()
+        .and_then(|(pm, exec)| pm.install_files_command(&exec, files));
+
+    let builder = match builder {
+        Some(builder) => builder,
+        None => {
+            let extension = files.first().and_then(|v| v.extension());
+            let program = match extension.and_then(|v| v.to_str()) {
+                Some("deb") => "dpkg",
+                Some("rpm") => "rpm",
+                _ => bail!("there are no package files to install {tool_name} from"),
+            };
+            if files.iter().any(|v| v.extension() != extension) {
+                bail!("the package files of {tool_name} must be of the same format");
+            }
+            let Some(exec) = which_opt(program)? else {
+                bail!("{program} is required to install the downloaded package of {tool_name}");
+            };
+
+            let mut builder = ProcessBuilder::new(exec);
+            builder.arg("--install").args(files);
+            builder
+        }
+    };
+
+    run_install_command(env, builder, true, tool_name, progress_handler)
+}
+
+/// Allows a downloaded installer to be run in Unix-like systems.
+fn set_executable(path: &Path) -> Result<()> {
+    cfg_if! {
+        if #[cfg(unix)] {
+            use std::os::unix::fs::PermissionsExt;
+            std::fs::set_permissions(path, std::fs::Permissions::from_mode(0o755))
+                .with_context(|| format!("could not make {} executable", path.display()))?;
+        } else {
+            _ = path;
+        }
+    }
     Ok(())
 }
 
@@ -92,22 +575,98 @@ fn try_open_executable(path: &Path) -> Result<()> {
     Ok(())
 }
 
+/// Creates the HTTP client that downloads files with
+/// the timeouts given in the download configuration.
+fn http_client(config: &DownloadConfig) -> Result<reqwest::Client> {
+    reqwest::Client::builder()
+        .connect_timeout(config.connect_timeout())
+        .read_timeout(config.read_timeout())
+        .build()
+        .context("could not create an HTTP client")
+}
+
+/// How often the progress of a download is reported.
+const DOWNLOAD_PROGRESS_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Downloads the file at `url` into `path` and verifies it with
+/// the given checksums while it is being written.
+///
+/// If `resume` is set and a part of the file was already downloaded,
+/// only the rest of it is requested with an HTTP `Range` header. It is
+/// downloaded from the start if the server does not support it.
+///
+/// Its progress is sent as [`InstallProgress::Download`] every
+/// [`DOWNLOAD_PROGRESS_INTERVAL`] and once the download completes.
+/// The file is deleted if it does not match any of the checksums.
 async fn download_file_from_url(
-    _progress_tx: &mpsc::Sender<InstallProgress>,
+    progress_tx: &mpsc::Sender<InstallProgress>,
+    client: &reqwest::Client,
     path: PathBuf,
-    url: String,
+    url: &str,
+    tool_name: &str,
+    mut verifier: ChecksumVerifier,
+    resume: bool,
 ) -> Result<()> {
     debug!("fetching resource: {url}");
 
-    let mut response = reqwest::get(url).await.context("HTTP request failed")?;
-    let mut file = tokio::fs::File::create(&path)
-        .await
-        .context("could not create a temporary downloaded file")?;
+    let partial_len = match tokio::fs::metadata(&path).await {
+        Ok(metadata) if resume => metadata.len(),
+        _ => 0,
+    };
+
+    let mut request = client.get(url);
+    if partial_len > 0 {
+        debug!("resuming download from byte {partial_len}");
+        request = request.header(reqwest::header::RANGE, format!("bytes={partial_len}-"));
+    }
+
+    let mut response = request.send().await.context("HTTP request failed")?;
+    if response.status() == reqwest::StatusCode::RANGE_NOT_SATISFIABLE {
+        debug!("server cannot resume the download; starting over");
+        response = client
+            .get(url)
+            .send()
+            .await
+            .context("HTTP request failed")?;
+    }
+    let mut response = response.error_for_status().context("HTTP request failed")?;
+
+    let resumed = partial_len > 0 && response.status() == reqwest::StatusCode::PARTIAL_CONTENT;
+    let mut file = if resumed {
+        verifier.update_from_file(&path)?;
+        tokio::fs::OpenOptions::new()
+            .append(true)
+            .open(&path)
+            .await
+            .with_context(|| format!("could not open {}", path.display()))?
+    } else {
+        tokio::fs::File::create(&path)
+            .await
+            .context("could not create a temporary downloaded file")?
+    };
 
     debug!("created temporary file: {}", path.display());
 
-    let mut bytes_written = 0usize;
-    let total_bytes = response.content_length().map(|v| v as usize);
+    let resumed_from = if resumed { partial_len } else { 0 };
+    let mut bytes_written = resumed_from;
+    let total_bytes = response.content_length().map(|v| v + resumed_from);
+
+    let start_time = Instant::now();
+    let mut last_reported = start_time;
+
+    // The receiver may be gone if the installation is aborted,
+    // but the download should not fail because of it.
+    let report = |received: u64| {
+        let progress = download_progress(
+            url,
+            received,
+            resumed_from,
+            total_bytes,
+            start_time.elapsed(),
+            tool_name,
+        );
+        _ = progress_tx.send(progress);
+    };
 
     while let Some(bytes) = response.chunk().await? {
         if let Some(total_bytes) = total_bytes {
@@ -115,43 +674,277 @@ async fn download_file_from_url(
         } else {
             debug!("received {bytes_written} byte(s) from stream",);
         }
-        bytes_written += bytes.len();
-        file.write(&bytes).await?;
+        bytes_written += bytes.len() as u64;
+        verifier.update(&bytes);
+        file.write_all(&bytes).await?;
+
+        if last_reported.elapsed() >= DOWNLOAD_PROGRESS_INTERVAL {
+            last_reported = Instant::now();
+            report(bytes_written);
+        }
     }
 
     debug!("downloaded {bytes_written} byte(s)");
     file.flush().await?;
+    drop(file);
+    report(bytes_written);
+
+    if let Err(error) = verifier.verify(url) {
+        tokio::fs::remove_file(&path)
+            .await
+            .with_context(|| format!("could not remove {}", path.display()))?;
+        return Err(error);
+    }
 
     Ok(())
 }
 
+/// Creates an [`InstallProgress::Download`] of a download that has
+/// received `received` bytes after `elapsed` time.
+///
+/// Bytes that were downloaded before the download was resumed
+/// (`resumed_from`) do not count towards its speed.
+fn download_progress(
+    url: &str,
+    received: u64,
+    resumed_from: u64,
+    total: Option<u64>,
+    elapsed: Duration,
+    tool_name: &str,
+) -> InstallProgress {
+    let secs = elapsed.as_secs_f64();
+    let bytes_per_sec = if secs > 0.0 {
+        (received.saturating_sub(resumed_from) as f64 / secs) as u64
+    } else {
+        0
+    };
+
+    let eta = total
+        .filter(|_| bytes_per_sec > 0)
+        .map(|total| Duration::from_secs(total.saturating_sub(received) / bytes_per_sec));
+
+    InstallProgress::Download {
+        url: url.to_string(),
+        received,
+        total,
+        bytes_per_sec,
+        eta,
+        tool_name: tool_name.to_string(),
+    }
+}
+
+/// Downloads a small text file, such as a detached signature.
+async fn download_text_from_url(client: &reqwest::Client, url: &str) -> Result<String> {
+    debug!("fetching resource: {url}");
+
+    let response = client
+        .get(url)
+        .send()
+        .await
+        .and_then(|v| v.error_for_status())
+        .with_context(|| format!("could not download {url}"))?;
+
+    response
+        .text()
+        .await
+        .with_context(|| format!("could not download {url}"))
+}
+
 /// Inner implementation of [`run_install_task`] function in [`Environment`]
-/// where the task must be [`InstallTask::PackageManager`] in order to perform
+/// where the task must be [`InstallTask::Source`] in order to perform
 /// this function.
 ///
+/// The repository is cloned into (or updated in) its cache directory first.
+/// Then, a copy of it is checked out and built in a temporary directory and
+/// the tool is installed into the task's prefix. Output of every command is
+/// reported as [`InstallProgress::Output`].
+///
 /// If the variant is different than expected, it will panic.
-pub fn perform_task_via_pkg_manager(
-    env: &dyn Environment,
+pub fn perform_task_via_source(
     task: &InstallTask,
     progress_handler: &mut dyn FnMut(InstallProgress),
 ) -> Result<()> {
-    let InstallTask::PackageManager {
-        exec,
-        arguments,
-        sudo: needs_privilege,
+    let InstallTask::Source {
+        source,
+        cache_dir,
+        prefix,
         tool_name,
     } = task
     else {
-        panic!("expected task to be InstallTask::PackageManager; got {task:?}")
+        panic!("expected task to be InstallTask::Source; got {task:?}")
     };
 
+    let Some(git) = which_opt("git")? else {
+        bail!("git is required to build {tool_name} from source");
+    };
+    let start_time = Instant::now();
+
+    // Cloned repositories are kept as mirrors so they can be updated later.
+    if cache_dir.join("HEAD").exists() {
+        let mut builder = ProcessBuilder::new(&git);
+        builder.arg("--git-dir").arg(cache_dir);
+        builder.args(&["fetch", "--prune", "origin"]);
+
+        // An outdated copy is still good enough to build from if we're offline.
+        if let Err(error) = run_streaming(&builder, tool_name, progress_handler) {
+            debug!("could not update {}: {error:#}", cache_dir.display());
+        }
+    } else {
+        // It may be left over from an interrupted clone.
+        if cache_dir.exists() {
+            std::fs::remove_dir_all(cache_dir).with_context(|| {
+                format!(
+                    "could not remove incomplete clone at {}",
+                    cache_dir.display()
+                )
+            })?;
+        }
+        if let Some(parent) = cache_dir.parent() {
+            std::fs::create_dir_all(parent)
+                .with_context(|| format!("could not create {}", parent.display()))?;
+        }
+
+        let mut builder = ProcessBuilder::new(&git);
+        builder.args(&["clone", "--mirror", "--"]);
+        builder.arg(&source.git).arg(cache_dir);
+        run_streaming(&builder, tool_name, progress_handler)?;
+    }
+
+    let dir = TempDir::new("ctftools_build")?;
+    let source_dir = dir.path().join("src");
+
+    let mut builder = ProcessBuilder::new(&git);
+    builder.args(&["clone", "--quiet", "--"]);
+    builder.arg(cache_dir).arg(&source_dir);
+    run_streaming(&builder, tool_name, progress_handler)?;
+
+    if let Some(git_ref) = source.git_ref.as_deref() {
+        let mut builder = ProcessBuilder::new(&git);
+        builder.arg("-C").arg(&source_dir);
+        builder.args(&["checkout", "--quiet", git_ref]);
+        run_streaming(&builder, tool_name, progress_handler)?;
+    }
+
+    std::fs::create_dir_all(prefix)
+        .with_context(|| format!("could not create {}", prefix.display()))?;
+
+    for mut builder in build_commands(&source.build_system, prefix) {
+        builder.cwd(&source_dir).env("PREFIX", prefix);
+        run_streaming(&builder, tool_name, progress_handler).with_context(|| {
+            format!(
+                "could not build {tool_name} with {}",
+                source.build_system.as_display_name()
+            )
+        })?;
+    }
+
+    dir.close()?;
+
+    // Report success.
+    progress_handler(InstallProgress::Success {
+        elapsed: start_time.elapsed(),
+        tool_name: tool_name.clone(),
+    });
+
+    Ok(())
+}
+
+/// Creates the commands that build and install a tool into
+/// the `prefix` with the given build system, in order.
+fn build_commands(build_system: &BuildSystem, prefix: &Path) -> Vec<ProcessBuilder> {
+    let jobs = std::thread::available_parallelism().map_or(1, |v| v.get());
+    let with_prefix = |arg: &str| {
+        let mut arg = OsString::from(arg);
+        arg.push(prefix);
+        arg
+    };
+
+    match build_system {
+        BuildSystem::Make => {
+            let mut build = ProcessBuilder::new("make");
+            build.arg(format!("-j{jobs}")).arg(with_prefix("PREFIX="));
+
+            let mut install = ProcessBuilder::new("make");
+            install.arg("install").arg(with_prefix("PREFIX="));
+            vec![build, install]
+        }
+        BuildSystem::CMake => {
+            let mut configure = ProcessBuilder::new("cmake");
+            configure
+                .args(&["-S", ".", "-B", "build", "-DCMAKE_BUILD_TYPE=Release"])
+                .arg(with_prefix("-DCMAKE_INSTALL_PREFIX="));
+
+            let mut build = ProcessBuilder::new("cmake");
+            build.args(&["--build", "build", "--parallel", &jobs.to_string()]);
+
+            let mut install = ProcessBuilder::new("cmake");
+            install.args(&["--install", "build"]);
+            vec![configure, build, install]
+        }
+        BuildSystem::Cargo => {
+            let mut install = ProcessBuilder::new("cargo");
+            install
+                .args(&["install", "--path", ".", "--root"])
+                .arg(prefix);
+            vec![install]
+        }
+        BuildSystem::Custom(lines) => {
+            let (shell, flag) = if cfg!(windows) {
+                ("cmd", "/C")
+            } else {
+                ("sh", "-c")
+            };
+
+            lines
+                .iter()
+                .filter(|v| !v.trim().is_empty())
+                .map(|line| {
+                    let mut builder = ProcessBuilder::new(shell);
+                    builder.arg(flag).arg(line);
+                    builder
+                })
+                .collect()
+        }
+    }
+}
+
+/// Runs a command of an installation and reports every
+/// line of its output to the progress handler.
+fn run_streaming(
+    builder: &ProcessBuilder,
+    tool_name: &str,
+    progress_handler: &mut dyn FnMut(InstallProgress),
+) -> Result<()> {
+    debug!("executing: {builder}");
+    progress_handler(InstallProgress::Command {
+        text: builder.to_string(),
+        tool_name: tool_name.to_string(),
+    });
+
+    builder.exec_with_streaming(&mut |line| {
+        progress_handler(InstallProgress::Output {
+            line: line.to_string(),
+            tool_name: tool_name.to_string(),
+        });
+    })
+}
+
+/// Runs a command that installs a tool, wrapping it with `sudo` if it
+/// needs elevated privileges, while blocking the first interrupt signal.
+fn run_install_command(
+    env: &dyn Environment,
+    mut builder: ProcessBuilder,
+    needs_privilege: bool,
+    tool_name: &str,
+    progress_handler: &mut dyn FnMut(InstallProgress),
+) -> Result<()> {
     // Check if this command requires elevated privileges.
     //
     // If so, verify whether the current process is running with sufficient privileges.
     //
     // If the process is not elevated and the OS does not support privilege escalation,
     // return an informative error message prompting the user to run with elevated privileges.
-    if *needs_privilege && !env.running_in_elevation() && !env.supports_privilege_escalation() {
+    if needs_privilege && !env.running_in_elevation() && !env.supports_privilege_escalation() {
         cfg_if! {
             if #[cfg(target_os = "windows")] {
                 bail!("Please run your terminal as administrator to allow memotools to install missing tools.");
@@ -161,20 +954,16 @@ pub fn perform_task_via_pkg_manager(
         }
     }
 
-    let mut builder = ProcessBuilder::new(exec);
-    builder.args(arguments);
-
-    if *needs_privilege && cfg!(unix) {
+    if needs_privilege && cfg!(unix) {
         builder.wrap(Some("sudo"));
     }
 
     let cmd_text = builder.to_string();
-    let start_time = Instant::now();
 
     // Set up a flag that will be set to `true` when a `SIGINT` signal is received.
     progress_handler(InstallProgress::Command {
         text: cmd_text.clone(),
-        tool_name: tool_name.clone(),
+        tool_name: tool_name.to_string(),
     });
 
     let output = builder.exec_locked(&mut |notification| match notification {
@@ -195,6 +984,125 @@ pub fn perform_task_via_pkg_manager(
         .into());
     }
 
+    Ok(())
+}
+
+/// Inner implementation of [`run_install_task`] function in [`Environment`]
+/// where the task must be [`InstallTask::AUR`] in order to perform
+/// this function.
+///
+/// This is used when there is no AUR helper installed. The package is
+/// cloned from the [configured AUR](crate::config::AurConfig) into a
+/// temporary directory and its `PKGBUILD` is reported as
+/// [`InstallProgress::BuildScript`] for the user to review. It is only
+/// built and installed with `makepkg -si` once `confirm` returns `true`
+/// for it.
+///
+/// `makepkg` refuses to run as root, so this fails if ctftools is
+/// running with elevated privileges.
+///
+/// If the variant is different than expected, it will panic.
+pub fn perform_task_via_aur(
+    env: &dyn Environment,
+    task: &InstallTask,
+    confirm: &mut dyn FnMut(&str, &str) -> Result<bool>,
+    progress_handler: &mut dyn FnMut(InstallProgress),
+) -> Result<()> {
+    let InstallTask::AUR {
+        package_name,
+        tool_name,
+    } = task
+    else {
+        panic!("expected task to be InstallTask::AUR; got {task:?}")
+    };
+
+    if env.running_in_elevation() {
+        bail!(
+            "makepkg cannot be run as root to install {tool_name} from the AUR. Please run \
+            ctftools as a regular user or install an AUR helper such as paru or yay."
+        );
+    }
+    if !env.supports_privilege_escalation() {
+        bail!("sudo is required for makepkg to install {tool_name} from the AUR");
+    }
+
+    let Some(git) = which_opt("git")? else {
+        bail!("git is required to install {tool_name} from the AUR");
+    };
+    let start_time = Instant::now();
+
+    let dir = TempDir::new("ctftools_aur")?;
+    let package_dir = dir.path().join(package_name);
+    let url = env.config().aur.clone_url(package_name);
+
+    let mut builder = ProcessBuilder::new(&git);
+    builder.args(&["clone", "--depth", "1", "--"]);
+    builder.arg(&url).arg(&package_dir);
+    run_streaming(&builder, tool_name, progress_handler)?;
+
+    // Cloning a package that does not exist gives an empty repository.
+    let pkgbuild = package_dir.join("PKGBUILD");
+    if !pkgbuild.is_file() {
+        bail!("{package_name} package was not found in the AUR at {url}");
+    }
+
+    let contents = std::fs::read_to_string(&pkgbuild)
+        .with_context(|| format!("could not read {}", pkgbuild.display()))?;
+    progress_handler(InstallProgress::BuildScript {
+        name: "PKGBUILD".to_string(),
+        contents,
+        tool_name: tool_name.clone(),
+    });
+    if !confirm("PKGBUILD", tool_name)? {
+        bail!("installing {tool_name} from the AUR was cancelled after reviewing its PKGBUILD");
+    }
+
+    let Some(makepkg) = which_opt("makepkg")? else {
+        bail!("makepkg is required to install {tool_name} from the AUR");
+    };
+
+    // makepkg runs sudo by itself to install the built package.
+    let mut builder = ProcessBuilder::new(makepkg);
+    builder.arg("-si").cwd(&package_dir);
+    run_install_command(env, builder, false, tool_name, progress_handler)?;
+
+    dir.close()?;
+
+    // Report success.
+    progress_handler(InstallProgress::Success {
+        elapsed: start_time.elapsed(),
+        tool_name: tool_name.clone(),
+    });
+
+    Ok(())
+}
+
+/// Inner implementation of [`run_install_task`] function in [`Environment`]
+/// where the task must be [`InstallTask::PackageManager`] in order to perform
+/// this function.
+///
+/// If the variant is different than expected, it will panic.
+pub fn perform_task_via_pkg_manager(
+    env: &dyn Environment,
+    task: &InstallTask,
+    progress_handler: &mut dyn FnMut(InstallProgress),
+) -> Result<()> {
+    let InstallTask::PackageManager {
+        exec,
+        arguments,
+        sudo: needs_privilege,
+        tool_name,
+        ..
+    } = task
+    else {
+        panic!("expected task to be InstallTask::PackageManager; got {task:?}")
+    };
+
+    let start_time = Instant::now();
+    let mut builder = ProcessBuilder::new(exec);
+    builder.args(arguments);
+    run_install_command(env, builder, *needs_privilege, tool_name, progress_handler)?;
+
     // Report success.
     progress_handler(InstallProgress::Success {
         elapsed: start_time.elapsed(),
@@ -203,3 +1111,481 @@ pub fn perform_task_via_pkg_manager(
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+    use std::path::Path;
+    use std::time::Duration;
+    use tempdir::TempDir;
+
+    use crate::env::MockEnvironment;
+    use crate::install::{InstallProgress, InstallTask};
+    use crate::process::ProcessBuilder;
+    use crate::registry::{BuildSystem, ToolSourceMetadata};
+    use crate::testing::{Request, Response, serve};
+
+    use super::{
+        download_file_from_url, download_progress, install_binary, perform_task_via_aur,
+        perform_task_via_package_files, perform_task_via_source,
+    };
+
+    fn git(dir: &Path, args: &[&str]) {
+        let mut builder = ProcessBuilder::new("git");
+        builder.args(&[
+            "-c",
+            "user.name=ctftools",
+            "-c",
+            "user.email=ctftools@localhost",
+        ]);
+        builder.args(args).cwd(dir);
+        builder.exec_with_output().unwrap();
+    }
+
+    fn commit_hello(work: &Path, message: &str) {
+        std::fs::write(work.join("hello.sh"), format!("echo {message}\n")).unwrap();
+        git(work, &["add", "."]);
+        git(work, &["commit", "--quiet", "-m", message]);
+        git(work, &["push", "--quiet", "--tags", "origin", "main"]);
+    }
+
+    /// Runs the task and returns the lines of output it reported
+    /// if the tool was installed successfully.
+    fn run_task(task: &InstallTask) -> Vec<String> {
+        let mut lines = Vec::new();
+        let mut succeeded = false;
+
+        perform_task_via_source(task, &mut |progress| match progress {
+            InstallProgress::Output { line, .. } => lines.push(line),
+            InstallProgress::Success { .. } => succeeded = true,
+            _ => {}
+        })
+        .unwrap();
+
+        assert!(succeeded);
+        lines
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_build_from_local_bare_repository() {
+        let dir = TempDir::new("ctftools_test").unwrap();
+        let remote = dir.path().join("remote.git");
+        let work = dir.path().join("work");
+        let prefix = dir.path().join("prefix");
+        let installed = prefix.join("bin").join("hello");
+
+        git(
+            dir.path(),
+            &["init", "--quiet", "--bare", "-b", "main", "remote.git"],
+        );
+        git(dir.path(), &["init", "--quiet", "-b", "main", "work"]);
+        git(
+            &work,
+            &["remote", "add", "origin", remote.to_str().unwrap()],
+        );
+
+        std::fs::write(
+            work.join("Makefile"),
+            "all:\n\techo building with make\n\n\
+            install:\n\tmkdir -p $(PREFIX)/bin\n\tcp hello.sh $(PREFIX)/bin/hello\n",
+        )
+        .unwrap();
+        commit_hello(&work, "v1");
+        git(&work, &["tag", "v1"]);
+        commit_hello(&work, "v2");
+        git(&work, &["push", "--quiet", "--tags", "origin"]);
+
+        let mut source = ToolSourceMetadata::builder()
+            .git(remote.to_string_lossy().into_owned())
+            .git_ref("v1".into())
+            .build_system(BuildSystem::Custom(vec![
+                "echo building".into(),
+                "mkdir -p \"$PREFIX/bin\"".into(),
+                "cp hello.sh \"$PREFIX/bin/hello\"".into(),
+            ]))
+            .build();
+
+        let cache_dir = dir.path().join("cache").join("hello");
+        let task = |source: ToolSourceMetadata| InstallTask::Source {
+            source,
+            cache_dir: cache_dir.clone(),
+            prefix: prefix.clone(),
+            tool_name: "hello".into(),
+        };
+
+        let lines = run_task(&task(source.clone()));
+        assert!(lines.iter().any(|v| v == "building"));
+        assert!(cache_dir.join("HEAD").exists());
+        assert_eq!(std::fs::read_to_string(&installed).unwrap(), "echo v1\n");
+
+        // Later builds should pick up new commits from the cached repository.
+        commit_hello(&work, "v3");
+        source.git_ref = None;
+        source.build_system = BuildSystem::Make;
+
+        let lines = run_task(&task(source));
+        assert!(lines.iter().any(|v| v == "building with make"));
+        assert_eq!(std::fs::read_to_string(&installed).unwrap(), "echo v3\n");
+    }
+
+    #[test]
+    fn test_aur_refuses_to_run_as_root() {
+        let task = InstallTask::AUR {
+            package_name: "foo-bin".into(),
+            tool_name: "foo".into(),
+        };
+
+        let env = MockEnvironment::builder()
+            .running_in_elevation(true)
+            .build();
+        let error =
+            perform_task_via_aur(&env, &task, &mut |_, _| Ok(true), &mut |_| {}).unwrap_err();
+        assert!(error.to_string().contains("makepkg cannot be run as root"));
+
+        let env = MockEnvironment::builder()
+            .running_in_elevation(false)
+            .supports_privilege_escalation(false)
+            .build();
+        let error =
+            perform_task_via_aur(&env, &task, &mut |_, _| Ok(true), &mut |_| {}).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "sudo is required for makepkg to install foo from the AUR"
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_aur_cancelled_after_reviewing_pkgbuild() {
+        use crate::config::Config;
+
+        let dir = TempDir::new("ctftools_test").unwrap();
+        let work = dir.path().join("work");
+        git(dir.path(), &["init", "--quiet", "-b", "main", "work"]);
+        std::fs::write(work.join("PKGBUILD"), "pkgname=foo-bin\n").unwrap();
+        git(&work, &["add", "."]);
+        git(&work, &["commit", "--quiet", "-m", "init"]);
+        git(
+            dir.path(),
+            &["clone", "--quiet", "--bare", "work", "foo-bin.git"],
+        );
+
+        let mut config = Config::default();
+        config.aur.base_url = dir.path().to_string_lossy().into_owned();
+        let env = MockEnvironment::builder()
+            .config(config)
+            .running_in_elevation(false)
+            .supports_privilege_escalation(true)
+            .build();
+
+        let task = InstallTask::AUR {
+            package_name: "foo-bin".into(),
+            tool_name: "foo".into(),
+        };
+
+        let mut reviewed = None;
+        let mut asked = Vec::new();
+        let error = perform_task_via_aur(
+            &env,
+            &task,
+            &mut |name, tool_name| {
+                asked.push(format!("{name} of {tool_name}"));
+                Ok(false)
+            },
+            &mut |progress| {
+                if let InstallProgress::BuildScript { contents, .. } = progress {
+                    reviewed = Some(contents);
+                }
+            },
+        )
+        .unwrap_err();
+
+        assert_eq!(reviewed.as_deref(), Some("pkgname=foo-bin\n"));
+        assert_eq!(asked, ["PKGBUILD of foo"]);
+        assert_eq!(
+            error.to_string(),
+            "installing foo from the AUR was cancelled after reviewing its PKGBUILD"
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_install_binary() {
+        use crate::registry::DownloadFileFormat;
+        use crate::util::ProgramDirs;
+
+        let dir = TempDir::new("ctftools_test").unwrap();
+        let dirs = ProgramDirs::from_root(dir.path());
+        let (tools_dir, bin_dir) = (dirs.tools_dir().unwrap(), dirs.bin_dir().unwrap());
+        let downloaded = dir.path().join("downloaded");
+        std::fs::write(&downloaded, "#!/bin/sh\necho hello\n").unwrap();
+
+        let exposed = install_binary(
+            &tools_dir,
+            &bin_dir,
+            &downloaded,
+            &DownloadFileFormat::Binary,
+            "hello",
+        )
+        .unwrap();
+        assert_eq!(exposed, bin_dir.join("hello"));
+        assert!(tools_dir.join("hello").join("hello").is_file());
+
+        let output = ProcessBuilder::new(&exposed).exec_with_output().unwrap();
+        assert_eq!(String::from_utf8_lossy(&output.stdout), "hello\n");
+
+        // AppImages keep their extension in the tools directory.
+        let exposed = install_binary(
+            &tools_dir,
+            &bin_dir,
+            &downloaded,
+            &DownloadFileFormat::AppImage,
+            "hello",
+        )
+        .unwrap();
+        assert_eq!(
+            std::fs::read_link(exposed).unwrap(),
+            tools_dir.join("hello").join("hello.AppImage")
+        );
+
+        // Other directories must never be replaced by a tool.
+        let result = install_binary(
+            &tools_dir,
+            &bin_dir,
+            &downloaded,
+            &DownloadFileFormat::Binary,
+            "..",
+        );
+        assert!(result.is_err());
+        assert!(tools_dir.join("hello").exists());
+    }
+
+    #[test]
+    fn test_refuse_corrupted_package_files() {
+        use crate::install::PackageFile;
+
+        let dir = TempDir::new("ctftools_test").unwrap();
+        let path = dir.path().join("foo.deb");
+        std::fs::write(&path, b"corrupted").unwrap();
+
+        let task = InstallTask::PackageFiles {
+            files: vec![PackageFile {
+                path,
+                sha256: "0".repeat(64),
+            }],
+            tool_name: "foo".into(),
+        };
+        let env = MockEnvironment::builder().build();
+        let error = perform_task_via_package_files(&env, &task, &mut |_| {}).unwrap_err();
+
+        assert_eq!(error.to_string(), "refusing to install foo");
+        assert!(format!("{error:#}").contains("checksum mismatch"));
+    }
+
+    #[test]
+    fn test_download_progress() {
+        let progress = download_progress(
+            "https://foo.local/tool",
+            1024,
+            0,
+            Some(4096),
+            Duration::from_secs(2),
+            "Tool",
+        );
+
+        let InstallProgress::Download {
+            received,
+            total,
+            bytes_per_sec,
+            eta,
+            ..
+        } = progress
+        else {
+            panic!("expected download progress; got {progress:?}");
+        };
+        assert_eq!(received, 1024);
+        assert_eq!(total, Some(4096));
+        assert_eq!(bytes_per_sec, 512);
+        assert_eq!(eta, Some(Duration::from_secs(6)));
+
+        // Nothing can be estimated before any time has passed
+        // or without knowing the size of the file.
+        let progress = download_progress("x", 0, 0, Some(4096), Duration::ZERO, "Tool");
+        assert!(matches!(
+            progress,
+            InstallProgress::Download {
+                bytes_per_sec: 0,
+                eta: None,
+                ..
+            }
+        ));
+
+        // Resumed bytes are not part of the speed.
+        let progress =
+            download_progress("x", 3072, 2048, Some(4096), Duration::from_secs(1), "Tool");
+        assert!(matches!(
+            progress,
+            InstallProgress::Download {
+                bytes_per_sec: 1024,
+                eta: Some(eta),
+                ..
+            } if eta == Duration::from_secs(1)
+        ));
+
+        let progress = download_progress("x", 1024, 0, None, Duration::from_secs(1), "Tool");
+        assert!(matches!(
+            progress,
+            InstallProgress::Download {
+                bytes_per_sec: 1024,
+                eta: None,
+                ..
+            }
+        ));
+    }
+
+    /// Serves `contents` over HTTP for each of the given number of requests,
+    /// honoring the `Range` header if `ranges` is set.
+    fn serve_file(
+        contents: &'static [u8],
+        requests: usize,
+        ranges: bool,
+    ) -> (String, std::thread::JoinHandle<Vec<Request>>) {
+        let (url, handle) = serve(requests, move |request| match range_start(request) {
+            Some(start) if ranges => Response {
+                status: "206 Partial Content",
+                body: contents[start as usize..].to_vec(),
+            },
+            _ => Response::ok(contents),
+        });
+        (format!("{url}/tool"), handle)
+    }
+
+    /// Gets where the requested range starts, if any.
+    fn range_start(request: &Request) -> Option<u64> {
+        let range = request.header("range")?.strip_prefix("bytes=")?;
+        range.trim_end_matches('-').parse().ok()
+    }
+
+    /// Responds to each of the given number of requests with
+    /// an empty body and the given status, such as `503 Service Unavailable`.
+    fn serve_status(
+        status: &'static str,
+        requests: usize,
+    ) -> (String, std::thread::JoinHandle<Vec<Request>>) {
+        let (url, handle) = serve(requests, move |_| Response::status(status));
+        (format!("{url}/tool"), handle)
+    }
+
+    fn download(path: &Path, url: &str, sha256: &str, resume: bool) -> anyhow::Result<()> {
+        use crate::install::checksum::ChecksumVerifier;
+        use crate::registry::{DownloadFileFormat, ToolDownloadInstructions};
+
+        let instructions = ToolDownloadInstructions::builder()
+            .format(DownloadFileFormat::Binary)
+            .url(url.into())
+            .sha256(sha256.into())
+            .build();
+
+        let (tx, _rx) = std::sync::mpsc::channel();
+        let rt = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .unwrap();
+
+        rt.block_on(download_file_from_url(
+            &tx,
+            &reqwest::Client::new(),
+            path.to_path_buf(),
+            url,
+            "Tool",
+            ChecksumVerifier::new(&instructions),
+            resume,
+        ))
+    }
+
+    #[test]
+    fn test_resume_download() {
+        // Digest of "hello\n".
+        const SHA256: &str = "5891b5b522d5df086d0ff0b110fbd9d21bb4fc7163af34d08286a2e846f6be03";
+
+        let dir = TempDir::new("ctftools_test").unwrap();
+        let path = dir.path().join("downloaded.part");
+
+        // Only the rest of the file is requested.
+        std::fs::write(&path, b"hel").unwrap();
+        let (url, server) = serve_file(b"hello\n", 1, true);
+        download(&path, &url, SHA256, true).unwrap();
+        let requests = server.join().unwrap();
+        assert_eq!(range_start(&requests[0]), Some(3));
+        assert_eq!(std::fs::read(&path).unwrap(), b"hello\n");
+
+        // It starts over if the server sends the whole file instead.
+        std::fs::write(&path, b"hel").unwrap();
+        let (url, server) = serve_file(b"hello\n", 1, false);
+        download(&path, &url, SHA256, true).unwrap();
+        let requests = server.join().unwrap();
+        assert_eq!(range_start(&requests[0]), Some(3));
+        assert_eq!(std::fs::read(&path).unwrap(), b"hello\n");
+
+        // A corrupted partial file fails verification and is deleted.
+        std::fs::write(&path, b"HEL").unwrap();
+        let (url, server) = serve_file(b"hello\n", 1, true);
+        let error = download(&path, &url, SHA256, true).unwrap_err();
+        assert!(
+            error.to_string().starts_with("checksum mismatch"),
+            "{error}"
+        );
+        server.join().unwrap();
+        assert!(!path.exists());
+    }
+
+    #[test]
+    fn test_download_from_mirror() {
+        use crate::install::checksum::ChecksumVerifier;
+        use crate::install::retry::RetryPolicy;
+        use crate::registry::{DownloadFileFormat, ToolDownloadInstructions};
+
+        // Digest of "hello\n".
+        const SHA256: &str = "5891b5b522d5df086d0ff0b110fbd9d21bb4fc7163af34d08286a2e846f6be03";
+
+        let dir = TempDir::new("ctftools_test").unwrap();
+        let path = dir.path().join("downloaded");
+
+        // The unavailable server is retried while the missing file is not.
+        let (unavailable, unavailable_server) = serve_status("503 Service Unavailable", 2);
+        let (missing, missing_server) = serve_status("404 Not Found", 1);
+        let (mirror, mirror_server) = serve_file(b"hello\n", 1, false);
+
+        let instructions = ToolDownloadInstructions::builder()
+            .format(DownloadFileFormat::Binary)
+            .url(unavailable)
+            .mirrors(vec![missing, mirror])
+            .sha256(SHA256.into())
+            .build();
+        let policy = RetryPolicy {
+            retries: 1,
+            initial_delay: Duration::ZERO,
+            max_delay: Duration::ZERO,
+        };
+
+        let (tx, _rx) = std::sync::mpsc::channel();
+        let client = reqwest::Client::new();
+        let urls: Vec<&str> = instructions.urls().collect();
+        let rt = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .unwrap();
+
+        rt.block_on(policy.run(&urls, async |url| {
+            let verifier = ChecksumVerifier::new(&instructions);
+            download_file_from_url(&tx, &client, path.clone(), url, "Tool", verifier, false).await
+        }))
+        .unwrap();
+
+        unavailable_server.join().unwrap();
+        missing_server.join().unwrap();
+        mirror_server.join().unwrap();
+        assert_eq!(std::fs::read(&path).unwrap(), b"hello\n");
+    }
+}
@@ -1,8 +1,15 @@
 use crate::registry::ToolMetadata;
 use std::time::Duration;
 
+pub mod archive;
+pub mod bundle;
+pub mod cache;
+pub mod checksum;
 pub mod live;
+pub mod retry;
+pub mod signature;
 pub mod task;
+pub mod verify;
 
 pub use self::task::*;
 
@@ -32,12 +39,63 @@ pub enum InstallProgress {
         tool_name: String,
     },
 
-    /// This indicates that ctftools will now start to download an installer from
-    /// the Internet according to its designated link.
+    /// A line of output written by a command that ctftools runs
+    /// to install a tool, such as a build step of a tool built
+    /// from source.
+    Output {
+        /// The line written by the command, without the line ending.
+        line: String,
+
+        /// Associated tool that will be installed.
+        tool_name: String,
+    },
+
+    /// This indicates how far ctftools is into downloading an installer
+    /// from the Internet according to its designated link.
+    ///
+    /// It is reported periodically while the file is being downloaded,
+    /// and once more when the download is complete.
     Download {
         /// Associated URL to be downloaded for a tool.
         url: String,
 
+        /// How many bytes have been received so far.
+        received: u64,
+
+        /// Size of the file in bytes, if the server tells it.
+        total: Option<u64>,
+
+        /// Average download speed in bytes per second.
+        bytes_per_sec: u64,
+
+        /// Estimated time left to complete the download, if the
+        /// size of the file is known.
+        eta: Option<Duration>,
+
+        /// Associated tool that will be installed.
+        tool_name: String,
+    },
+
+    /// The downloaded file could not be verified because its download
+    /// instructions have neither a checksum nor a signature. It is installed anyway unless the
+    /// configuration [requires checksums](crate::config::DownloadConfig::require_checksums).
+    UnverifiedDownload {
+        /// URL of the unverified file.
+        url: String,
+
+        /// Associated tool that will be installed.
+        tool_name: String,
+    },
+
+    /// A build script that is about to be run to install a tool, such as
+    /// the `PKGBUILD` of an AUR package, shown for the user to review.
+    BuildScript {
+        /// Name of the build script file.
+        name: String,
+
+        /// Contents of the build script.
+        contents: String,
+
         /// Associated tool that will be installed.
         tool_name: String,
     },
@@ -0,0 +1,216 @@
+use anyhow::{Result, anyhow};
+use log::{debug, warn};
+use std::time::Duration;
+
+use crate::config::DownloadConfig;
+
+/// How failed downloads are retried with exponential backoff
+/// before falling over to the next mirror.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RetryPolicy {
+    /// How many times a URL is retried after a transient failure.
+    pub retries: u32,
+
+    /// Time to wait before the first retry. It doubles after each one.
+    pub initial_delay: Duration,
+
+    /// Longest time to wait before a retry.
+    pub max_delay: Duration,
+}
+
+impl RetryPolicy {
+    /// Creates the [`RetryPolicy`] given in the download configuration.
+    #[must_use]
+    pub fn new(config: &DownloadConfig) -> Self {
+        Self {
+            retries: config.retries,
+            initial_delay: Duration::from_secs(1),
+            max_delay: Duration::from_secs(30),
+        }
+    }
+
+    /// Time to wait before the given retry, starting from 1.
+    #[must_use]
+    pub fn delay(&self, retry: u32) -> Duration {
+        let factor = 2u32.saturating_pow(retry.saturating_sub(1));
+        self.initial_delay
+            .saturating_mul(factor)
+            .min(self.max_delay)
+    }
+
+    /// Runs `attempt` with each of the `urls` in order until it succeeds.
+    ///
+    /// Transient failures of a URL are retried after a [delay](RetryPolicy::delay),
+    /// while other failures fall over to the next URL right away. Every
+    /// attempt is logged, and the error of the last one is returned if
+    /// all of them fail.
+    pub async fn run<T>(
+        &self,
+        urls: &[&str],
+        mut attempt: impl AsyncFnMut(&str) -> Result<T>,
+    ) -> Result<T> {
+        let mut attempts = 0;
+        let mut last_error = None;
+
+        for url in urls {
+            for retry in 0..=self.retries {
+                if retry > 0 {
+                    let delay = self.delay(retry);
+                    debug!("retrying {url} in {delay:?}");
+                    tokio::time::sleep(delay).await;
+                }
+
+                attempts += 1;
+                debug!("downloading {url} (attempt {})", retry + 1);
+
+                let error = match attempt(url).await {
+                    Ok(value) => return Ok(value),
+                    Err(error) => error,
+                };
+
+                let transient = is_transient(&error);
+                warn!("download attempt {attempts} from {url} failed: {error:#}");
+                last_error = Some(error);
+
+                if !transient {
+                    break;
+                }
+            }
+        }
+
+        let error = last_error.unwrap_or_else(|| anyhow!("there is no URL to download from"));
+        Err(error.context(format!("all {attempts} download attempt(s) failed")))
+    }
+}
+
+/// Whether a failed download may succeed if it is retried, as it
+/// failed because of the network or a temporary server error.
+#[must_use]
+pub fn is_transient(error: &anyhow::Error) -> bool {
+    let Some(error) = error
+        .chain()
+        .find_map(|v| v.downcast_ref::<reqwest::Error>())
+    else {
+        return false;
+    };
+
+    if let Some(status) = error.status() {
+        return status.is_server_error()
+            || status == reqwest::StatusCode::REQUEST_TIMEOUT
+            || status == reqwest::StatusCode::TOO_MANY_REQUESTS;
+    }
+
+    error.is_timeout() || error.is_connect()
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::{Context, bail};
+    use pretty_assertions::assert_eq;
+    use std::time::Duration;
+
+    use super::RetryPolicy;
+
+    fn policy(retries: u32) -> RetryPolicy {
+        RetryPolicy {
+            retries,
+            initial_delay: Duration::ZERO,
+            max_delay: Duration::ZERO,
+        }
+    }
+
+    fn block_on<F: Future>(future: F) -> F::Output {
+        tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .unwrap()
+            .block_on(future)
+    }
+
+    #[test]
+    fn test_retry_delay() {
+        let policy = RetryPolicy {
+            retries: 5,
+            initial_delay: Duration::from_secs(1),
+            max_delay: Duration::from_secs(5),
+        };
+
+        let delays: Vec<_> = (1..=5).map(|v| policy.delay(v).as_secs()).collect();
+        assert_eq!(delays, [1, 2, 4, 5, 5]);
+        assert_eq!(policy.delay(u32::MAX), Duration::from_secs(5));
+    }
+
+    #[test]
+    fn test_fall_over_to_mirrors() {
+        let mut attempted = Vec::new();
+        let result = block_on(policy(2).run(&["a", "b", "c"], async |url| {
+            attempted.push(url.to_string());
+            match url {
+                "c" => Ok(url.to_string()),
+                _ => bail!("not found"),
+            }
+        }));
+
+        // Errors that are not transient are never retried.
+        assert_eq!(result.unwrap(), "c");
+        assert_eq!(attempted, ["a", "b", "c"]);
+
+        let error = block_on(policy(2).run(&["a"], async |_| -> anyhow::Result<()> {
+            bail!("not found")
+        }))
+        .unwrap_err();
+        assert_eq!(
+            format!("{error:#}"),
+            "all 1 download attempt(s) failed: not found"
+        );
+    }
+
+    #[test]
+    fn test_retry_transient_failures() {
+        // Nothing listens on the port once the listener is dropped.
+        let addr = std::net::TcpListener::bind("127.0.0.1:0")
+            .unwrap()
+            .local_addr()
+            .unwrap();
+        let url = format!("http://{addr}/tool");
+
+        let mut attempts = 0;
+        let error = block_on(policy(2).run(&[&url], async |url| {
+            attempts += 1;
+            reqwest::get(url).await.context("HTTP request failed")
+        }))
+        .unwrap_err();
+
+        assert_eq!(attempts, 3);
+        assert!(super::is_transient(&error));
+    }
+
+    #[test]
+    fn test_transient_statuses() {
+        use crate::testing::{Response, serve};
+
+        let statuses = [
+            ("503 Service Unavailable", true),
+            ("500 Internal Server Error", true),
+            ("408 Request Timeout", true),
+            ("429 Too Many Requests", true),
+            ("404 Not Found", false),
+            ("403 Forbidden", false),
+        ];
+
+        for (status, transient) in statuses {
+            let (url, server) = serve(1, move |_| Response::status(status));
+            let error = block_on(async {
+                reqwest::get(&url).await?.error_for_status()?;
+                anyhow::Ok(())
+            })
+            .unwrap_err();
+
+            server.join().unwrap();
+            assert_eq!(super::is_transient(&error), transient, "{status}");
+        }
+
+        // Errors that do not come from the network are never retried.
+        assert!(!super::is_transient(&anyhow::anyhow!("checksum mismatch")));
+    }
+}
@@ -0,0 +1,291 @@
+use anyhow::{Context, Result, bail};
+use log::debug;
+use std::fs::File;
+use std::io::Read;
+use std::path::Path;
+use tempdir::TempDir;
+
+use crate::process::ProcessBuilder;
+use crate::registry::{DownloadSignature, SignatureKey};
+use crate::util::which_opt;
+
+/// Verifies a downloaded file with its detached signature, which must
+/// be made with the pinned public key of the [download signature].
+///
+/// Minisign signatures are verified in-process, while OpenPGP signatures
+/// are verified with GnuPG in a keyring that only has the pinned key.
+///
+/// [download signature]: DownloadSignature
+pub fn verify_signature(path: &Path, signature: &DownloadSignature, contents: &str) -> Result<()> {
+    match &signature.key {
+        SignatureKey::Minisign(..) => verify_minisign(path, &signature.key, contents),
+        SignatureKey::OpenPgp(key) => verify_openpgp(path, key, contents),
+    }
+    .with_context(|| format!("could not verify the signature from {}", signature.url))
+}
+
+fn verify_minisign(path: &Path, key: &SignatureKey, contents: &str) -> Result<()> {
+    let public_key = key.minisign_public_key()?;
+    let signature = minisign_verify::Signature::decode(contents)
+        .map_err(|error| anyhow::anyhow!("malformed signature: {error}"))?;
+
+    let mut verifier = public_key
+        .verify_stream(&signature)
+        .map_err(|error| anyhow::anyhow!("unsupported signature: {error}"))?;
+
+    let mut file =
+        File::open(path).with_context(|| format!("could not open {}", path.display()))?;
+    let mut buf = vec![0u8; 64 * 1024];
+    loop {
+        let len = file.read(&mut buf)?;
+        if len == 0 {
+            break;
+        }
+        verifier.update(&buf[..len]);
+    }
+
+    verifier
+        .finalize()
+        .map_err(|error| anyhow::anyhow!("bad signature: {error}"))
+}
+
+fn verify_openpgp(path: &Path, key: &str, contents: &str) -> Result<()> {
+    let Some(gpg) = which_opt("gpg")? else {
+        bail!("gpg is required to verify OpenPGP signatures");
+    };
+
+    // Attached and clearsigned signatures carry their own data, which
+    // gpg would verify instead of the downloaded file.
+    if !contents
+        .trim_start()
+        .starts_with("-----BEGIN PGP SIGNATURE-----")
+    {
+        bail!("expected a detached OpenPGP signature");
+    }
+
+    // A throwaway home directory keeps the user's own keyring out of it,
+    // so only the pinned key can make the signature valid.
+    let home = TempDir::new("ctftools_gpg")?;
+    let key_path = home.path().join("key.asc");
+    let signature_path = home.path().join("download.sig");
+    std::fs::write(&key_path, key)?;
+    std::fs::write(&signature_path, contents)?;
+
+    let gpg = |args: &[&Path]| {
+        let mut builder = ProcessBuilder::new(&gpg);
+        builder
+            .args(&["--batch", "--no-tty", "--homedir"])
+            .arg(home.path())
+            .args(args);
+
+        debug!("executing: {builder}");
+        builder.exec_with_output()
+    };
+
+    gpg(&[Path::new("--import"), &key_path]).context("could not import the pinned key")?;
+    let keys = gpg(&[Path::new("--with-colons"), Path::new("--fingerprint")])
+        .context("could not list the pinned key")?;
+    let fingerprint = primary_fingerprint(&String::from_utf8_lossy(&keys.stdout))?;
+
+    let status = gpg(&[
+        Path::new("--status-fd"),
+        Path::new("1"),
+        Path::new("--verify"),
+        &signature_path,
+        path,
+    ])
+    .context("bad signature")?;
+
+    check_verify_status(&String::from_utf8_lossy(&status.stdout), &fingerprint)
+}
+
+/// Gets the fingerprint of the only primary key from gpg's
+/// `--with-colons` key listing.
+fn primary_fingerprint(listing: &str) -> Result<String> {
+    let mut fingerprints = Vec::new();
+    let mut in_primary = false;
+    for fields in listing
+        .lines()
+        .map(|line| line.split(':').collect::<Vec<_>>())
+    {
+        match fields[0] {
+            "pub" => in_primary = true,
+            "fpr" if in_primary => {
+                fingerprints.extend(fields.get(9).map(|v| v.to_string()));
+                in_primary = false;
+            }
+            "sub" => in_primary = false,
+            _ => {}
+        }
+    }
+
+    match fingerprints.as_slice() {
+        [fingerprint] => Ok(fingerprint.clone()),
+        [] => bail!("the pinned key has no primary key"),
+        _ => bail!("the pinned key must be a single public key"),
+    }
+}
+
+/// Checks the `--status-fd` output of `gpg --verify` for a valid,
+/// detached signature made by the key with the given primary fingerprint.
+fn check_verify_status(status: &str, fingerprint: &str) -> Result<()> {
+    let mut valid = false;
+    for line in status.lines() {
+        let mut fields = line.split_whitespace();
+        if fields.next() != Some("[GNUPG:]") {
+            continue;
+        }
+
+        match fields.next() {
+            // Only signed data carries a literal data packet.
+            Some("PLAINTEXT") => bail!("expected a detached OpenPGP signature"),
+            Some("VALIDSIG") => {
+                let fields = fields.collect::<Vec<_>>();
+                let primary = fields.get(9).or(fields.first());
+                if primary.is_some_and(|v| v.eq_ignore_ascii_case(fingerprint)) {
+                    valid = true;
+                } else {
+                    bail!("signature was not made by the pinned key");
+                }
+            }
+            _ => {}
+        }
+    }
+
+    if !valid {
+        bail!("no valid signature from the pinned key");
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use minisign::KeyPair;
+    use std::io::Cursor;
+    use tempdir::TempDir;
+
+    use super::{check_verify_status, primary_fingerprint, verify_signature};
+    use crate::registry::{DownloadSignature, SignatureKey};
+
+    fn sign(keypair: &KeyPair, contents: &[u8]) -> String {
+        minisign::sign(None, &keypair.sk, Cursor::new(contents), None, None)
+            .unwrap()
+            .into_string()
+    }
+
+    #[test]
+    fn test_verify_minisign_signature() {
+        let publisher = KeyPair::generate_unencrypted_keypair().unwrap();
+        let stranger = KeyPair::generate_unencrypted_keypair().unwrap();
+
+        let dir = TempDir::new("ctftools_test").unwrap();
+        let path = dir.path().join("downloaded");
+        std::fs::write(&path, b"hello\n").unwrap();
+
+        // Keys can be pinned in base64 or in the `minisign.pub` file format.
+        for key in [
+            publisher.pk.to_base64(),
+            publisher.pk.to_box().unwrap().into_string(),
+        ] {
+            let signature = DownloadSignature {
+                url: "https://foo.local/tool.minisig".into(),
+                key: SignatureKey::Minisign(key),
+            };
+            signature.validate().unwrap();
+
+            verify_signature(&path, &signature, &sign(&publisher, b"hello\n")).unwrap();
+            assert!(verify_signature(&path, &signature, &sign(&publisher, b"bye\n")).is_err());
+            assert!(verify_signature(&path, &signature, &sign(&stranger, b"hello\n")).is_err());
+            assert!(verify_signature(&path, &signature, "not a signature").is_err());
+        }
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_verify_openpgp_signature() {
+        use crate::process::ProcessBuilder;
+        use crate::util::which_opt;
+
+        let Some(gpg) = which_opt("gpg").unwrap() else {
+            return;
+        };
+
+        let dir = TempDir::new("ctftools_test").unwrap();
+        let home = dir.path().join("gnupg");
+        std::fs::create_dir(&home).unwrap();
+        let gpg = |args: &[&str]| {
+            let mut builder = ProcessBuilder::new(&gpg);
+            builder
+                .args(&["--batch", "--no-tty", "--pinentry-mode", "loopback"])
+                .args(&["--passphrase", "", "--homedir"])
+                .arg(&home)
+                .args(args);
+            builder.exec_with_output().unwrap().stdout
+        };
+
+        let path = dir.path().join("downloaded");
+        std::fs::write(&path, b"hello\n").unwrap();
+        let path = path.to_str().unwrap();
+
+        gpg(&["--quick-gen-key", "publisher@foo.local", "ed25519", "sign"]);
+        let key = String::from_utf8(gpg(&["--armor", "--export", "publisher@foo.local"])).unwrap();
+        let contents =
+            String::from_utf8(gpg(&["--armor", "--detach-sign", "--output", "-", path])).unwrap();
+
+        let signature = DownloadSignature {
+            url: "https://foo.local/tool.asc".into(),
+            key: SignatureKey::OpenPgp(key),
+        };
+        signature.validate().unwrap();
+        verify_signature(path.as_ref(), &signature, &contents).unwrap();
+
+        // Signatures that carry their own data are rejected even if valid.
+        for mode in ["--clearsign", "--sign"] {
+            let contents =
+                String::from_utf8(gpg(&["--armor", mode, "--output", "-", path])).unwrap();
+            assert!(verify_signature(path.as_ref(), &signature, &contents).is_err());
+        }
+
+        std::fs::write(path, b"tampered\n").unwrap();
+        assert!(verify_signature(path.as_ref(), &signature, &contents).is_err());
+    }
+
+    #[test]
+    fn test_primary_fingerprint() {
+        let listing = "\
+tru::1:1700000000:0:3:1:5
+pub:u:255:22:AAAA:1700000000:::u:::scSC:::::ed25519:::0:
+fpr:::::::::0123456789ABCDEF0123456789ABCDEF01234567:
+uid:u::::1700000000::HASH::publisher@foo.local::::::::::0:
+sub:u:255:18:BBBB:1700000000::::::e:::::cv25519::
+fpr:::::::::FEDCBA9876543210FEDCBA9876543210FEDCBA98:
+";
+        assert_eq!(
+            primary_fingerprint(listing).unwrap(),
+            "0123456789ABCDEF0123456789ABCDEF01234567"
+        );
+
+        let two_keys = format!("{listing}{}", listing.replace("0123", "4567"));
+        assert!(primary_fingerprint(&two_keys).is_err());
+        assert!(primary_fingerprint("").is_err());
+    }
+
+    #[test]
+    fn test_check_verify_status() {
+        let fingerprint = "0123456789ABCDEF0123456789ABCDEF01234567";
+        let valid = format!(
+            "[GNUPG:] NEWSIG\n\
+            [GNUPG:] GOODSIG 89ABCDEF01234567 publisher@foo.local\n\
+            [GNUPG:] VALIDSIG {fingerprint} 2024-01-01 1700000000 0 4 0 22 10 00 {fingerprint}\n"
+        );
+        check_verify_status(&valid, fingerprint).unwrap();
+        check_verify_status(&valid, &fingerprint.to_lowercase()).unwrap();
+
+        let other = "FEDCBA9876543210FEDCBA9876543210FEDCBA98";
+        assert!(check_verify_status(&valid, other).is_err());
+        assert!(check_verify_status("[GNUPG:] NEWSIG\n", fingerprint).is_err());
+
+        let attached = format!("[GNUPG:] PLAINTEXT 62 1700000000 downloaded\n{valid}");
+        assert!(check_verify_status(&attached, fingerprint).is_err());
+    }
+}
@@ -1,9 +1,16 @@
 use serde::Serialize;
-use std::path::PathBuf;
+use sha2::{Digest, Sha256};
+use std::path::{Path, PathBuf};
 use thiserror::Error;
 
-use crate::pkg::{AurHelper, PackageManager};
-use crate::registry::{ToolDownloadInstructions, ToolMetadata};
+use crate::config::PackageScope;
+use crate::container::ContainerRuntime;
+use crate::install::checksum::to_hex;
+use crate::pkg::{
+    AurHelper, Installer, LanguagePackageManager, PackageManager, UniversalPackageManager,
+};
+use crate::registry::{ToolDownloadInstructions, ToolMetadata, ToolSourceMetadata};
+use crate::util::ProgramDirs;
 
 /// Represents an action to install a tool.
 #[derive(Debug, PartialEq, Eq, Serialize)]
@@ -19,6 +26,9 @@ pub enum InstallTask {
         /// Whether the package manager invocation requires elevated privileges.
         sudo: bool,
 
+        /// The installer that was chosen to install the tool with.
+        installer: Installer,
+
         /// The original tool name to be installed.
         tool_name: String,
     },
@@ -28,6 +38,58 @@ pub enum InstallTask {
         /// Instructions on how to install a tool from a download.
         instructions: ToolDownloadInstructions,
 
+        /// The command used to run the tool. Executables extracted
+        /// from archives are exposed under this name.
+        command: String,
+
+        /// The original tool name to be installed.
+        tool_name: String,
+    },
+
+    /// Install the tool from a file that was downloaded beforehand,
+    /// such as one taken from an [installation bundle](crate::install::bundle).
+    LocalDownload {
+        /// Path to the downloaded file.
+        path: PathBuf,
+
+        /// Contents of the detached signature that was
+        /// downloaded along with the file, if any.
+        signature: Option<String>,
+
+        /// Instructions that the file was downloaded with.
+        instructions: Box<ToolDownloadInstructions>,
+
+        /// The command used to run the tool. Executables extracted
+        /// from archives are exposed under this name.
+        command: String,
+
+        /// The original tool name to be installed.
+        tool_name: String,
+    },
+
+    /// Install the tool from package files (e.g. `.deb` or `.rpm`) along
+    /// with their dependencies, such as the ones taken from an
+    /// [installation bundle](crate::install::bundle).
+    PackageFiles {
+        /// The package files, which are verified before they are installed.
+        files: Vec<PackageFile>,
+
+        /// The original tool name to be installed.
+        tool_name: String,
+    },
+
+    /// Install the tool by building it from the source code in its git repository.
+    Source {
+        /// Where to get the source code and how to build it.
+        source: ToolSourceMetadata,
+
+        /// Directory where the git repository is cloned into and kept
+        /// so later builds only have to fetch the new changes.
+        cache_dir: PathBuf,
+
+        /// Installation prefix of the built tool.
+        prefix: PathBuf,
+
         /// The original tool name to be installed.
         tool_name: String,
     },
@@ -50,6 +112,16 @@ pub enum InstallTask {
     },
 }
 
+/// A package file (e.g. `.deb` or `.rpm`) of an [`InstallTask::PackageFiles`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct PackageFile {
+    /// Path to the package file.
+    pub path: PathBuf,
+
+    /// The sha256 checksum the file must match.
+    pub sha256: String,
+}
+
 impl InstallTask {
     /// Gets the associated tool name from a task in any variant.
     #[must_use]
@@ -57,7 +129,99 @@ impl InstallTask {
         match self {
             Self::AUR { tool_name, .. } => tool_name,
             Self::Download { tool_name, .. } => tool_name,
+            Self::LocalDownload { tool_name, .. } => tool_name,
+            Self::PackageFiles { tool_name, .. } => tool_name,
             Self::PackageManager { tool_name, .. } => tool_name,
+            Self::Source { tool_name, .. } => tool_name,
+        }
+    }
+
+    /// Combines the [package manager tasks](Self::PackageManager) that run
+    /// the same installer into one task that installs all of their packages
+    /// in a single transaction, such as `apt install -y nmap gdb`.
+    ///
+    /// The tasks can only be combined if their arguments differ in the
+    /// package alone. Returns `None` if they cannot be combined or if the
+    /// installer can only install one package at a time.
+    #[must_use]
+    pub fn batch(tasks: &[&InstallTask]) -> Option<InstallTask> {
+        let Some(InstallTask::PackageManager {
+            exec,
+            arguments,
+            sudo,
+            installer,
+            ..
+        }) = tasks.first()
+        else {
+            return None;
+        };
+
+        if tasks.len() < 2 || !installer.can_install_many() {
+            return None;
+        }
+
+        let mut package_index = None;
+        let mut packages = Vec::new();
+        for task in &tasks[1..] {
+            let InstallTask::PackageManager {
+                exec: other_exec,
+                arguments: other_arguments,
+                sudo: other_sudo,
+                installer: other_installer,
+                ..
+            } = task
+            else {
+                return None;
+            };
+
+            if other_exec != exec
+                || other_sudo != sudo
+                || other_installer != installer
+                || other_arguments.len() != arguments.len()
+            {
+                return None;
+            }
+
+            let mut differing =
+                (0..arguments.len()).filter(|&i| arguments[i] != other_arguments[i]);
+            match (differing.next(), differing.next()) {
+                // Other tools may be provided by the same package.
+                (None, _) => {}
+                (Some(index), None) if package_index.is_none_or(|v| v == index) => {
+                    package_index = Some(index);
+                    if !packages.contains(&other_arguments[index]) {
+                        packages.push(other_arguments[index].clone());
+                    }
+                }
+                _ => return None,
+            }
+        }
+
+        let mut arguments = arguments.clone();
+        if let Some(index) = package_index {
+            arguments.splice(index + 1..index + 1, packages);
+        }
+
+        Some(InstallTask::PackageManager {
+            exec: exec.clone(),
+            arguments,
+            sudo: *sudo,
+            installer: *installer,
+            tool_name: tasks
+                .iter()
+                .map(|task| task.tool_name())
+                .collect::<Vec<_>>()
+                .join(", "),
+        })
+    }
+
+    /// Gets the installer that was chosen to install the tool with,
+    /// if the tool is installed from a package.
+    #[must_use]
+    pub fn installer(&self) -> Option<Installer> {
+        match self {
+            Self::PackageManager { installer, .. } => Some(*installer),
+            _ => None,
         }
     }
 }
@@ -69,6 +233,11 @@ pub enum InstallTaskError {
     #[error("{tool_name:?} cannot be installed automatically")]
     CannotInstallTool { tool_name: String },
 
+    /// The tool can only be installed from a download that has no checksum
+    /// or signature while the configuration requires every download to be verified.
+    #[error("Cannot verify the download of {tool_name:?} without a checksum or signature")]
+    UnverifiedDownload { tool_name: String },
+
     /// The tool is not available in the Arch User Repository (AUR).
     #[error("Cannot find AUR equivalent package for {tool_name:?}")]
     AurPackageNotFound {
@@ -82,6 +251,20 @@ pub enum InstallTaskError {
         pkg_manager: PackageManager,
         tool_name: String,
     },
+
+    /// The tool is not available in the requested language package manager's registry.
+    #[error("Cannot find {} equivalent package for {tool_name:?}", .pkg_manager.as_display_name())]
+    LanguagePackageNotFound {
+        pkg_manager: LanguagePackageManager,
+        tool_name: String,
+    },
+
+    /// The tool is not available in the requested universal package manager's registry.
+    #[error("Cannot find {} equivalent package for {tool_name:?}", .pkg_manager.as_display_name())]
+    UniversalPackageNotFound {
+        pkg_manager: UniversalPackageManager,
+        tool_name: String,
+    },
 }
 
 impl InstallTask {
@@ -105,29 +288,101 @@ impl InstallTask {
             exec: path_to_aur_helper,
             arguments,
             sudo: aur_helper.needs_privilege(),
+            installer: Installer::Aur(aur_helper),
             tool_name,
         }
     }
 
+    /// Creates an appropriate [`InstallTask`] object from a language
+    /// package manager (e.g. pip or Cargo) to install a provided tool.
+    ///
+    /// The package is looked up from the package manager's own key
+    /// in the tool's `packages` mapping. `home` is the user's home
+    /// directory where the package is installed into.
+    pub fn from_language_pkg_manager(
+        pkg_manager: LanguagePackageManager,
+        path_to_pkg_manager: PathBuf,
+        home: &Path,
+        tool: &ToolMetadata,
+    ) -> Result<Self, InstallTaskError> {
+        let Some(package_name) = pkg_manager.resolve_package(&tool.packages) else {
+            return Err(InstallTaskError::LanguagePackageNotFound {
+                pkg_manager,
+                tool_name: tool.name.clone(),
+            });
+        };
+
+        Ok(InstallTask::PackageManager {
+            exec: path_to_pkg_manager,
+            arguments: pkg_manager.install_args(package_name, home),
+            sudo: false,
+            installer: Installer::Language(pkg_manager),
+            tool_name: tool.name.clone(),
+        })
+    }
+
+    /// Creates an appropriate [`InstallTask`] object from a universal
+    /// package manager (e.g. Flatpak or Nix) to install a provided tool.
+    ///
+    /// The package is looked up from the package manager's own key in
+    /// the tool's `packages` mapping. It is installed in the `scope`
+    /// preferred by the user if the package manager supports it.
+    pub fn from_universal_pkg_manager(
+        pkg_manager: UniversalPackageManager,
+        path_to_pkg_manager: PathBuf,
+        scope: PackageScope,
+        tool: &ToolMetadata,
+    ) -> Result<Self, InstallTaskError> {
+        let Some(package_name) = pkg_manager.resolve_package(&tool.packages) else {
+            return Err(InstallTaskError::UniversalPackageNotFound {
+                pkg_manager,
+                tool_name: tool.name.clone(),
+            });
+        };
+
+        let scope = pkg_manager.scope(scope);
+        Ok(InstallTask::PackageManager {
+            exec: path_to_pkg_manager,
+            arguments: pkg_manager.install_args(package_name, scope),
+            sudo: scope == PackageScope::System,
+            installer: Installer::Universal(pkg_manager),
+            tool_name: tool.name.clone(),
+        })
+    }
+
+    /// Creates an [`InstallTask`] that pulls the image of a container
+    /// tool with the given container runtime.
+    pub fn from_container_runtime(
+        runtime: ContainerRuntime,
+        path_to_runtime: PathBuf,
+        tool: &ToolMetadata,
+    ) -> Result<Self, InstallTaskError> {
+        let Some(container) = tool.container.as_ref() else {
+            return Err(InstallTaskError::CannotInstallTool {
+                tool_name: tool.name.clone(),
+            });
+        };
+
+        Ok(InstallTask::PackageManager {
+            exec: path_to_runtime,
+            arguments: vec!["pull".into(), container.image.clone()],
+            sudo: false,
+            installer: Installer::Container(runtime),
+            tool_name: tool.name.clone(),
+        })
+    }
+
     /// Create an [`InstallTask`] from the tool's download metadata.
     ///
     /// This prefers platform-specific download entries. If no matching
     /// download URL exists for the current target OS, it returns
     /// `Err(InstallTaskError::CannotInstallTool)`.
     pub fn from_downloads(tool: &ToolMetadata) -> Result<Self, InstallTaskError> {
-        let instructions = if cfg!(target_os = "windows") {
-            tool.downloads.windows.clone()
-        } else if cfg!(target_os = "macos") {
-            tool.downloads.macos.clone()
-        } else if cfg!(target_os = "linux") {
-            tool.downloads.linux.clone()
-        } else {
-            None
-        };
-
-        instructions
+        tool.downloads
+            .for_os(std::env::consts::OS)
             .map(|inner| Self::Download {
-                instructions: inner,
+                instructions: inner.clone(),
+                command: tool.command.clone(),
                 tool_name: tool.name.clone(),
             })
             .ok_or_else(|| InstallTaskError::CannotInstallTool {
@@ -135,6 +390,30 @@ impl InstallTask {
             })
     }
 
+    /// Creates an [`InstallTask`] that builds the tool from its source code.
+    ///
+    /// The repository is cloned into a cache directory derived from its URL
+    /// and the tool is installed into the [prefix](ProgramDirs::prefix_dir).
+    pub fn from_source(dirs: &ProgramDirs, tool: &ToolMetadata) -> Result<Self, InstallTaskError> {
+        // Building from source needs both the cache and data directories.
+        let (Some(source), Some(sources_dir), Some(prefix)) = (
+            tool.source.as_ref(),
+            dirs.sources_cache_dir(),
+            dirs.prefix_dir(),
+        ) else {
+            return Err(InstallTaskError::CannotInstallTool {
+                tool_name: tool.name.clone(),
+            });
+        };
+
+        Ok(InstallTask::Source {
+            source: source.clone(),
+            cache_dir: sources_dir.join(cache_key(&source.git)),
+            prefix,
+            tool_name: tool.name.clone(),
+        })
+    }
+
     /// Creates an appropriate [`InstallTask`] object from
     /// a specific package manager to install a provided tool.
     ///
@@ -145,66 +424,33 @@ impl InstallTask {
         path_to_pkg_manager: PathBuf,
         tool: &ToolMetadata,
     ) -> Result<Self, InstallTaskError> {
-        // Handle Pacman separately because it may need the AUR helper.
-        if pkg_manager == PackageManager::Pacman {
-            // Look for pacman, aur, or default packages
-            let mut pkg_name = tool.packages.get("pacman");
-            let mut use_aur = false;
-
-            if pkg_name.is_none() {
-                pkg_name = tool.packages.get("aur");
-                use_aur = pkg_name.is_some();
-            }
-
-            // Or maybe in the defaults?
-            if pkg_name.is_none() {
-                pkg_name = tool.packages.get("default");
-                use_aur = false;
-            }
-
-            let Some(arch_package) = pkg_name else {
-                return Err(InstallTaskError::PackageNotFound {
-                    pkg_manager,
-                    tool_name: tool.name.clone(),
-                });
-            };
-
-            if use_aur {
-                return Ok(InstallTask::AUR {
-                    package_name: arch_package.to_string(),
-                    tool_name: tool.name.clone(),
-                });
-            }
-
-            let arguments = ["-S", "--noconfirm", arch_package]
-                .into_iter()
-                .map(String::from)
-                .collect();
-
-            return Ok(InstallTask::PackageManager {
-                exec: path_to_pkg_manager,
-                arguments,
-                sudo: pkg_manager.needs_privilege(),
+        let Some((package_name, origin)) = resolve_package(pkg_manager, tool) else {
+            return Err(InstallTaskError::PackageNotFound {
+                pkg_manager,
                 tool_name: tool.name.clone(),
             });
-        }
+        };
 
-        let package_name = tool
-            .packages
-            .get(pkg_manager.as_registry_key())
-            .or_else(|| tool.packages.get("default"))
-            .ok_or_else(|| InstallTaskError::PackageNotFound {
-                pkg_manager,
+        // AUR packages need to be installed with an AUR helper instead.
+        if origin == PackageOrigin::AUR {
+            return Ok(InstallTask::AUR {
+                package_name: package_name.to_string(),
                 tool_name: tool.name.clone(),
-            })?;
+            });
+        }
 
         let args = match pkg_manager {
-            PackageManager::APT => ["install", "-y", package_name],
-            PackageManager::DNF => ["install", "-y", package_name],
-            PackageManager::Homebrew => ["install", package_name, ""],
-            PackageManager::Chocolatey => ["install", package_name, "-y"],
-            PackageManager::WinGet => ["install", package_name, "--accept-package-agreements"],
-            PackageManager::Pacman => unreachable!(),
+            PackageManager::APT => vec!["install", "-y", package_name],
+            PackageManager::DNF => vec!["install", "-y", package_name],
+            PackageManager::Homebrew => vec!["install", package_name],
+            PackageManager::Chocolatey => vec!["install", package_name, "-y"],
+            PackageManager::WinGet => vec!["install", package_name, "--accept-package-agreements"],
+            PackageManager::Pacman => vec!["-S", "--noconfirm", package_name],
+            PackageManager::Zypper => vec!["--non-interactive", "install", package_name],
+            PackageManager::APK => vec!["add", package_name],
+            PackageManager::XBPS => vec!["--sync", "--yes", package_name],
+            PackageManager::Emerge => vec!["--ask=n", package_name],
+            PackageManager::Eopkg => vec!["install", "-y", package_name],
         }
         .into_iter()
         .map(String::from)
@@ -214,22 +460,89 @@ impl InstallTask {
             exec: path_to_pkg_manager,
             arguments: args,
             sudo: pkg_manager.needs_privilege(),
+            installer: Installer::System(pkg_manager),
             tool_name: tool.name.clone(),
         })
     }
 }
 
+/// Turns a git repository URL into a directory name that
+/// can be used to cache the repository.
+///
+/// The URL is hashed so that different URLs never share a directory.
+fn cache_key(url: &str) -> String {
+    to_hex(&Sha256::digest(url.as_bytes()))
+}
+
+/// Where a package that provides a tool is expected to come from.
+#[allow(clippy::upper_case_acronyms)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PackageOrigin {
+    /// The official repositories of the package manager.
+    Repository,
+
+    /// The Arch User Repository (AUR). This is only applicable for Pacman.
+    AUR,
+}
+
+/// Finds the name of the package that provides a tool from a package manager.
+///
+/// It prefers the package manager's own key in the tool's `packages` mapping
+/// and falls back to the `default` key. For Pacman, this function will prefer
+/// pacman-specific packages, fall back to AUR packages if present, or use
+/// its default package.
+#[must_use]
+pub fn resolve_package(
+    pkg_manager: PackageManager,
+    tool: &ToolMetadata,
+) -> Option<(&str, PackageOrigin)> {
+    let packages = &tool.packages;
+    if let Some(package) = packages.get(pkg_manager.as_registry_key()) {
+        return Some((package, PackageOrigin::Repository));
+    }
+
+    if pkg_manager == PackageManager::Pacman
+        && let Some(package) = packages.get("aur")
+    {
+        return Some((package, PackageOrigin::AUR));
+    }
+
+    packages
+        .get("default")
+        .map(|package| (package.as_str(), PackageOrigin::Repository))
+}
+
 #[cfg(test)]
 mod tests {
     use maplit::hashmap;
     use pretty_assertions::assert_eq;
-    use std::path::PathBuf;
+    use std::path::{Path, PathBuf};
 
+    use super::cache_key;
     use crate::install::{InstallTask, InstallTaskError};
-    use crate::pkg::PackageManager;
+    use crate::pkg::{Installer, LanguagePackageManager, PackageManager};
     use crate::registry::{
-        DownloadFileFormat, ToolDownloadInstructions, ToolMetadata, ToolPlatformDownloads,
+        BuildSystem, DownloadFileFormat, ToolDownloadInstructions, ToolMetadata,
+        ToolPlatformDownloads, ToolSourceMetadata,
     };
+    use crate::util::ProgramDirs;
+
+    #[test]
+    fn test_cache_key() {
+        let key = cache_key("https://github.com/RickdeJager/stegseek.git");
+        assert_eq!(key.len(), 64);
+        assert!(key.chars().all(|v| v.is_ascii_hexdigit()));
+
+        // URLs that only differ in their separators must not collide.
+        assert_ne!(
+            cache_key("https://foo.local/a/b_c"),
+            cache_key("https://foo.local/a_b/c")
+        );
+        assert_ne!(
+            cache_key("https://foo.local/a"),
+            cache_key("ssh://foo.local/a")
+        );
+    }
 
     #[test]
     fn test_from_download_with_no_download_links() {
@@ -255,9 +568,9 @@ mod tests {
         let expected_link = if cfg!(target_os = "windows") {
             "https://foo.local/downloads/windows.exe"
         } else if cfg!(target_os = "macos") {
-            "https://foo.local/downloads/macos.exe"
+            "https://foo.local/downloads/macos.dmg"
         } else if cfg!(target_os = "linux") {
-            "https://foo.local/downloads/linux.exe"
+            "https://foo.local/downloads/linux"
         } else {
             unreachable!()
         };
@@ -297,6 +610,7 @@ mod tests {
                     .url(expected_link.to_string())
                     .format(DownloadFileFormat::Executable)
                     .build(),
+                command: "foo".to_string(),
                 tool_name: "foo".to_string(),
             })
         );
@@ -442,6 +756,7 @@ mod tests {
                     .map(String::from)
                     .collect(),
                 sudo: true,
+                installer: Installer::System(PackageManager::Pacman),
                 tool_name: "foo".to_string(),
             })
         );
@@ -475,6 +790,7 @@ mod tests {
                     .map(String::from)
                     .collect(),
                 sudo: true,
+                installer: Installer::System(PackageManager::Pacman),
                 tool_name: "foo".to_string(),
             })
         );
@@ -530,4 +846,166 @@ mod tests {
             })
         );
     }
+
+    #[test]
+    fn test_install_arguments() {
+        let tool = ToolMetadata::builder()
+            .name("foo".to_string())
+            .command("foo".to_string())
+            .packages(hashmap! {
+                "default".to_string() => "foo".to_string(),
+                "emerge".to_string() => "app-misc/foo".to_string(),
+            })
+            .build();
+
+        let cases: &[(PackageManager, &[&str], bool)] = &[
+            (PackageManager::Homebrew, &["install", "foo"], false),
+            (
+                PackageManager::Zypper,
+                &["--non-interactive", "install", "foo"],
+                true,
+            ),
+            (PackageManager::APK, &["add", "foo"], true),
+            (PackageManager::XBPS, &["--sync", "--yes", "foo"], true),
+            (PackageManager::Emerge, &["--ask=n", "app-misc/foo"], true),
+            (PackageManager::Eopkg, &["install", "-y", "foo"], true),
+        ];
+
+        for (pkg_manager, arguments, sudo) in cases {
+            let result = InstallTask::from_package_manager(
+                *pkg_manager,
+                PathBuf::from("/usr/bin/pm"),
+                &tool,
+            );
+
+            assert_eq!(
+                result,
+                Ok(InstallTask::PackageManager {
+                    exec: PathBuf::from("/usr/bin/pm"),
+                    arguments: arguments.iter().map(|v| v.to_string()).collect(),
+                    sudo: *sudo,
+                    installer: Installer::System(*pkg_manager),
+                    tool_name: "foo".to_string(),
+                }),
+                "{pkg_manager:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_batch() {
+        let tool = |name: &str, package: &str| {
+            ToolMetadata::builder()
+                .name(name.to_string())
+                .command(name.to_string())
+                .packages(hashmap! {
+                    "default".to_string() => package.to_string(),
+                })
+                .build()
+        };
+        let task = |pkg_manager, name: &str, package: &str| {
+            InstallTask::from_package_manager(
+                pkg_manager,
+                PathBuf::from("/usr/bin/pm"),
+                &tool(name, package),
+            )
+            .unwrap()
+        };
+
+        let nmap = task(PackageManager::Chocolatey, "Nmap", "nmap");
+        let gdb = task(PackageManager::Chocolatey, "GDB", "gdb");
+        let tshark = task(PackageManager::Chocolatey, "TShark", "wireshark");
+        let wireshark = task(PackageManager::Chocolatey, "Wireshark", "wireshark");
+
+        assert_eq!(
+            InstallTask::batch(&[&nmap, &gdb, &tshark, &wireshark]),
+            Some(InstallTask::PackageManager {
+                exec: PathBuf::from("/usr/bin/pm"),
+                arguments: ["install", "nmap", "gdb", "wireshark", "-y"]
+                    .into_iter()
+                    .map(String::from)
+                    .collect(),
+                sudo: false,
+                installer: Installer::System(PackageManager::Chocolatey),
+                tool_name: "Nmap, GDB, TShark, Wireshark".to_string(),
+            })
+        );
+
+        // There is nothing to combine with a single task.
+        assert_eq!(InstallTask::batch(&[&nmap]), None);
+
+        // WinGet can only install one package at a time.
+        let nmap = task(PackageManager::WinGet, "Nmap", "nmap");
+        let gdb = task(PackageManager::WinGet, "GDB", "gdb");
+        assert_eq!(InstallTask::batch(&[&nmap, &gdb]), None);
+
+        // Go only installs packages of the same module at once.
+        let go = |name: &str, package: &str| {
+            let tool = ToolMetadata::builder()
+                .name(name.to_string())
+                .command(name.to_string())
+                .packages(hashmap! {
+                    "go".to_string() => package.to_string(),
+                })
+                .build();
+
+            InstallTask::from_language_pkg_manager(
+                LanguagePackageManager::Go,
+                PathBuf::from("/usr/bin/go"),
+                Path::new("/home/ctf"),
+                &tool,
+            )
+            .unwrap()
+        };
+        let ffuf = go("ffuf", "github.com/ffuf/ffuf/v2@latest");
+        let httpx = go(
+            "httpx",
+            "github.com/projectdiscovery/httpx/cmd/httpx@latest",
+        );
+        assert_eq!(InstallTask::batch(&[&ffuf, &httpx]), None);
+
+        // Tasks of different installers cannot be combined.
+        let nmap = task(PackageManager::APT, "Nmap", "nmap");
+        let gdb = task(PackageManager::DNF, "GDB", "gdb");
+        assert_eq!(InstallTask::batch(&[&nmap, &gdb]), None);
+    }
+
+    #[test]
+    fn test_from_source() {
+        let dirs = ProgramDirs::from_root("/home/ctf/.ctftools");
+        let source = ToolSourceMetadata::builder()
+            .git("https://github.com/RickdeJager/stegseek.git".to_string())
+            .git_ref("v0.6".to_string())
+            .build_system(BuildSystem::CMake)
+            .build();
+
+        let tool = ToolMetadata::builder()
+            .name("StegSeek".to_string())
+            .command("stegseek".to_string())
+            .source(source.clone())
+            .build();
+
+        assert_eq!(
+            InstallTask::from_source(&dirs, &tool),
+            Ok(InstallTask::Source {
+                source,
+                cache_dir: PathBuf::from("/home/ctf/.ctftools/cache/sources")
+                    .join(cache_key("https://github.com/RickdeJager/stegseek.git")),
+                prefix: PathBuf::from("/home/ctf/.ctftools/data/prefix"),
+                tool_name: "StegSeek".to_string(),
+            })
+        );
+
+        let tool = ToolMetadata::builder()
+            .name("foo".to_string())
+            .command("foo".to_string())
+            .build();
+
+        assert_eq!(
+            InstallTask::from_source(&dirs, &tool),
+            Err(InstallTaskError::CannotInstallTool {
+                tool_name: "foo".to_string()
+            })
+        );
+    }
 }
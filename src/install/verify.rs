@@ -0,0 +1,219 @@
+use anyhow::{Context, Result};
+use std::collections::HashSet;
+use std::path::Path;
+
+use crate::install::{PackageOrigin, resolve_package};
+use crate::pkg::{PackageManager, find_aur_packages};
+use crate::registry::{ToolMetadata, ToolType};
+
+/// Whether the package mapped to a tool exists in the repositories.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PackageStatus {
+    /// The package can be installed from the repositories.
+    Found,
+
+    /// The package could not be found, so installing the tool will fail.
+    Missing,
+
+    /// The tool has no package for this package manager. The installer will
+    /// fall back to its download instructions instead.
+    Unmapped,
+}
+
+/// The outcome of verifying the package mapping of a single tool.
+#[derive(Debug, PartialEq, Eq)]
+pub struct PackageVerification<'t> {
+    /// The tool being verified.
+    pub tool: &'t ToolMetadata,
+
+    /// Name of the package mapped to the tool and where it comes from.
+    pub package: Option<(String, PackageOrigin)>,
+
+    /// Whether the mapped package exists.
+    pub status: PackageStatus,
+}
+
+/// Checks the `packages` entry of every tool against the repository
+/// metadata of a package manager.
+///
+/// `exec` is the path to the package manager executable and AUR packages
+/// (Pacman only) are looked up with the AUR RPC interface at `aur_rpc_url`.
+pub fn verify_packages<'t>(
+    pkg_manager: PackageManager,
+    exec: &Path,
+    tools: &'t [ToolMetadata],
+    aur_rpc_url: &str,
+) -> Result<Vec<PackageVerification<'t>>> {
+    let tools = tools
+        .iter()
+        .filter(|v| matches!(v.kind, ToolType::Executable))
+        .collect::<Vec<_>>();
+
+    // AUR packages can be looked up all at once.
+    let aur_packages = tools
+        .iter()
+        .filter_map(|tool| match resolve_package(pkg_manager, tool) {
+            Some((package, PackageOrigin::AUR)) => Some(package),
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+
+    let found_aur_packages = if aur_packages.is_empty() {
+        HashSet::new()
+    } else {
+        find_aur_packages(aur_rpc_url, &aur_packages)?
+    };
+
+    let mut results = Vec::new();
+    for tool in tools {
+        let Some((package, origin)) = resolve_package(pkg_manager, tool) else {
+            results.push(PackageVerification {
+                tool,
+                package: None,
+                status: PackageStatus::Unmapped,
+            });
+            continue;
+        };
+
+        let exists = match origin {
+            PackageOrigin::AUR => found_aur_packages.contains(package),
+            PackageOrigin::Repository => pkg_manager
+                .package_exists(exec, package)
+                .with_context(|| format!("failed to look up package {package:?}"))?,
+        };
+
+        results.push(PackageVerification {
+            tool,
+            package: Some((package.to_string(), origin)),
+            status: if exists {
+                PackageStatus::Found
+            } else {
+                PackageStatus::Missing
+            },
+        });
+    }
+
+    Ok(results)
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use maplit::hashmap;
+    use pretty_assertions::assert_eq;
+    use std::collections::HashMap;
+    use std::path::{Path, PathBuf};
+    use tempdir::TempDir;
+
+    use crate::install::PackageOrigin;
+    use crate::install::verify::{PackageStatus, verify_packages};
+    use crate::pkg::PackageManager;
+    use crate::registry::ToolMetadata;
+    use crate::testing::{Response, serve, write_script};
+
+    /// Creates a fake executable that exits successfully only if its
+    /// last argument is one of the `known` packages.
+    fn fake_executable(dir: &Path, name: &str, known: &[&str]) -> PathBuf {
+        let script = format!(
+            "for last; do :; done\nfor pkg in {}; do\n  if [ \"$last\" = \"$pkg\" ]; then\n    echo \"Description : $pkg\"\n    exit 0\n  fi\ndone\nexit 1\n",
+            known.join(" ")
+        );
+        write_script(dir, name, &script)
+    }
+
+    fn tool(command: &str, packages: HashMap<String, String>) -> ToolMetadata {
+        ToolMetadata::builder()
+            .name(command.to_string())
+            .command(command.to_string())
+            .packages(packages)
+            .build()
+    }
+
+    #[test]
+    fn test_verify_packages_with_fake_pkg_manager() {
+        let dir = TempDir::new("ctftools_test").unwrap();
+        let exec = fake_executable(dir.path(), "dnf", &["foo", "bar-dnf"]);
+
+        let tools = vec![
+            tool("foo", hashmap! { "default".into() => "foo".into() }),
+            tool("bar", hashmap! { "dnf".into() => "bar-dnf".into() }),
+            tool("baz", hashmap! { "default".into() => "baz-typo".into() }),
+            tool("qux", HashMap::new()),
+        ];
+
+        let results = verify_packages(PackageManager::DNF, &exec, &tools, "").unwrap();
+        let statuses = results
+            .iter()
+            .map(|v| (v.tool.command.as_str(), v.status))
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            statuses,
+            [
+                ("foo", PackageStatus::Found),
+                ("bar", PackageStatus::Found),
+                ("baz", PackageStatus::Missing),
+                ("qux", PackageStatus::Unmapped),
+            ]
+        );
+        assert_eq!(
+            results[2].package,
+            Some(("baz-typo".to_string(), PackageOrigin::Repository))
+        );
+    }
+
+    #[test]
+    fn test_verify_packages_with_fake_apt_cache() {
+        let dir = TempDir::new("ctftools_test").unwrap();
+        let exec = dir.path().join("apt");
+
+        let script = "echo \"$2:\"\necho \"  Installed: (none)\"\nif [ \"$2\" = \"foo\" ]; then\n  echo \"  Candidate: 1.0\"\nelse\n  echo \"  Candidate: (none)\"\nfi\n";
+        write_script(dir.path(), "apt-cache", script);
+
+        let tools = vec![
+            tool("foo", hashmap! { "apt".into() => "foo".into() }),
+            tool("bar", hashmap! { "apt".into() => "bar".into() }),
+        ];
+
+        let results = verify_packages(PackageManager::APT, &exec, &tools, "").unwrap();
+        assert_eq!(results[0].status, PackageStatus::Found);
+        assert_eq!(results[1].status, PackageStatus::Missing);
+    }
+
+    #[test]
+    fn test_verify_aur_packages() {
+        // A tiny HTTP server that pretends to be the AUR RPC interface
+        // where only `foo-bin` package exists.
+        let (url, server) = serve(1, |_| {
+            Response::ok(
+                r#"{"resultcount":1,"results":[{"Name":"foo-bin"}],"type":"multiinfo","version":5}"#,
+            )
+        });
+        let rpc_url = format!("{url}/rpc/v5/info");
+
+        let dir = TempDir::new("ctftools_test").unwrap();
+        let exec = fake_executable(dir.path(), "pacman", &["baz"]);
+        let tools = vec![
+            tool("foo", hashmap! { "aur".into() => "foo-bin".into() }),
+            tool("bar", hashmap! { "aur".into() => "bar-git".into() }),
+            tool("baz", hashmap! { "pacman".into() => "baz".into() }),
+        ];
+
+        let results = verify_packages(PackageManager::Pacman, &exec, &tools, &rpc_url).unwrap();
+        let requests = server.join().unwrap();
+        assert!(requests[0].line.starts_with("GET /rpc/v5/info?"));
+
+        let statuses = results.iter().map(|v| v.status).collect::<Vec<_>>();
+        assert_eq!(
+            statuses,
+            [
+                PackageStatus::Found,
+                PackageStatus::Missing,
+                PackageStatus::Found
+            ]
+        );
+        assert_eq!(
+            results[0].package,
+            Some(("foo-bin".to_string(), PackageOrigin::AUR))
+        );
+    }
+}
@@ -8,8 +8,13 @@ cfg_if! {
 }
 
 pub mod cli;
+pub mod config;
+pub mod container;
 pub mod registry;
 
 pub mod env;
 pub mod process;
 pub mod util;
+
+#[cfg(test)]
+pub mod testing;
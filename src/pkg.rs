@@ -1,6 +1,16 @@
-use anyhow::Result;
+use anyhow::{Context, Result, bail};
 use cfg_if::cfg_if;
-use std::path::PathBuf;
+use log::debug;
+use serde::{Deserialize, Serialize, Serializer};
+use std::collections::{HashMap, HashSet};
+use std::ffi::OsString;
+use std::path::{Path, PathBuf};
+
+use crate::config::PackageScope;
+use crate::container::ContainerRuntime;
+use crate::process::ProcessBuilder;
+use crate::registry::LibraryLanguage;
+use crate::util::{find_first_match, which_opt};
 
 /// Represents the system's package manager.
 ///
@@ -9,7 +19,7 @@ use std::path::PathBuf;
 ///
 /// - **Windows**: `Chocolatey`, `WinGet`
 /// - **macOS**: `Homebrew`
-/// - **Linux**: `APT`, `DNF`, `Pacman`
+/// - **Linux**: `APT`, `DNF`, `Pacman`, `Zypper`, `APK`, `XBPS`, `Emerge`, `Eopkg`
 #[allow(clippy::upper_case_acronyms)]
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum PackageManager {
@@ -24,9 +34,29 @@ pub enum PackageManager {
     APT,
     DNF,
     Pacman,
+    Zypper,
+    APK,
+    XBPS,
+    Emerge,
+    Eopkg,
 }
 
 impl PackageManager {
+    /// Every supported package manager.
+    pub const ALL: [Self; 11] = [
+        Self::Chocolatey,
+        Self::WinGet,
+        Self::Homebrew,
+        Self::APT,
+        Self::DNF,
+        Self::Pacman,
+        Self::Zypper,
+        Self::APK,
+        Self::XBPS,
+        Self::Emerge,
+        Self::Eopkg,
+    ];
+
     /// Detects the system's preferred package manager.
     ///
     /// Returns `Ok(Some((PackageManager, PathBuf)))` if a package manager
@@ -37,17 +67,22 @@ impl PackageManager {
     /// Detection is performed based on the operating system and available
     /// binaries in the system PATH.
     pub fn detect() -> Result<Option<(Self, PathBuf)>> {
-        cfg_if! {
-            if #[cfg(target_os = "linux")] {
-                Self::detect_linux()
-            } else if #[cfg(target_os = "macos")] {
-                Self::detect_macos()
-            } else if #[cfg(target_os = "windows")] {
-                Self::detect_windows()
-            } else {
-                Ok(None)
+        find_first_match(Self::candidates())
+    }
+
+    /// Detects every package manager available in the system, in
+    /// the same order of preference as [`detect`](Self::detect).
+    ///
+    /// Some systems have more than one of them, such as
+    /// Homebrew installed on top of Fedora's DNF.
+    pub fn detect_all() -> Result<Vec<(Self, PathBuf)>> {
+        let mut found = Vec::new();
+        for (command, pkg_manager) in Self::candidates() {
+            if let Some(path) = which_opt(command)? {
+                found.push((*pkg_manager, path));
             }
         }
+        Ok(found)
     }
 
     /// Returns a human-friendly name for this package manager
@@ -61,6 +96,11 @@ impl PackageManager {
             Self::APT => "APT",
             Self::DNF => "DNF",
             Self::Pacman => "Pacman",
+            Self::Zypper => "Zypper",
+            Self::APK => "APK",
+            Self::XBPS => "XBPS",
+            Self::Emerge => "Portage",
+            Self::Eopkg => "eopkg",
         }
     }
 
@@ -78,29 +118,37 @@ impl PackageManager {
             Self::APT => "apt",
             Self::DNF => "dnf",
             Self::Pacman => "pacman",
+            Self::Zypper => "zypper",
+            Self::APK => "apk",
+            Self::XBPS => "xbps",
+            Self::Emerge => "emerge",
+            Self::Eopkg => "eopkg",
         }
     }
 
-    #[cfg(target_os = "macos")]
-    fn detect_macos() -> Result<Option<(Self, PathBuf)>> {
-        find_first_match(&[("brew", PackageManager::Homebrew)])
-    }
-
-    #[cfg(target_os = "linux")]
-    fn detect_linux() -> Result<Option<(Self, PathBuf)>> {
-        find_first_match(&[
-            ("apt", PackageManager::APT),
-            ("dnf", PackageManager::DNF),
-            ("pacman", PackageManager::Pacman),
-        ])
-    }
-
-    #[cfg(target_os = "windows")]
-    fn detect_windows() -> Result<Option<(Self, PathBuf)>> {
-        find_first_match(&[
-            ("choco", PackageManager::Chocolatey),
-            ("winget", PackageManager::WinGet),
-        ])
+    /// Gets the executables of the package managers supported
+    /// in the current operating system, in order of preference.
+    fn candidates() -> &'static [(&'static str, Self)] {
+        cfg_if! {
+            if #[cfg(target_os = "linux")] {
+                &[
+                    ("apt", Self::APT),
+                    ("dnf", Self::DNF),
+                    ("pacman", Self::Pacman),
+                    ("zypper", Self::Zypper),
+                    ("apk", Self::APK),
+                    ("xbps-install", Self::XBPS),
+                    ("emerge", Self::Emerge),
+                    ("eopkg", Self::Eopkg),
+                ]
+            } else if #[cfg(target_os = "macos")] {
+                &[("brew", Self::Homebrew)]
+            } else if #[cfg(target_os = "windows")] {
+                &[("choco", Self::Chocolatey), ("winget", Self::WinGet)]
+            } else {
+                &[]
+            }
+        }
     }
 }
 
@@ -111,11 +159,307 @@ impl PackageManager {
     pub const fn needs_privilege(&self) -> bool {
         match self {
             Self::Chocolatey | Self::WinGet | Self::Homebrew => false,
-            Self::APT | Self::DNF | Self::Pacman => true,
+            Self::APT
+            | Self::DNF
+            | Self::Pacman
+            | Self::Zypper
+            | Self::APK
+            | Self::XBPS
+            | Self::Emerge
+            | Self::Eopkg => true,
         }
     }
 }
 
+impl PackageManager {
+    /// Builds a command that prints the repository metadata of
+    /// a package from this package manager.
+    ///
+    /// `exec` is the path to the package manager executable. Some
+    /// package managers ship their query tool as a separate binary
+    /// (e.g. `apt-cache` for APT) which is expected to live next to it.
+    ///
+    /// Portage only searches by pattern, so the package name is turned into
+    /// a regular expression that only matches the package itself.
+    #[must_use]
+    pub fn info_command(&self, exec: &Path, package: &str) -> ProcessBuilder {
+        let (program, args): (PathBuf, &[&str]) = match self {
+            Self::APT => (exec.with_file_name("apt-cache"), &["show"]),
+            Self::DNF => (exec.to_path_buf(), &["info"]),
+            Self::Pacman => (exec.to_path_buf(), &["-Si"]),
+            Self::Zypper => (exec.to_path_buf(), &["info"]),
+            Self::APK => (exec.to_path_buf(), &["info", "--description"]),
+            Self::XBPS => (exec.with_file_name("xbps-query"), &["--repository"]),
+            Self::Emerge => (exec.to_path_buf(), &["--search"]),
+            Self::Eopkg => (exec.to_path_buf(), &["info"]),
+            Self::Homebrew => (exec.to_path_buf(), &["info"]),
+            Self::Chocolatey => (exec.to_path_buf(), &["info"]),
+            Self::WinGet => (exec.to_path_buf(), &["show", "--exact"]),
+        };
+
+        let mut builder = ProcessBuilder::new(program);
+        builder.args(args);
+        if *self == Self::Emerge {
+            builder.arg(exact_emerge_search_key(package));
+        } else {
+            builder.arg(package);
+        }
+        builder
+    }
+
+    /// Checks whether the output of [`info_command`](PackageManager::info_command)
+    /// tells that the package could not be found, for package managers that
+    /// exit successfully either way.
+    fn is_not_found_output(&self, package: &str, stdout: &str) -> bool {
+        match self {
+            // Chocolatey exits successfully even if there are no matches.
+            Self::Chocolatey => stdout.contains("0 packages found"),
+            // Portage always reports how many packages matched the search.
+            Self::Emerge => stdout
+                .lines()
+                .find_map(|line| line.trim().strip_prefix("[ Applications found :"))
+                .and_then(|v| v.trim_end_matches(']').trim().parse::<u32>().ok())
+                .is_none_or(|count| count == 0),
+            // Descriptions may mention "not found" too, so only the exact lines match.
+            Self::Zypper => has_line(stdout, &format!("package '{package}' not found.")),
+            Self::Eopkg => has_line(stdout, &format!("{package}: Package not found")),
+            // Every package found starts with a "<name>-<version> description:" line.
+            Self::APK => !stdout.lines().any(|line| line.ends_with(" description:")),
+            _ => false,
+        }
+    }
+
+    /// Queries the repositories of this package manager for a package.
+    ///
+    /// Returns `Ok(None)` if the package manager could not find the package.
+    pub fn query_package(&self, exec: &Path, package: &str) -> Result<Option<PackageInfo>> {
+        let builder = self.info_command(exec, package);
+        let output = builder.output()?;
+
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        if !output.status.success()
+            || self.is_not_found_output(package, &stdout)
+            || stdout.trim().is_empty()
+        {
+            return Ok(None);
+        }
+
+        Ok(Some(PackageInfo {
+            name: package.to_string(),
+            summary: parse_package_summary(&stdout),
+        }))
+    }
+}
+
+impl PackageManager {
+    /// Checks whether a package can be installed from the repositories
+    /// of this package manager.
+    pub fn package_exists(&self, exec: &Path, package: &str) -> Result<bool> {
+        match self {
+            // `apt-cache show` also succeeds for virtual packages which cannot be
+            // installed by themselves, so we have to check for its install candidate.
+            Self::APT => {
+                let mut builder = ProcessBuilder::new(exec.with_file_name("apt-cache"));
+                builder.args(&["policy", package]);
+
+                let output = builder.exec_with_output()?;
+                let stdout = String::from_utf8_lossy(&output.stdout);
+                Ok(parse_apt_candidate(&stdout).is_some())
+            }
+            _ => Ok(self.query_package(exec, package)?.is_some()),
+        }
+    }
+}
+
+impl PackageManager {
+    /// Builds a command that prints the names of every installed package,
+    /// one package per line.
+    ///
+    /// Returns `None` if this package manager cannot list its installed
+    /// packages in a machine-readable format.
+    #[must_use]
+    pub fn list_installed_command(&self, exec: &Path) -> Option<ProcessBuilder> {
+        let (program, args): (PathBuf, &[&str]) = match self {
+            Self::APT => (
+                exec.with_file_name("dpkg-query"),
+                &["--show", "--showformat=${Package}\\n"],
+            ),
+            Self::DNF | Self::Zypper => (
+                exec.with_file_name("rpm"),
+                &["--query", "--all", "--queryformat=%{NAME}\\n"],
+            ),
+            Self::Pacman => (exec.to_path_buf(), &["-Qq"]),
+            Self::APK => (exec.to_path_buf(), &["info", "--quiet"]),
+            Self::Homebrew => (exec.to_path_buf(), &["list", "-1"]),
+            Self::Chocolatey => (exec.to_path_buf(), &["list", "--limit-output"]),
+            Self::WinGet | Self::XBPS | Self::Emerge | Self::Eopkg => return None,
+        };
+
+        let mut builder = ProcessBuilder::new(program);
+        builder.args(args);
+        Some(builder)
+    }
+
+    /// Lists the names of every package installed with this package manager.
+    ///
+    /// It returns an empty set if the package manager does not support it.
+    pub fn installed_packages(&self, exec: &Path) -> Result<HashSet<String>> {
+        let Some(builder) = self.list_installed_command(exec) else {
+            debug!(
+                "{} cannot list installed packages; skipping",
+                self.as_display_name()
+            );
+            return Ok(HashSet::new());
+        };
+
+        let output = builder.exec_with_output()?;
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        Ok(parse_installed_packages(&stdout))
+    }
+}
+
+impl PackageManager {
+    /// Builds a command that installs downloaded package files
+    /// (e.g. `.deb` for APT) along with their dependencies.
+    ///
+    /// Returns `None` if this package manager cannot install every file.
+    #[must_use]
+    pub fn install_files_command(&self, exec: &Path, files: &[PathBuf]) -> Option<ProcessBuilder> {
+        let extension = match self {
+            Self::APT => "deb",
+            Self::DNF => "rpm",
+            _ => return None,
+        };
+
+        if files.is_empty()
+            || files
+                .iter()
+                .any(|v| v.extension().is_none_or(|v| v != extension))
+        {
+            return None;
+        }
+
+        let mut builder = ProcessBuilder::new(exec);
+        builder.args(&["install", "-y"]).args(files);
+        Some(builder)
+    }
+
+    /// Builds a command that downloads the package files of a package,
+    /// along with its dependencies that are not installed yet, into
+    /// `dir` without installing them.
+    ///
+    /// Returns `None` if this package manager cannot download `.deb`
+    /// or `.rpm` package files.
+    #[must_use]
+    pub fn download_only_command(
+        &self,
+        exec: &Path,
+        package: &str,
+        dir: &Path,
+    ) -> Option<ProcessBuilder> {
+        let mut builder = match self {
+            Self::APT => {
+                let mut archives = OsString::from("Dir::Cache::archives=");
+                archives.push(dir);
+
+                let mut builder = ProcessBuilder::new(exec.with_file_name("apt-get"));
+                builder.args(&["install", "--download-only", "--reinstall", "-y", "-o"]);
+                builder.arg(archives);
+                builder
+            }
+            Self::DNF => {
+                let mut builder = ProcessBuilder::new(exec);
+                builder
+                    .args(&["download", "--resolve", "--destdir"])
+                    .arg(dir);
+                builder
+            }
+            _ => return None,
+        };
+
+        builder.arg(package);
+        Some(builder)
+    }
+
+    /// Returns whether [downloading package files](PackageManager::download_only_command)
+    /// with this package manager requires elevated privileges.
+    #[must_use]
+    pub const fn download_needs_privilege(&self) -> bool {
+        matches!(self, Self::APT)
+    }
+}
+
+/// Parses the output of [`PackageManager::list_installed_command`].
+///
+/// Chocolatey prints `<name>|<version>` lines, so anything after
+/// the `|` separator is ignored.
+fn parse_installed_packages(output: &str) -> HashSet<String> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let name = line.split('|').next()?.trim();
+            (!name.is_empty()).then(|| name.to_string())
+        })
+        .collect()
+}
+
+/// Finds the install candidate version from the output of `apt-cache policy`.
+fn parse_apt_candidate(output: &str) -> Option<&str> {
+    output.lines().find_map(|line| {
+        let version = line.trim().strip_prefix("Candidate:")?.trim();
+        (!version.is_empty() && version != "(none)").then_some(version)
+    })
+}
+
+/// Repository metadata of a package found by [`PackageManager::query_package`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PackageInfo {
+    /// Name of the package in the repository.
+    pub name: String,
+
+    /// A short description of the package, if the package
+    /// manager provides one.
+    pub summary: Option<String>,
+}
+
+/// Finds the description (or summary) field from the output of
+/// [`PackageManager::info_command`].
+///
+/// Most package managers print their metadata in `Key : Value` lines.
+fn parse_package_summary(output: &str) -> Option<String> {
+    output.lines().find_map(|line| {
+        let (key, value) = line.split_once(':')?;
+        let key = key.trim().to_ascii_lowercase();
+        let value = value.trim();
+        if (key.starts_with("description") || key == "summary") && !value.is_empty() {
+            Some(value.to_string())
+        } else {
+            None
+        }
+    })
+}
+
+/// Checks whether any line of the output equals `expected`,
+/// ignoring the whitespace around it.
+fn has_line(output: &str, expected: &str) -> bool {
+    output.lines().any(|line| line.trim() == expected)
+}
+
+/// Turns a package name into a search key of `emerge --search` that
+/// only matches that package. Names with a category (e.g. `net-analyzer/nmap`)
+/// are matched against the full name of the package.
+fn exact_emerge_search_key(package: &str) -> String {
+    let mut key = String::from(if package.contains('/') { "%@^" } else { "%^" });
+    for c in package.chars() {
+        if "\\.+*?()|[]{}^$".contains(c) {
+            key.push('\\');
+        }
+        key.push(c);
+    }
+    key.push('$');
+    key
+}
+
 /// Represents an AUR helper, which are user-space wrappers for Pacman
 /// commonly used on Arch Linux distributions.
 ///
@@ -149,15 +493,1021 @@ impl AurHelper {
     }
 }
 
-/// Searches the system for the first matching the binary.
-fn find_first_match<T: Copy>(candidates: &[(&str, T)]) -> Result<Option<(T, PathBuf)>> {
-    use crate::util::which_opt;
+/// Represents a package manager of a programming language ecosystem.
+///
+/// Unlike [`PackageManager`], these install packages into the user's
+/// home directory so they never require elevated privileges.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum LanguagePackageManager {
+    /// Installs Python applications into their own virtual environments.
+    Pipx,
+
+    /// Python's package installer.
+    Pip,
+
+    /// Ruby's package manager.
+    Gem,
+
+    /// Rust's package manager.
+    Cargo,
+
+    /// Go's toolchain, which installs Go programs with `go install`.
+    Go,
+
+    /// Node.js package manager.
+    Npm,
+}
+
+impl LanguagePackageManager {
+    /// Every supported language package manager.
+    pub const ALL: [Self; 6] = [
+        Self::Pipx,
+        Self::Pip,
+        Self::Gem,
+        Self::Cargo,
+        Self::Go,
+        Self::Npm,
+    ];
+
+    /// Detects every language package manager available in the system.
+    ///
+    /// Detection is performed based on the available binaries in the system PATH.
+    /// Pip is left out when its Python installation is externally managed
+    /// (PEP 668), since it refuses to install packages there.
+    pub fn detect_all() -> Result<Vec<(Self, PathBuf)>> {
+        let candidates: [&[(&str, Self)]; 6] = [
+            &[("pipx", Self::Pipx)],
+            &[("pip3", Self::Pip), ("pip", Self::Pip)],
+            &[("gem", Self::Gem)],
+            &[("cargo", Self::Cargo)],
+            &[("go", Self::Go)],
+            &[("npm", Self::Npm)],
+        ];
+
+        let mut found = Vec::new();
+        for candidates in candidates {
+            if let Some(matched) = find_first_match(candidates)? {
+                if matched.0 == Self::Pip && is_externally_managed(&matched.1) {
+                    debug!("skipping {:?}: its Python is externally managed", matched.1);
+                    continue;
+                }
+                found.push(matched);
+            }
+        }
+        Ok(found)
+    }
+
+    /// Returns a human-friendly name for this package manager
+    /// suitable for UI display.
+    #[must_use]
+    pub fn as_display_name(&self) -> &'static str {
+        match self {
+            Self::Pipx => "pipx",
+            Self::Pip => "pip",
+            Self::Gem => "RubyGems",
+            Self::Cargo => "Cargo",
+            Self::Go => "Go",
+            Self::Npm => "npm",
+        }
+    }
+
+    /// Returns the string key associated with this package manager
+    /// in the `packages` mapping of a tool.
+    #[must_use]
+    pub fn as_registry_key(&self) -> &'static str {
+        match self {
+            Self::Pipx => "pipx",
+            Self::Pip => "pip",
+            Self::Gem => "gem",
+            Self::Cargo => "cargo",
+            Self::Go => "go",
+            Self::Npm => "npm",
+        }
+    }
+
+    /// Returns the language whose libraries are installed with this
+    /// package manager, if it can install libraries at all.
+    ///
+    /// pipx only installs applications in isolated environments,
+    /// so their modules cannot be imported by the interpreter.
+    #[must_use]
+    pub const fn language(&self) -> Option<LibraryLanguage> {
+        match self {
+            Self::Pip => Some(LibraryLanguage::Python),
+            Self::Gem => Some(LibraryLanguage::Ruby),
+            Self::Pipx | Self::Cargo | Self::Go | Self::Npm => None,
+        }
+    }
+
+    /// Finds the package that provides a tool from the tool's `packages` mapping.
+    ///
+    /// pipx falls back to the `pip` key since it installs the same packages.
+    #[must_use]
+    pub fn resolve_package<'t>(&self, packages: &'t HashMap<String, String>) -> Option<&'t str> {
+        let package = packages.get(self.as_registry_key());
+        let package = match self {
+            Self::Pipx => package.or_else(|| packages.get(Self::Pip.as_registry_key())),
+            _ => package,
+        };
+        package.map(String::as_str)
+    }
 
-    for (cmd, pm) in candidates {
-        if let Some(path) = which_opt(cmd)? {
-            return Ok(Some((*pm, path)));
+    /// Returns the arguments to install a package for the current user.
+    ///
+    /// `home` is the user's home directory. npm installs packages
+    /// globally, so its prefix is moved into `~/.local` instead.
+    #[must_use]
+    pub fn install_args(&self, package: &str, home: &Path) -> Vec<String> {
+        match self {
+            Self::Pipx | Self::Cargo => vec!["install".into(), package.into()],
+            Self::Pip => vec!["install".into(), "--user".into(), package.into()],
+            Self::Gem => vec!["install".into(), "--user-install".into(), package.into()],
+            // `go install` requires a version if it is run outside of a module.
+            Self::Go if !package.contains('@') => {
+                vec!["install".into(), format!("{package}@latest")]
+            }
+            Self::Go => vec!["install".into(), package.into()],
+            Self::Npm => vec![
+                "install".into(),
+                "--global".into(),
+                "--prefix".into(),
+                home.join(".local").to_string_lossy().into_owned(),
+                package.into(),
+            ],
         }
     }
 
-    Ok(None)
+    /// Returns the directory where this package manager puts the
+    /// executables of the packages installed for the current user.
+    ///
+    /// `exec` is the path to the package manager executable and `home`
+    /// is the user's home directory.
+    pub fn user_bin_dir(&self, exec: &Path, home: &Path) -> Result<Option<PathBuf>> {
+        let from_var = |name: &str| std::env::var_os(name).filter(|v| !v.is_empty());
+        let dir = match self {
+            Self::Pipx => from_var("PIPX_BIN_DIR")
+                .map(PathBuf::from)
+                .unwrap_or_else(|| home.join(".local").join("bin")),
+            Self::Pip => home.join(".local").join("bin"),
+            Self::Cargo => from_var("CARGO_HOME")
+                .map(PathBuf::from)
+                .unwrap_or_else(|| home.join(".cargo"))
+                .join("bin"),
+            Self::Go => match (from_var("GOBIN"), from_var("GOPATH")) {
+                (Some(bin), _) => PathBuf::from(bin),
+                (None, Some(path)) => std::env::split_paths(&path)
+                    .next()
+                    .unwrap_or_else(|| home.join("go"))
+                    .join("bin"),
+                (None, None) => home.join("go").join("bin"),
+            },
+            Self::Npm if cfg!(windows) => home.join(".local"),
+            Self::Npm => home.join(".local").join("bin"),
+            Self::Gem => {
+                let mut builder = ProcessBuilder::new(exec);
+                builder.args(&["environment", "user_gemhome"]);
+
+                let output = builder.exec_with_output()?;
+                let gemhome = String::from_utf8_lossy(&output.stdout);
+                let gemhome = gemhome.trim();
+                if gemhome.is_empty() {
+                    return Ok(None);
+                }
+                Path::new(gemhome).join("bin")
+            }
+        };
+        Ok(Some(dir))
+    }
+}
+
+/// Checks whether the Python installation next to `pip` is marked as
+/// externally managed, as described in PEP 668.
+///
+/// Read more at: https://peps.python.org/pep-0668/
+fn is_externally_managed(pip: &Path) -> bool {
+    let python = pip.with_file_name(if cfg!(windows) {
+        "python.exe"
+    } else {
+        "python3"
+    });
+    let mut builder = ProcessBuilder::new(&python);
+    builder.args(&[
+        "-c",
+        "import sysconfig; print(sysconfig.get_path('stdlib'))",
+    ]);
+
+    let Ok(output) = builder.exec_with_output() else {
+        return false;
+    };
+    let stdlib = String::from_utf8_lossy(&output.stdout);
+    let stdlib = stdlib.trim();
+    !stdlib.is_empty() && Path::new(stdlib).join("EXTERNALLY-MANAGED").is_file()
+}
+
+/// Represents a universal package manager, which installs packages
+/// from its own repositories regardless of the Linux distribution.
+///
+/// Unlike [`PackageManager`], these can coexist with the system's
+/// package manager and are tried after it by default.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum UniversalPackageManager {
+    /// The Nix package manager, which installs packages from nixpkgs.
+    Nix,
+
+    /// Installs sandboxed applications, mostly GUI ones, from Flathub.
+    Flatpak,
+
+    /// Installs sandboxed applications from the Snap Store.
+    Snap,
+
+    /// Homebrew running on Linux.
+    Linuxbrew,
+}
+
+impl UniversalPackageManager {
+    /// Every supported universal package manager.
+    pub const ALL: [Self; 4] = [Self::Nix, Self::Flatpak, Self::Snap, Self::Linuxbrew];
+
+    /// Detects every universal package manager available in the system.
+    ///
+    /// Detection is performed based on the available binaries in the system
+    /// PATH, then in the default install locations of Nix and Linuxbrew since
+    /// their shell setup may not have been loaded. `home` is the user's home
+    /// directory, if there is any.
+    pub fn detect_all(home: Option<&Path>) -> Result<Vec<(Self, PathBuf)>> {
+        let candidates: &[(&str, Self)] = if cfg!(target_os = "linux") {
+            &[
+                ("nix-env", Self::Nix),
+                ("flatpak", Self::Flatpak),
+                ("snap", Self::Snap),
+                ("brew", Self::Linuxbrew),
+            ]
+        } else if cfg!(target_os = "macos") {
+            &[("nix-env", Self::Nix)]
+        } else {
+            &[]
+        };
+
+        let mut found = Vec::new();
+        for (command, pkg_manager) in candidates {
+            if let Some(matched) = find_first_match(&[(*command, *pkg_manager)])? {
+                found.push(matched);
+                continue;
+            }
+
+            let fallback = pkg_manager
+                .bin_dirs(home)
+                .into_iter()
+                .map(|dir| dir.join(command))
+                .find(|path| path.is_file());
+
+            if let Some(path) = fallback {
+                found.push((*pkg_manager, path));
+            }
+        }
+        Ok(found)
+    }
+
+    /// Returns a human-friendly name for this package manager
+    /// suitable for UI display.
+    #[must_use]
+    pub fn as_display_name(&self) -> &'static str {
+        match self {
+            Self::Nix => "Nix",
+            Self::Flatpak => "Flatpak",
+            Self::Snap => "Snap",
+            Self::Linuxbrew => "Homebrew",
+        }
+    }
+
+    /// Returns the string key associated with this package manager
+    /// in the `packages` mapping of a tool.
+    #[must_use]
+    pub fn as_registry_key(&self) -> &'static str {
+        match self {
+            Self::Nix => "nix",
+            Self::Flatpak => "flatpak",
+            Self::Snap => "snap",
+            Self::Linuxbrew => "linuxbrew",
+        }
+    }
+
+    /// Finds the package that provides a tool from the tool's `packages` mapping.
+    ///
+    /// Linuxbrew falls back to the `homebrew` key since most formulae
+    /// are available on both macOS and Linux.
+    #[must_use]
+    pub fn resolve_package<'t>(&self, packages: &'t HashMap<String, String>) -> Option<&'t str> {
+        let package = packages.get(self.as_registry_key());
+        let package = match self {
+            Self::Linuxbrew => package.or_else(|| packages.get("homebrew")),
+            _ => package,
+        };
+        package.map(String::as_str)
+    }
+
+    /// Gets the scope that packages are installed in, given the scope
+    /// preferred by the user.
+    ///
+    /// Snaps are always installed for the whole system while Homebrew
+    /// refuses to run as root, so packages are installed for the user.
+    #[must_use]
+    pub const fn scope(&self, preferred: PackageScope) -> PackageScope {
+        match self {
+            Self::Nix | Self::Flatpak => preferred,
+            Self::Snap => PackageScope::System,
+            Self::Linuxbrew => PackageScope::User,
+        }
+    }
+
+    /// Returns the arguments to install a package in the given [scope](Self::scope).
+    #[must_use]
+    pub fn install_args(&self, package: &str, scope: PackageScope) -> Vec<String> {
+        match (self, scope) {
+            (Self::Nix, PackageScope::User) => {
+                vec![
+                    "--install".into(),
+                    "--attr".into(),
+                    format!("nixpkgs.{package}"),
+                ]
+            }
+            (Self::Nix, PackageScope::System) => vec![
+                "--profile".into(),
+                NIX_SYSTEM_PROFILE.into(),
+                "--install".into(),
+                "--attr".into(),
+                format!("nixpkgs.{package}"),
+            ],
+            (Self::Flatpak, scope) => vec![
+                "install".into(),
+                match scope {
+                    PackageScope::User => "--user".into(),
+                    PackageScope::System => "--system".into(),
+                },
+                "--noninteractive".into(),
+                "flathub".into(),
+                package.into(),
+            ],
+            (Self::Snap | Self::Linuxbrew, _) => vec!["install".into(), package.into()],
+        }
+    }
+
+    /// Returns the directories where this package manager exposes the
+    /// executables of its packages in every scope, which may not be in
+    /// `PATH`. `home` is the user's home directory, if there is any.
+    #[must_use]
+    pub fn bin_dirs(&self, home: Option<&Path>) -> Vec<PathBuf> {
+        // These package managers only run on Unix systems.
+        let in_home = |path: &str| home.map(|home| home.join(path));
+        let dirs = match self {
+            Self::Nix => vec![
+                in_home(".nix-profile/bin"),
+                Some(Path::new(NIX_SYSTEM_PROFILE).join("bin")),
+            ],
+            Self::Flatpak => vec![
+                in_home(".local/share/flatpak/exports/bin"),
+                Some(PathBuf::from("/var/lib/flatpak/exports/bin")),
+            ],
+            Self::Snap => vec![Some(PathBuf::from("/snap/bin"))],
+            Self::Linuxbrew => vec![
+                Some(PathBuf::from("/home/linuxbrew/.linuxbrew/bin")),
+                in_home(".linuxbrew/bin"),
+            ],
+        };
+        dirs.into_iter().flatten().collect()
+    }
+
+    /// Returns the names that a package exposes a tool under in the
+    /// [bin directories](Self::bin_dirs).
+    ///
+    /// Flatpak exposes applications under their application ID
+    /// (e.g. `org.wireshark.Wireshark`) instead of their command.
+    #[must_use]
+    pub fn exported_names<'t>(&self, command: &'t str, package: Option<&'t str>) -> Vec<&'t str> {
+        match (self, package) {
+            (Self::Flatpak, Some(package)) => vec![package, command],
+            _ => vec![command],
+        }
+    }
+}
+
+/// Profile of Nix packages installed for every user of the system.
+const NIX_SYSTEM_PROFILE: &str = "/nix/var/nix/profiles/default";
+
+/// Anything that can install a tool from a package, which is recorded
+/// in an [`InstallTask`](crate::install::InstallTask) as the source
+/// the tool is installed from.
+///
+/// Only the system, universal and language package managers can be
+/// [ordered by the user](crate::config::Config::installers). AUR helpers
+/// and container runtimes are only used for AUR packages and container
+/// tools respectively.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Installer {
+    System(PackageManager),
+    Aur(AurHelper),
+    Universal(UniversalPackageManager),
+    Language(LanguagePackageManager),
+    Container(ContainerRuntime),
+}
+
+impl Installer {
+    /// Returns a human-friendly name for this installer
+    /// suitable for UI display.
+    #[must_use]
+    pub fn as_display_name(&self) -> &'static str {
+        match self {
+            Self::System(pm) => pm.as_display_name(),
+            Self::Aur(AurHelper::Paru) => "paru",
+            Self::Aur(AurHelper::Yay) => "yay",
+            Self::Universal(pm) => pm.as_display_name(),
+            Self::Language(pm) => pm.as_display_name(),
+            Self::Container(runtime) => runtime.as_display_name(),
+        }
+    }
+
+    /// Returns the key that the user refers to this installer with,
+    /// which is also its key in the `packages` mapping of a tool.
+    #[must_use]
+    pub fn as_registry_key(&self) -> &'static str {
+        match self {
+            Self::System(pm) => pm.as_registry_key(),
+            Self::Aur(..) => "aur",
+            Self::Universal(pm) => pm.as_registry_key(),
+            Self::Language(pm) => pm.as_registry_key(),
+            Self::Container(ContainerRuntime::Docker) => "docker",
+            Self::Container(ContainerRuntime::Podman) => "podman",
+        }
+    }
+
+    /// Every installer that can be [ordered by the user](crate::config::Config::installers),
+    /// which are the system, universal and language package managers.
+    pub fn orderable() -> impl Iterator<Item = Self> {
+        let system = PackageManager::ALL.into_iter().map(Self::System);
+        let universal = UniversalPackageManager::ALL
+            .into_iter()
+            .map(Self::Universal);
+        let language = LanguagePackageManager::ALL.into_iter().map(Self::Language);
+        system.chain(universal).chain(language)
+    }
+
+    /// Returns whether this installer can install more than
+    /// one package in a single invocation.
+    ///
+    /// WinGet only accepts one package to install at a time, `go install`
+    /// refuses packages of different modules in a single invocation and
+    /// container runtimes only pull one image at a time.
+    #[must_use]
+    pub const fn can_install_many(&self) -> bool {
+        !matches!(
+            self,
+            Self::System(PackageManager::WinGet)
+                | Self::Language(LanguagePackageManager::Go)
+                | Self::Container(..)
+        )
+    }
+
+    /// Sorts the installers by the `priority` of the user, which lists
+    /// the [keys](Self::as_registry_key) of the installers to try first.
+    ///
+    /// Installers that are not listed keep their order after the listed ones.
+    pub fn sort_by_priority<T>(installers: &mut [(Self, T)], priority: &[String]) {
+        installers.sort_by_key(|(installer, _)| {
+            priority
+                .iter()
+                .position(|key| key == installer.as_registry_key())
+                .unwrap_or(usize::MAX)
+        });
+    }
+}
+
+impl Serialize for Installer {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.as_registry_key())
+    }
+}
+
+/// The default endpoint of the AUR RPC interface used to look up packages.
+///
+/// Read more at: https://wiki.archlinux.org/title/Aurweb_RPC_interface
+pub const DEFAULT_AUR_RPC_URL: &str = "https://aur.archlinux.org/rpc/v5/info";
+
+/// Looks up the given packages from the Arch User Repository (AUR)
+/// with its RPC interface located at `rpc_url`.
+///
+/// Returns the names of the packages that exist in the AUR.
+pub fn find_aur_packages(rpc_url: &str, packages: &[&str]) -> Result<HashSet<String>> {
+    #[derive(Deserialize)]
+    struct InfoResponse {
+        #[serde(default)]
+        error: Option<String>,
+        #[serde(default)]
+        results: Vec<InfoResult>,
+    }
+
+    #[derive(Deserialize)]
+    struct InfoResult {
+        #[serde(rename = "Name")]
+        name: String,
+    }
+
+    if packages.is_empty() {
+        return Ok(HashSet::new());
+    }
+
+    let query = packages.iter().map(|v| ("arg[]", *v)).collect::<Vec<_>>();
+    let rt = tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()
+        .context("failed to build tokio runtime for AUR lookups")?;
+
+    debug!(
+        "looking up {} package(s) from AUR: {rpc_url}",
+        packages.len()
+    );
+    let body = rt.block_on(async {
+        let response = reqwest::Client::new()
+            .get(rpc_url)
+            .query(&query)
+            .send()
+            .await?
+            .error_for_status()?;
+
+        response.bytes().await
+    });
+    let body = body.context("AUR RPC request failed")?;
+
+    let response: InfoResponse =
+        serde_json::from_slice(&body).context("got an invalid response from AUR RPC")?;
+
+    if let Some(error) = response.error {
+        bail!("AUR RPC returned an error: {error}");
+    }
+
+    Ok(response.results.into_iter().map(|v| v.name).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use maplit::hashmap;
+    use std::collections::HashSet;
+    use std::path::{Path, PathBuf};
+
+    use super::{
+        Installer, LanguagePackageManager, PackageManager, UniversalPackageManager,
+        is_externally_managed, parse_apt_candidate, parse_installed_packages,
+        parse_package_summary,
+    };
+    use crate::config::PackageScope;
+
+    #[test]
+    fn test_query_commands() {
+        let info =
+            |pm: PackageManager, exec: &str| pm.info_command(Path::new(exec), "nmap").to_string();
+        let list = |pm: PackageManager, exec: &str| {
+            pm.list_installed_command(Path::new(exec))
+                .map(|v| v.to_string())
+        };
+
+        assert_eq!(
+            info(PackageManager::Zypper, "/usr/bin/zypper"),
+            "/usr/bin/zypper info nmap"
+        );
+        assert_eq!(
+            info(PackageManager::APK, "/sbin/apk"),
+            "/sbin/apk info --description nmap"
+        );
+        assert_eq!(
+            info(PackageManager::XBPS, "/usr/bin/xbps-install"),
+            "/usr/bin/xbps-query --repository nmap"
+        );
+        assert_eq!(
+            info(PackageManager::Emerge, "/usr/bin/emerge"),
+            "/usr/bin/emerge --search '%^nmap$'"
+        );
+        assert_eq!(
+            PackageManager::Emerge
+                .info_command(Path::new("/usr/bin/emerge"), "sys-devel/gcc++")
+                .to_string(),
+            "/usr/bin/emerge --search '%@^sys-devel/gcc\\+\\+$'"
+        );
+
+        assert_eq!(
+            list(PackageManager::Zypper, "/usr/bin/zypper").as_deref(),
+            Some("/usr/bin/rpm --query --all '--queryformat=%{NAME}\\n'")
+        );
+        assert_eq!(
+            list(PackageManager::APK, "/sbin/apk").as_deref(),
+            Some("/sbin/apk info --quiet")
+        );
+        assert_eq!(list(PackageManager::Emerge, "/usr/bin/emerge"), None);
+    }
+
+    #[test]
+    fn test_install_files_command() {
+        let install = |pm: PackageManager, exec: &str, files: &[&str]| {
+            let files: Vec<PathBuf> = files.iter().map(PathBuf::from).collect();
+            pm.install_files_command(Path::new(exec), &files)
+                .map(|v| v.to_string())
+        };
+
+        assert_eq!(
+            install(
+                PackageManager::APT,
+                "/usr/bin/apt",
+                &["/tmp/x/downloaded.deb"]
+            )
+            .as_deref(),
+            Some("/usr/bin/apt install -y /tmp/x/downloaded.deb")
+        );
+        assert_eq!(
+            install(
+                PackageManager::DNF,
+                "/usr/bin/dnf",
+                &["/tmp/x/downloaded.rpm"]
+            )
+            .as_deref(),
+            Some("/usr/bin/dnf install -y /tmp/x/downloaded.rpm")
+        );
+        assert_eq!(
+            install(
+                PackageManager::APT,
+                "/usr/bin/apt",
+                &["/tmp/a.deb", "/tmp/b.deb"]
+            )
+            .as_deref(),
+            Some("/usr/bin/apt install -y /tmp/a.deb /tmp/b.deb")
+        );
+        assert_eq!(
+            install(
+                PackageManager::APT,
+                "/usr/bin/apt",
+                &["/tmp/x/downloaded.rpm"]
+            ),
+            None
+        );
+        assert_eq!(
+            install(
+                PackageManager::APT,
+                "/usr/bin/apt",
+                &["/tmp/a.deb", "/tmp/b"]
+            ),
+            None
+        );
+        assert_eq!(install(PackageManager::APT, "/usr/bin/apt", &[]), None);
+        assert_eq!(
+            install(
+                PackageManager::Pacman,
+                "/usr/bin/pacman",
+                &["/tmp/x/downloaded.deb"]
+            ),
+            None
+        );
+    }
+
+    #[test]
+    fn test_download_only_command() {
+        let download = |pm: PackageManager, exec: &str| {
+            pm.download_only_command(Path::new(exec), "nmap", Path::new("/tmp/pkgs"))
+                .map(|v| v.to_string())
+        };
+
+        assert_eq!(
+            download(PackageManager::APT, "/usr/bin/apt").as_deref(),
+            Some(
+                "/usr/bin/apt-get install --download-only --reinstall -y \
+                -o 'Dir::Cache::archives=/tmp/pkgs' nmap"
+            )
+        );
+        assert_eq!(
+            download(PackageManager::DNF, "/usr/bin/dnf").as_deref(),
+            Some("/usr/bin/dnf download --resolve --destdir /tmp/pkgs nmap")
+        );
+        assert_eq!(download(PackageManager::Pacman, "/usr/bin/pacman"), None);
+
+        assert!(PackageManager::APT.download_needs_privilege());
+        assert!(!PackageManager::DNF.download_needs_privilege());
+    }
+
+    #[test]
+    fn test_not_found_output() {
+        let emerge_found = "[ Results for search key : ^nmap$ ]\n\
+            [ Applications found : 1 ]\n\n\
+            *  net-analyzer/nmap\n      Description:   Network exploration tool\n";
+        let emerge_missing = "[ Results for search key : ^foo$ ]\n[ Applications found : 0 ]\n";
+        assert!(!PackageManager::Emerge.is_not_found_output("nmap", emerge_found));
+        assert!(PackageManager::Emerge.is_not_found_output("foo", emerge_missing));
+
+        let zypper_found = "Information for package nmap:\nName           : nmap\nSummary        : Network exploration tool\n";
+        let zypper_missing = "Loading repository data...\nReading installed packages...\n\npackage 'foo' not found.\n";
+        assert!(!PackageManager::Zypper.is_not_found_output("nmap", zypper_found));
+        assert!(PackageManager::Zypper.is_not_found_output("foo", zypper_missing));
+        assert!(!PackageManager::Zypper.is_not_found_output("bar", zypper_missing));
+
+        // Descriptions mentioning "not found" are not mistaken for a missing package.
+        let zypper_described = "Information for package foo:\nDescription    :\n    Finds files not found elsewhere.\n";
+        assert!(!PackageManager::Zypper.is_not_found_output("foo", zypper_described));
+
+        let eopkg_found = "Package found in Solus repository:\nName                : nmap\nSummary             : Network exploration tool\n";
+        let eopkg_described =
+            "Name                : foo\nSummary             : Reports package not found errors\n";
+        assert!(!PackageManager::Eopkg.is_not_found_output("nmap", eopkg_found));
+        assert!(!PackageManager::Eopkg.is_not_found_output("foo", eopkg_described));
+        assert!(PackageManager::Eopkg.is_not_found_output("foo", "foo: Package not found\n"));
+
+        let apk_found =
+            "nmap-7.95-r0 description:\nNetwork exploration tool and security/port scanner\n\n";
+        assert!(!PackageManager::APK.is_not_found_output("nmap", apk_found));
+        assert!(
+            PackageManager::APK.is_not_found_output("foo", "WARNING: opening foo: No such file\n")
+        );
+
+        assert!(
+            PackageManager::Chocolatey
+                .is_not_found_output("foo", "Chocolatey v2.2.2\n0 packages found.\n")
+        );
+    }
+
+    #[test]
+    fn test_parse_apt_candidate() {
+        let found = "binwalk:\n  Installed: (none)\n  Candidate: 2.3.4+dfsg1-5\n  Version table:\n";
+        assert_eq!(parse_apt_candidate(found), Some("2.3.4+dfsg1-5"));
+
+        let virtual_pkg = "awk:\n  Installed: (none)\n  Candidate: (none)\n  Version table:\n";
+        assert_eq!(parse_apt_candidate(virtual_pkg), None);
+
+        assert_eq!(parse_apt_candidate(""), None);
+    }
+
+    #[test]
+    fn test_parse_package_summary() {
+        let apt = "Package: binwalk\nVersion: 2.3.3\nDescription-en: tool library for analyzing binary blobs\n";
+        assert_eq!(
+            parse_package_summary(apt).as_deref(),
+            Some("tool library for analyzing binary blobs")
+        );
+
+        let dnf = "Name         : binwalk\nSummary      : Firmware analysis tool\n";
+        assert_eq!(
+            parse_package_summary(dnf).as_deref(),
+            Some("Firmware analysis tool")
+        );
+
+        let pacman = "Repository      : extra\nName            : binwalk\nDescription     : A tool for searching a given binary image\n";
+        assert_eq!(
+            parse_package_summary(pacman).as_deref(),
+            Some("A tool for searching a given binary image")
+        );
+
+        assert_eq!(parse_package_summary("==> binwalk: stable 3.1.0"), None);
+    }
+
+    #[test]
+    fn test_parse_installed_packages() {
+        let pacman = "binwalk\ngdb\n\nnmap\n";
+        let mut packages: Vec<_> = parse_installed_packages(pacman).into_iter().collect();
+        packages.sort();
+        assert_eq!(packages, ["binwalk", "gdb", "nmap"]);
+
+        let chocolatey = "nmap|7.95.0\nwireshark|4.4.1\n";
+        let mut packages: Vec<_> = parse_installed_packages(chocolatey).into_iter().collect();
+        packages.sort();
+        assert_eq!(packages, ["nmap", "wireshark"]);
+    }
+
+    #[test]
+    fn test_language_pkg_manager_install_args() {
+        let home = Path::new("/home/ctf");
+        let install_args =
+            |pm: LanguagePackageManager, package: &str| pm.install_args(package, home);
+
+        assert_eq!(
+            install_args(LanguagePackageManager::Pip, "pwntools"),
+            ["install", "--user", "pwntools"]
+        );
+        assert_eq!(
+            install_args(LanguagePackageManager::Gem, "zsteg"),
+            ["install", "--user-install", "zsteg"]
+        );
+        assert_eq!(
+            install_args(LanguagePackageManager::Go, "github.com/ffuf/ffuf/v2@v2.1.0"),
+            ["install", "github.com/ffuf/ffuf/v2@v2.1.0"]
+        );
+
+        let prefix = home.join(".local").to_string_lossy().into_owned();
+        assert_eq!(
+            install_args(LanguagePackageManager::Npm, "jwt-cracker"),
+            [
+                "install",
+                "--global",
+                "--prefix",
+                prefix.as_str(),
+                "jwt-cracker"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_language_pkg_manager_resolve_package() {
+        let packages = hashmap! {
+            "pip".to_string() => "ropgadget".to_string(),
+            "cargo".to_string() => "rustscan".to_string(),
+        };
+
+        assert_eq!(
+            LanguagePackageManager::Pipx.resolve_package(&packages),
+            Some("ropgadget")
+        );
+        assert_eq!(
+            LanguagePackageManager::Cargo.resolve_package(&packages),
+            Some("rustscan")
+        );
+        assert_eq!(LanguagePackageManager::Npm.resolve_package(&packages), None);
+    }
+
+    #[test]
+    fn test_universal_pkg_manager_install_args() {
+        let install_args = |pm: UniversalPackageManager, scope: PackageScope| {
+            pm.install_args("wireshark", pm.scope(scope))
+        };
+
+        assert_eq!(
+            install_args(UniversalPackageManager::Nix, PackageScope::User),
+            ["--install", "--attr", "nixpkgs.wireshark"]
+        );
+        assert_eq!(
+            install_args(UniversalPackageManager::Nix, PackageScope::System),
+            [
+                "--profile",
+                "/nix/var/nix/profiles/default",
+                "--install",
+                "--attr",
+                "nixpkgs.wireshark"
+            ]
+        );
+        assert_eq!(
+            install_args(UniversalPackageManager::Flatpak, PackageScope::System),
+            [
+                "install",
+                "--system",
+                "--noninteractive",
+                "flathub",
+                "wireshark"
+            ]
+        );
+        assert_eq!(
+            install_args(UniversalPackageManager::Snap, PackageScope::User),
+            ["install", "wireshark"]
+        );
+
+        assert_eq!(
+            UniversalPackageManager::Snap.scope(PackageScope::User),
+            PackageScope::System
+        );
+        assert_eq!(
+            UniversalPackageManager::Linuxbrew.scope(PackageScope::System),
+            PackageScope::User
+        );
+    }
+
+    #[test]
+    fn test_universal_pkg_manager_lookup() {
+        let packages = hashmap! {
+            "homebrew".to_string() => "ghidra".to_string(),
+            "flatpak".to_string() => "org.ghidra_sre.Ghidra".to_string(),
+        };
+
+        assert_eq!(
+            UniversalPackageManager::Linuxbrew.resolve_package(&packages),
+            Some("ghidra")
+        );
+        assert_eq!(
+            UniversalPackageManager::Nix.resolve_package(&packages),
+            None
+        );
+
+        let package = UniversalPackageManager::Flatpak.resolve_package(&packages);
+        assert_eq!(
+            UniversalPackageManager::Flatpak.exported_names("ghidra", package),
+            ["org.ghidra_sre.Ghidra", "ghidra"]
+        );
+        assert_eq!(
+            UniversalPackageManager::Snap.exported_names("ghidra", Some("ghidra-snap")),
+            ["ghidra"]
+        );
+
+        let home = Path::new("/home/ctf");
+        assert_eq!(
+            UniversalPackageManager::Flatpak.bin_dirs(Some(home)),
+            [
+                PathBuf::from("/home/ctf/.local/share/flatpak/exports/bin"),
+                PathBuf::from("/var/lib/flatpak/exports/bin"),
+            ]
+        );
+        assert_eq!(
+            UniversalPackageManager::Nix.bin_dirs(None),
+            [PathBuf::from("/nix/var/nix/profiles/default/bin")]
+        );
+        assert_eq!(
+            UniversalPackageManager::Linuxbrew.bin_dirs(Some(home)),
+            [
+                PathBuf::from("/home/linuxbrew/.linuxbrew/bin"),
+                PathBuf::from("/home/ctf/.linuxbrew/bin"),
+            ]
+        );
+        assert_eq!(
+            UniversalPackageManager::Snap.bin_dirs(Some(home)),
+            [PathBuf::from("/snap/bin")]
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_gem_user_bin_dir() {
+        use crate::testing::write_script;
+        use tempdir::TempDir;
+
+        let dir = TempDir::new("ctftools_test").unwrap();
+        let gem = write_script(
+            dir.path(),
+            "gem",
+            "[ \"$1 $2\" = \"environment user_gemhome\" ] && echo /home/ctf/.gem/ruby/3.3.0\n",
+        );
+
+        assert_eq!(
+            LanguagePackageManager::Gem
+                .user_bin_dir(&gem, Path::new("/home/ctf"))
+                .unwrap(),
+            Some(PathBuf::from("/home/ctf/.gem/ruby/3.3.0/bin"))
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_externally_managed_pip() {
+        use crate::testing::write_script;
+        use tempdir::TempDir;
+
+        let dir = TempDir::new("ctftools_test").unwrap();
+        let stdlib = dir.path().join("lib");
+        std::fs::create_dir(&stdlib).unwrap();
+        write_script(
+            dir.path(),
+            "python3",
+            &format!("echo {}\n", stdlib.display()),
+        );
+
+        let pip = dir.path().join("pip");
+        assert!(!is_externally_managed(&pip));
+
+        std::fs::write(stdlib.join("EXTERNALLY-MANAGED"), "[externally-managed]\n").unwrap();
+        assert!(is_externally_managed(&pip));
+        assert!(!is_externally_managed(
+            &dir.path().join("missing").join("pip")
+        ));
+    }
+
+    #[test]
+    fn test_orderable_installers() {
+        let keys = Installer::orderable()
+            .map(|v| v.as_registry_key())
+            .collect::<Vec<_>>();
+
+        for key in ["apt", "homebrew", "linuxbrew", "flatpak", "pipx", "npm"] {
+            assert!(keys.contains(&key), "{key}");
+        }
+        // These are never listed by `installers()` so they cannot be ordered.
+        for key in ["aur", "docker", "podman"] {
+            assert!(!keys.contains(&key), "{key}");
+        }
+
+        let unique = keys.iter().collect::<HashSet<_>>();
+        assert_eq!(unique.len(), keys.len());
+    }
+
+    #[test]
+    fn test_sort_installers_by_priority() {
+        let mut installers = vec![
+            (Installer::System(PackageManager::DNF), 0),
+            (Installer::Universal(UniversalPackageManager::Flatpak), 1),
+            (Installer::Universal(UniversalPackageManager::Linuxbrew), 2),
+            (Installer::Language(LanguagePackageManager::Cargo), 3),
+        ];
+
+        let priority = vec!["linuxbrew".into(), "unknown".into(), "flatpak".into()];
+        Installer::sort_by_priority(&mut installers, &priority);
+        assert_eq!(
+            installers.iter().map(|(_, v)| *v).collect::<Vec<_>>(),
+            [2, 1, 0, 3]
+        );
+
+        // Without any priority, the default order is kept.
+        Installer::sort_by_priority(&mut installers, &[]);
+        assert_eq!(
+            installers.iter().map(|(_, v)| *v).collect::<Vec<_>>(),
+            [2, 1, 0, 3]
+        );
+    }
 }
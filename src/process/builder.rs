@@ -5,12 +5,14 @@ use libc::{SIGINT, SIGTERM};
 use shell_escape::escape;
 use signal_hook::flag as signal_flag;
 
+use std::collections::BTreeMap;
 use std::ffi::{OsStr, OsString};
+use std::io::{BufRead, BufReader, Read};
 use std::iter::once;
 use std::path::PathBuf;
 use std::process::{Command, Output, Stdio};
-use std::sync::Arc;
 use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, mpsc};
 use std::time::{Duration, Instant};
 
 use crate::process::ProcessError;
@@ -29,6 +31,12 @@ pub struct ProcessBuilder {
     ///
     /// The last entry is the outermost wrapper.
     wrappers: Vec<PathBuf>,
+
+    /// Any environment variables that should be set for the program.
+    env: BTreeMap<String, Option<OsString>>,
+
+    /// The directory to run the program from.
+    cwd: Option<OsString>,
 }
 
 impl ProcessBuilder {
@@ -39,6 +47,8 @@ impl ProcessBuilder {
             program: cmd.into(),
             args: Vec::new(),
             wrappers: Vec::new(),
+            env: BTreeMap::new(),
+            cwd: None,
         }
     }
 
@@ -55,6 +65,38 @@ impl ProcessBuilder {
         self
     }
 
+    /// (chainable) Sets the current working directory of the process.
+    pub fn cwd<T: AsRef<OsStr>>(&mut self, path: T) -> &mut ProcessBuilder {
+        self.cwd = Some(path.as_ref().to_os_string());
+        self
+    }
+
+    /// (chainable) Sets an environment variable for the process.
+    pub fn env<T: AsRef<OsStr>>(&mut self, key: &str, val: T) -> &mut ProcessBuilder {
+        self.env
+            .insert(key.to_string(), Some(val.as_ref().to_os_string()));
+        self
+    }
+
+    /// (chainable) Unsets an environment variable for the process.
+    pub fn env_remove(&mut self, key: &str) -> &mut ProcessBuilder {
+        self.env.insert(key.to_string(), None);
+        self
+    }
+
+    /// Gets the current working directory for the process.
+    #[must_use]
+    pub fn get_cwd(&self) -> Option<&std::path::Path> {
+        self.cwd.as_ref().map(std::path::Path::new)
+    }
+
+    /// Gets all environment variables explicitly set or unset for the process
+    /// (not inherited vars).
+    #[must_use]
+    pub fn get_envs(&self) -> &BTreeMap<String, Option<OsString>> {
+        &self.env
+    }
+
     /// Gets the executable name of the process to run.
     ///
     /// If it is wrapped, then the last entry of the wrappers
@@ -107,6 +149,66 @@ impl ProcessBuilder {
         }
     }
 
+    /// Executes the process and calls `on_line` with every line written
+    /// to its stdout or stderr as soon as it is written, or returns an
+    /// error if non-zero exit status.
+    pub fn exec_with_streaming(&self, on_line: &mut dyn FnMut(&str)) -> Result<()> {
+        let mut cmd = self.build_command();
+        let mut child = piped(&mut cmd, false)
+            .spawn()
+            .with_context(|| ProcessError::could_not_execute(self))?;
+
+        let stdout = child
+            .stdout
+            .take()
+            .map(|v| Box::new(v) as Box<dyn Read + Send>);
+        let stderr = child
+            .stderr
+            .take()
+            .map(|v| Box::new(v) as Box<dyn Read + Send>);
+
+        std::thread::scope(|scope| {
+            let (tx, rx) = mpsc::channel::<String>();
+            for reader in [stdout, stderr].into_iter().flatten() {
+                let tx = tx.clone();
+                scope.spawn(move || {
+                    let mut reader = BufReader::new(reader);
+                    let mut buffer = Vec::new();
+                    while let Ok(len) = reader.read_until(b'\n', &mut buffer) {
+                        if len == 0 {
+                            break;
+                        }
+                        let line = String::from_utf8_lossy(&buffer);
+                        if tx.send(line.trim_end_matches(['\r', '\n']).into()).is_err() {
+                            break;
+                        }
+                        buffer.clear();
+                    }
+                });
+            }
+            drop(tx);
+
+            for line in rx {
+                on_line(&line);
+            }
+        });
+
+        let status = child
+            .wait()
+            .with_context(|| ProcessError::could_not_execute(self))?;
+
+        if !status.success() {
+            return Err(ProcessError::new(
+                &format!("process didn't exit successfully: {self}"),
+                Some(status),
+                None,
+            )
+            .into());
+        }
+
+        Ok(())
+    }
+
     /// Like [`Command::output`] but with a better error message.
     pub fn output(&self) -> Result<Output> {
         self.output_inner()
@@ -137,6 +239,21 @@ impl ProcessBuilder {
             command.arg(arg);
         }
 
+        if let Some(cwd) = self.get_cwd() {
+            command.current_dir(cwd);
+        }
+
+        for (key, val) in self.env.iter() {
+            match val {
+                Some(val) => {
+                    command.env(key, val);
+                }
+                None => {
+                    command.env_remove(key);
+                }
+            }
+        }
+
         command
     }
 }
@@ -279,4 +396,43 @@ mod tests {
             "/usr/bin/sudo /usr/bin/pacman --hello"
         );
     }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_env_and_cwd() {
+        let dir = tempdir::TempDir::new("ctftools_test").unwrap();
+        let cwd = dir.path().canonicalize().unwrap();
+
+        let mut builder = ProcessBuilder::new("sh");
+        builder.args(&[
+            "-c",
+            "printf '%s:%s:%s' \"$FOO\" \"${HOME-unset}\" \"$(pwd -P)\"",
+        ]);
+        builder.env("FOO", "bar").env_remove("HOME").cwd(&cwd);
+
+        let output = builder.exec_with_output().unwrap();
+        assert_eq!(
+            String::from_utf8_lossy(&output.stdout),
+            format!("bar:unset:{}", cwd.display())
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_exec_with_streaming() {
+        let mut builder = ProcessBuilder::new("sh");
+        builder.args(&["-c", "echo one; echo two >&2; printf three"]);
+
+        let mut lines = Vec::new();
+        builder
+            .exec_with_streaming(&mut |line| lines.push(line.to_string()))
+            .unwrap();
+
+        lines.sort();
+        assert_eq!(lines, ["one", "three", "two"]);
+
+        let mut builder = ProcessBuilder::new("sh");
+        builder.args(&["-c", "echo failed; exit 3"]);
+        assert!(builder.exec_with_streaming(&mut |_| {}).is_err());
+    }
 }
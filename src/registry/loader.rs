@@ -0,0 +1,286 @@
+use anyhow::{Context, Result, bail};
+use log::{debug, warn};
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+use crate::registry::{Toolkit, TrustStore, UserToolkit, signature_path};
+
+/// Builds a [`Toolkit`] from a base toolkit extended with toolkit
+/// files, and keeps track of whether those files have changed.
+///
+/// This allows the interactive mode to pick up edits made to the
+/// toolkit files without restarting the program.
+///
+/// Toolkit files shared by others must be signed by one of the keys in
+/// the [trust store](TrustStore) since they may point to malicious
+/// installers if they were tampered with.
+#[derive(Debug, Clone)]
+pub struct ToolkitLoader {
+    base: Toolkit,
+    files: Vec<WatchedFile>,
+    trust: TrustStore,
+    allow_unsigned: bool,
+}
+
+/// A toolkit file along with its state when it was last loaded.
+#[derive(Debug, Clone)]
+struct WatchedFile {
+    path: PathBuf,
+
+    /// Whether this file is shared by others and requires a signature.
+    shared: bool,
+
+    state: Option<FileState>,
+    signature_state: Option<FileState>,
+}
+
+/// Observable state of a file used to detect changes. It is `None`
+/// in [`WatchedFile`] if the file does not exist.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct FileState {
+    modified: Option<SystemTime>,
+    len: u64,
+}
+
+impl ToolkitLoader {
+    /// Creates a new [`ToolkitLoader`] where the tools from the user's
+    /// own toolkit `files` are merged into `base` in order.
+    ///
+    /// The files do not have to exist yet.
+    #[must_use]
+    pub fn new(base: Toolkit, files: Vec<PathBuf>) -> Self {
+        let files = files
+            .into_iter()
+            .map(|path| WatchedFile {
+                path,
+                shared: false,
+                state: None,
+                signature_state: None,
+            })
+            .collect();
+
+        Self {
+            base,
+            files,
+            trust: TrustStore::default(),
+            allow_unsigned: false,
+        }
+    }
+
+    /// Adds toolkit files shared by others. They are merged before the
+    /// user's own toolkit files so the user can still override their tools.
+    ///
+    /// Every shared file must have a detached minisign signature next to
+    /// it (`<file>.minisig`) made by a key from `trust`. Unsigned or badly
+    /// signed files fail to load unless `allow_unsigned` is set.
+    #[must_use]
+    pub fn with_shared_files(
+        mut self,
+        files: Vec<PathBuf>,
+        trust: TrustStore,
+        allow_unsigned: bool,
+    ) -> Self {
+        let shared = files.into_iter().map(|path| WatchedFile {
+            path,
+            shared: true,
+            state: None,
+            signature_state: None,
+        });
+
+        self.files.splice(0..0, shared);
+        self.trust = trust;
+        self.allow_unsigned = allow_unsigned;
+        self
+    }
+
+    /// The toolkit that the toolkit files extend.
+    #[must_use]
+    pub fn base(&self) -> &Toolkit {
+        &self.base
+    }
+
+    /// Paths of the toolkit files used to extend the base toolkit.
+    pub fn files(&self) -> impl Iterator<Item = &Path> {
+        self.files.iter().map(|v| v.path.as_path())
+    }
+
+    /// Builds the toolkit from the base toolkit and the toolkit files.
+    ///
+    /// The current state of every file is remembered even if one of
+    /// them fails to load, so [`ToolkitLoader::has_changed`] only reports
+    /// changes made after this call.
+    pub fn load(&mut self) -> Result<Toolkit> {
+        for file in self.files.iter_mut() {
+            file.state = file_state(&file.path)?;
+            if file.shared {
+                file.signature_state = file_state(&signature_path(&file.path))?;
+            }
+        }
+
+        let mut toolkit = self.base.clone();
+        for file in self.files.iter() {
+            if file.shared {
+                toolkit.merge(self.load_shared(&file.path)?);
+            } else if let Some(extension) = UserToolkit::new(&file.path).load()? {
+                toolkit.merge(extension);
+            }
+        }
+
+        Ok(toolkit)
+    }
+
+    fn load_shared(&self, path: &Path) -> Result<Toolkit> {
+        let contents =
+            std::fs::read(path).with_context(|| format!("could not read {}", path.display()))?;
+
+        let signature_path = signature_path(path);
+        let verification = match std::fs::read_to_string(&signature_path) {
+            Ok(signature) => self
+                .trust
+                .verify(&contents, &signature)
+                .map(|name| debug!("{} is signed by {name:?}", path.display()))
+                .map_err(|error| error.to_string()),
+            Err(error) if error.kind() == std::io::ErrorKind::NotFound => {
+                Err(format!("{} is not found", signature_path.display()))
+            }
+            Err(error) => {
+                return Err(error)
+                    .with_context(|| format!("could not read {}", signature_path.display()));
+            }
+        };
+
+        if let Err(reason) = verification {
+            if !self.allow_unsigned {
+                bail!(
+                    "refusing to load unverified toolkit {} ({reason}). \
+                    Trust its publisher key with `ctftools toolkit trust` \
+                    or use --allow-unsigned to load it anyway",
+                    path.display()
+                );
+            }
+            warn!("loading unverified toolkit {} ({reason})", path.display());
+        }
+
+        let contents = String::from_utf8(contents)
+            .with_context(|| format!("{} is not a valid UTF-8 file", path.display()))?;
+
+        Toolkit::from_yaml(&contents)
+            .with_context(|| format!("could not load toolkit from {}", path.display()))
+    }
+
+    /// Checks whether any of the toolkit files have been created, modified
+    /// or removed since the last time [`ToolkitLoader::load`] was called.
+    pub fn has_changed(&self) -> Result<bool> {
+        for file in self.files.iter() {
+            if file_state(&file.path)? != file.state {
+                return Ok(true);
+            }
+
+            if file.shared && file_state(&signature_path(&file.path))? != file.signature_state {
+                return Ok(true);
+            }
+        }
+        Ok(false)
+    }
+}
+
+fn file_state(path: &Path) -> Result<Option<FileState>> {
+    match std::fs::metadata(path) {
+        Ok(metadata) => Ok(Some(FileState {
+            modified: metadata.modified().ok(),
+            len: metadata.len(),
+        })),
+        Err(error) if error.kind() == std::io::ErrorKind::NotFound => Ok(None),
+        Err(error) => {
+            Err(error).with_context(|| format!("could not read metadata of {}", path.display()))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+    use tempdir::TempDir;
+
+    use crate::registry::trust::tests::{generate_key, sign};
+    use crate::registry::{ToolMetadata, Toolkit, ToolkitLoader, TrustStore, signature_path};
+
+    fn commands(toolkit: &Toolkit) -> Vec<&str> {
+        toolkit.tools().iter().map(|v| v.command.as_str()).collect()
+    }
+
+    #[test]
+    fn test_load_and_watch_files() {
+        let dir = TempDir::new("ctftools_test").unwrap();
+        let path = dir.path().join("toolkit.yml");
+
+        let base = Toolkit::new(vec![
+            ToolMetadata::builder()
+                .name("foo".into())
+                .command("foo".into())
+                .build(),
+        ]);
+
+        let mut loader = ToolkitLoader::new(base, vec![path.clone()]);
+        assert_eq!(commands(&loader.load().unwrap()), ["foo"]);
+        assert!(!loader.has_changed().unwrap());
+
+        // Creating the file counts as a change.
+        std::fs::write(&path, "bar:\n  description: bar").unwrap();
+        assert!(loader.has_changed().unwrap());
+        assert_eq!(commands(&loader.load().unwrap()), ["bar", "foo"]);
+        assert!(!loader.has_changed().unwrap());
+
+        // Broken files fail to load but they are not reported
+        // as changed until they are edited again.
+        std::fs::write(&path, "bar:\n  examples: 1\n").unwrap();
+        assert!(loader.has_changed().unwrap());
+        assert!(loader.load().is_err());
+        assert!(!loader.has_changed().unwrap());
+
+        std::fs::remove_file(&path).unwrap();
+        assert!(loader.has_changed().unwrap());
+        assert_eq!(commands(&loader.load().unwrap()), ["foo"]);
+    }
+
+    #[test]
+    fn test_load_shared_files() {
+        let (publisher, publisher_pk) = generate_key();
+        let (stranger, _) = generate_key();
+
+        let dir = TempDir::new("ctftools_test").unwrap();
+        let keys = dir.path().join("trusted-keys");
+        TrustStore::install_key(&keys, "publisher", &publisher_pk).unwrap();
+
+        let shared = dir.path().join("shared.yml");
+        let contents = "bar:\n  description: bar";
+        std::fs::write(&shared, contents).unwrap();
+
+        let loader = |allow_unsigned: bool| {
+            ToolkitLoader::new(Toolkit::new(Vec::new()), Vec::new()).with_shared_files(
+                vec![shared.clone()],
+                TrustStore::load(&keys).unwrap(),
+                allow_unsigned,
+            )
+        };
+
+        // Unsigned toolkits are refused unless it is allowed.
+        assert!(loader(false).load().is_err());
+        assert_eq!(commands(&loader(true).load().unwrap()), ["bar"]);
+
+        // Signed by the trusted publisher
+        let signature = signature_path(&shared);
+        std::fs::write(&signature, sign(&publisher, contents.as_bytes())).unwrap();
+        assert_eq!(commands(&loader(false).load().unwrap()), ["bar"]);
+
+        // Tampered toolkit
+        std::fs::write(&shared, "bar:\n  description: evil").unwrap();
+        assert!(loader(false).load().is_err());
+
+        // Signed by someone else
+        std::fs::write(&shared, contents).unwrap();
+        std::fs::write(&signature, sign(&stranger, contents.as_bytes())).unwrap();
+        assert!(loader(false).load().is_err());
+        assert_eq!(commands(&loader(true).load().unwrap()), ["bar"]);
+    }
+}
@@ -1,2 +1,13 @@
+mod loader;
+mod option;
+mod source;
 mod toolkit;
+mod trust;
+mod user;
+
+pub use self::loader::*;
+pub use self::option::*;
+pub use self::source::*;
 pub use self::toolkit::*;
+pub use self::trust::*;
+pub use self::user::*;
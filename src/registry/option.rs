@@ -0,0 +1,260 @@
+use anyhow::{Result, bail};
+use bon::Builder;
+use serde::{Deserialize, Serialize};
+
+/// Describes a commonly used option of a tool so the user can build
+/// the tool's arguments step by step from a form instead of typing
+/// them all at once.
+///
+/// ```yaml
+/// options:
+///   - flag: -a
+///     description: Attack mode
+///     value: number
+///     choices:
+///       - value: "0"
+///         description: Straight (wordlist)
+///       - value: "3"
+///         description: Brute-force (mask)
+///   - description: File containing the hashes
+///     value: path
+///     required: true
+/// ```
+#[derive(Debug, Deserialize, Serialize, Builder, Clone, PartialEq, Eq)]
+pub struct ToolOption {
+    /// The flag passed to the tool (e.g. `-m` or `--format=`).
+    ///
+    /// Options without a flag are positional arguments. If the flag
+    /// ends with `=`, its value is joined into the same argument.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub flag: Option<String>,
+
+    /// A short, human-readable description of the option.
+    pub description: String,
+
+    /// Type of the value the option takes. Options without
+    /// a value are switches that are either passed or not.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub value: Option<OptionValueType>,
+
+    /// Values the user can choose from, if the option only
+    /// accepts a fixed set of values.
+    #[builder(default)]
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub choices: Vec<ToolOptionChoice>,
+
+    /// Whether the user must fill in this option.
+    #[builder(default)]
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub required: bool,
+}
+
+/// Type of the value taken by a [tool option](ToolOption).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum OptionValueType {
+    /// Any text.
+    Text,
+
+    /// A decimal number.
+    Number,
+
+    /// A path to a file or directory.
+    Path,
+}
+
+/// A value that can be chosen for a [tool option](ToolOption).
+///
+/// It can be written in the toolkit as the value itself, or as a
+/// mapping with the value and its description.
+#[derive(Debug, Deserialize, Serialize, Builder, Clone, PartialEq, Eq)]
+#[serde(from = "RawOptionChoice", into = "RawOptionChoice")]
+pub struct ToolOptionChoice {
+    pub value: String,
+    pub description: Option<String>,
+}
+
+#[derive(Deserialize, Serialize)]
+#[serde(untagged)]
+enum RawOptionChoice {
+    Plain(String),
+    Described { value: String, description: String },
+}
+
+impl From<RawOptionChoice> for ToolOptionChoice {
+    fn from(raw: RawOptionChoice) -> Self {
+        match raw {
+            RawOptionChoice::Plain(value) => Self {
+                value,
+                description: None,
+            },
+            RawOptionChoice::Described { value, description } => Self {
+                value,
+                description: Some(description),
+            },
+        }
+    }
+}
+
+impl From<ToolOptionChoice> for RawOptionChoice {
+    fn from(choice: ToolOptionChoice) -> Self {
+        match choice.description {
+            Some(description) => Self::Described {
+                value: choice.value,
+                description,
+            },
+            None => Self::Plain(choice.value),
+        }
+    }
+}
+
+impl OptionValueType {
+    /// Checks whether the input is acceptable for this type of value.
+    #[must_use]
+    pub fn accepts(&self, input: &str) -> bool {
+        match self {
+            Self::Text | Self::Path => !input.is_empty(),
+            Self::Number => input.parse::<f64>().is_ok_and(f64::is_finite),
+        }
+    }
+}
+
+impl ToolOption {
+    /// Whether this option is a switch that does not take a value.
+    #[must_use]
+    pub fn is_switch(&self) -> bool {
+        self.value.is_none()
+    }
+
+    /// Returns a label of this option suitable for UI display.
+    #[must_use]
+    pub fn label(&self) -> String {
+        match self.flag.as_deref() {
+            Some(flag) => format!("{} ({})", self.description, flag.trim_end_matches('=')),
+            None => self.description.clone(),
+        }
+    }
+
+    /// Converts the option along with its value into the arguments
+    /// passed to the tool.
+    ///
+    /// `value` is ignored if this option is a [switch](ToolOption::is_switch).
+    #[must_use]
+    pub fn to_args(&self, value: &str) -> Vec<String> {
+        match (self.flag.as_deref(), self.is_switch()) {
+            (Some(flag), true) => vec![flag.to_string()],
+            (Some(flag), false) if flag.ends_with('=') => vec![format!("{flag}{value}")],
+            (Some(flag), false) => vec![flag.to_string(), value.to_string()],
+            (None, _) => vec![value.to_string()],
+        }
+    }
+
+    /// Checks whether this option is well-formed.
+    pub fn validate(&self) -> Result<()> {
+        if let Some(flag) = self.flag.as_deref()
+            && (!flag.starts_with('-') || flag.len() < 2 || flag.chars().any(char::is_whitespace))
+        {
+            bail!("option {flag:?} must start with `-` and must not contain spaces");
+        }
+
+        if self.is_switch() {
+            if self.flag.is_none() {
+                bail!("option {:?} must have a flag or a value", self.description);
+            }
+            if self.required {
+                bail!("switch {:?} cannot be required", self.label());
+            }
+            if !self.choices.is_empty() {
+                bail!("switch {:?} cannot have choices", self.label());
+            }
+        }
+
+        if let Some(value) = self.value
+            && let Some(choice) = self.choices.iter().find(|v| !value.accepts(&v.value))
+        {
+            bail!(
+                "choice {:?} of option {:?} is not a valid {value:?} value",
+                choice.value,
+                self.label()
+            );
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use crate::registry::{OptionValueType, ToolOption, ToolOptionChoice};
+
+    #[test]
+    fn test_option_to_args() {
+        let switch = ToolOption::builder()
+            .flag("-e".into())
+            .description("Extract files".into())
+            .build();
+        assert_eq!(switch.to_args(""), ["-e"]);
+
+        let separate = ToolOption::builder()
+            .flag("-m".into())
+            .description("Hash type".into())
+            .value(OptionValueType::Number)
+            .build();
+        assert_eq!(separate.to_args("100"), ["-m", "100"]);
+
+        let joined = ToolOption::builder()
+            .flag("--wordlist=".into())
+            .description("Wordlist".into())
+            .value(OptionValueType::Path)
+            .build();
+        assert_eq!(joined.to_args("rockyou.txt"), ["--wordlist=rockyou.txt"]);
+        assert_eq!(joined.label(), "Wordlist (--wordlist)");
+
+        let positional = ToolOption::builder()
+            .description("Hash file".into())
+            .value(OptionValueType::Path)
+            .build();
+        assert_eq!(positional.to_args("hash.txt"), ["hash.txt"]);
+    }
+
+    #[test]
+    fn test_validate_option() {
+        let option = |flag: Option<&str>, value: Option<OptionValueType>| {
+            ToolOption::builder()
+                .maybe_flag(flag.map(String::from))
+                .description("foo".into())
+                .maybe_value(value)
+                .build()
+        };
+
+        assert!(option(Some("-e"), None).validate().is_ok());
+        assert!(option(None, Some(OptionValueType::Text)).validate().is_ok());
+        assert!(option(None, None).validate().is_err());
+        assert!(option(Some("e"), None).validate().is_err());
+        assert!(option(Some("-"), None).validate().is_err());
+        assert!(option(Some("-e f"), None).validate().is_err());
+
+        let mut required_switch = option(Some("-e"), None);
+        required_switch.required = true;
+        assert!(required_switch.validate().is_err());
+
+        let mut bad_choice = option(Some("-m"), Some(OptionValueType::Number));
+        bad_choice.choices = vec![
+            ToolOptionChoice::builder().value("0".into()).build(),
+            ToolOptionChoice::builder().value("md5".into()).build(),
+        ];
+        assert!(bad_choice.validate().is_err());
+    }
+
+    #[test]
+    fn test_value_type_accepts() {
+        assert!(OptionValueType::Number.accepts("100"));
+        assert!(OptionValueType::Number.accepts("0.5"));
+        assert!(!OptionValueType::Number.accepts("NaN"));
+        assert!(!OptionValueType::Number.accepts("abc"));
+        assert!(OptionValueType::Text.accepts("abc"));
+        assert!(!OptionValueType::Path.accepts(""));
+    }
+}
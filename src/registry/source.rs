@@ -0,0 +1,197 @@
+use anyhow::{Result, bail};
+use bon::Builder;
+use serde::{Deserialize, Serialize};
+
+/// Describes how to build a tool from the source code in its git repository.
+///
+/// This is used for tools that are not packaged in the user's system.
+#[derive(Debug, Builder, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct ToolSourceMetadata {
+    /// URL of the git repository (or a path to a local one).
+    pub git: String,
+
+    /// Branch, tag or commit to build. It defaults to the
+    /// default branch of the repository.
+    #[serde(rename = "ref", default, skip_serializing_if = "Option::is_none")]
+    pub git_ref: Option<String>,
+
+    /// How to build and install the tool.
+    #[serde(rename = "build")]
+    pub build_system: BuildSystem,
+}
+
+impl ToolSourceMetadata {
+    /// Checks whether the source can be passed safely to git and
+    /// whether its build system describes anything to run.
+    pub fn validate(&self) -> Result<()> {
+        if self.git.is_empty() || self.git.starts_with('-') {
+            bail!("invalid git repository: {:?}", self.git);
+        }
+
+        if let Some(git_ref) = self.git_ref.as_deref()
+            && (git_ref.is_empty() || git_ref.starts_with('-'))
+        {
+            bail!("invalid git ref: {git_ref:?}");
+        }
+
+        if let BuildSystem::Custom(commands) = &self.build_system
+            && commands.iter().all(|v| v.trim().is_empty())
+        {
+            bail!("custom build must have at least one command");
+        }
+
+        Ok(())
+    }
+}
+
+/// Build systems that a tool can be built from source with.
+///
+/// It is written in the toolkit as the name of the build system, or as
+/// a mapping with a `custom` list of commands. Every build system installs
+/// the tool into the installation prefix managed by ctftools.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(try_from = "RawBuildSystem", into = "RawBuildSystem")]
+pub enum BuildSystem {
+    /// Runs `make` and then `make install` with `PREFIX` set.
+    Make,
+
+    /// Configures, builds and installs the project with CMake.
+    CMake,
+
+    /// Installs the crate with `cargo install --path .`.
+    Cargo,
+
+    /// Runs each command with the shell, from the root of the
+    /// repository. The installation prefix is given in the
+    /// `PREFIX` environment variable.
+    Custom(Vec<String>),
+}
+
+#[derive(Deserialize, Serialize)]
+#[serde(untagged)]
+enum RawBuildSystem {
+    Named(String),
+    Custom { custom: Vec<String> },
+}
+
+impl TryFrom<RawBuildSystem> for BuildSystem {
+    type Error = String;
+
+    fn try_from(raw: RawBuildSystem) -> Result<Self, Self::Error> {
+        match raw {
+            RawBuildSystem::Named(name) => match name.as_str() {
+                "make" => Ok(Self::Make),
+                "cmake" => Ok(Self::CMake),
+                "cargo" => Ok(Self::Cargo),
+                _ => Err(format!(
+                    "unknown build system {name:?}, expected make, cmake, cargo or custom commands"
+                )),
+            },
+            RawBuildSystem::Custom { custom } => Ok(Self::Custom(custom)),
+        }
+    }
+}
+
+impl From<BuildSystem> for RawBuildSystem {
+    fn from(build_system: BuildSystem) -> Self {
+        match build_system {
+            BuildSystem::Make => Self::Named("make".into()),
+            BuildSystem::CMake => Self::Named("cmake".into()),
+            BuildSystem::Cargo => Self::Named("cargo".into()),
+            BuildSystem::Custom(custom) => Self::Custom { custom },
+        }
+    }
+}
+
+impl BuildSystem {
+    /// Returns a human-friendly name for this build system.
+    #[must_use]
+    pub fn as_display_name(&self) -> &'static str {
+        match self {
+            Self::Make => "Make",
+            Self::CMake => "CMake",
+            Self::Cargo => "Cargo",
+            Self::Custom(..) => "custom commands",
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::{BuildSystem, ToolSourceMetadata};
+
+    #[test]
+    fn test_deserialize_source() {
+        let source: ToolSourceMetadata = serde_yml::from_str(
+            "
+            git: https://github.com/RickdeJager/stegseek
+            ref: v0.6
+            build: cmake
+            ",
+        )
+        .unwrap();
+
+        assert_eq!(
+            source,
+            ToolSourceMetadata::builder()
+                .git("https://github.com/RickdeJager/stegseek".into())
+                .git_ref("v0.6".into())
+                .build_system(BuildSystem::CMake)
+                .build()
+        );
+
+        let source: ToolSourceMetadata = serde_yml::from_str(
+            "
+            git: ./foo
+            build:
+              custom:
+                - ./configure --prefix=\"$PREFIX\"
+                - make install
+            ",
+        )
+        .unwrap();
+
+        assert_eq!(source.git_ref, None);
+        assert_eq!(
+            source.build_system,
+            BuildSystem::Custom(vec![
+                "./configure --prefix=\"$PREFIX\"".into(),
+                "make install".into()
+            ])
+        );
+        source.validate().unwrap();
+    }
+
+    #[test]
+    fn test_validate_source() {
+        let source = |git: &str, git_ref: Option<&str>, build_system| ToolSourceMetadata {
+            git: git.into(),
+            git_ref: git_ref.map(String::from),
+            build_system,
+        };
+
+        assert!(
+            source("--upload-pack=x", None, BuildSystem::Make)
+                .validate()
+                .is_err()
+        );
+        assert!(source("", None, BuildSystem::Make).validate().is_err());
+        assert!(
+            source("https://foo.local/foo", Some("-b"), BuildSystem::Make)
+                .validate()
+                .is_err()
+        );
+        assert!(
+            source("https://foo.local/foo", None, BuildSystem::Custom(vec![]))
+                .validate()
+                .is_err()
+        );
+        assert!(
+            source("https://foo.local/foo", Some("main"), BuildSystem::Cargo)
+                .validate()
+                .is_ok()
+        );
+    }
+}
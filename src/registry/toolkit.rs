@@ -1,11 +1,33 @@
-use anyhow::{Context, Result};
+use anyhow::{Context, Result, bail};
 use bon::Builder;
 use log::debug;
 use serde::{Deserialize, Serialize};
 use std::collections::BTreeMap;
-use std::path::PathBuf;
+#[cfg(feature = "auto-install-tools")]
+use std::collections::HashMap;
+use std::path::{Component, Path, PathBuf};
 use std::sync::LazyLock;
 
+use crate::registry::ToolOption;
+#[cfg(feature = "auto-install-tools")]
+use crate::registry::ToolSourceMetadata;
+use crate::util::expand_vars;
+
+/// Keys of a tool entry that describe how to run or install the tool.
+///
+/// Tools having any of these keys are executable tools unless
+/// they explicitly declare their kind.
+const EXECUTABLE_KEYS: &[&str] = &[
+    "examples",
+    "options",
+    "env",
+    "cwd",
+    "windows",
+    "packages",
+    "downloads",
+    "source",
+];
+
 /// A collection of tool definitions that make up the user's toolkit.
 ///
 /// The [`Toolkit`] struct represents a set of external CTF tools that the program
@@ -31,26 +53,99 @@ impl Toolkit {
 
     /// Deserializes the YAML from a given string into a toolkit.
     pub fn from_yaml(yaml: &str) -> Result<Self> {
-        let map: BTreeMap<String, serde_yml::Value> = serde_yml::from_str(yaml)
-            .expect("failed to deserialize built-in toolkit from YAML payload");
+        // Empty documents (or documents with comments only) are considered
+        // as an empty toolkit instead of being a malformed one.
+        let map: Option<BTreeMap<String, serde_yml::Value>> =
+            serde_yml::from_str(yaml).context("failed to deserialize toolkit from YAML payload")?;
 
         let mut tools = Vec::new();
-        for (command, metadata) in map {
+        for (command, metadata) in map.unwrap_or_default() {
             // Ignore the _comment key because it contains invalid schema on it.
             if command == "_comment" {
                 continue;
             }
 
-            let mut tool: ToolMetadata = match serde_yml::from_value(metadata) {
-                Ok(okay) => okay,
-                Err(error) => panic!("failed to deserialize tool {command:?}: {error:#?}"),
-            };
+            // Commands name the directories where tools are installed into.
+            if !is_valid_command(&command) {
+                bail!("invalid tool command: {command:?}");
+            }
 
-            let mut kind = tool.kind;
+            // The kind of a tool is inferred unless it is declared explicitly.
+            let explicit_kind = metadata.get("kind").is_some();
+            let runnable = EXECUTABLE_KEYS
+                .iter()
+                .any(|key| metadata.get(key).is_some());
+
+            let mut tool: ToolMetadata = serde_yml::from_value(metadata)
+                .with_context(|| format!("failed to deserialize tool {command:?}"))?;
+
+            if !explicit_kind {
+                tool.kind = infer_kind(&tool, runnable);
+            }
 
-            // Change classification if the metadata has a link present
-            if tool.url.is_some() {
-                kind = ToolType::Website;
+            match tool.kind {
+                ToolType::Website if tool.url.is_none() => {
+                    bail!("website tool {command:?} must have a `url` field");
+                }
+                ToolType::Library if tool.library.is_none() => {
+                    bail!("library tool {command:?} must have a `library` field");
+                }
+                ToolType::Container if tool.container.is_none() => {
+                    bail!("container tool {command:?} must have a `container` field");
+                }
+                _ => {}
+            }
+
+            // Older toolkits use `url` to link executable tools to their homepage.
+            if tool.kind != ToolType::Website && tool.homepage.is_none() {
+                tool.homepage = tool.url.take();
+            }
+
+            if let Some(library) = tool.library.as_ref()
+                && !is_valid_module_name(library.language, &library.module)
+            {
+                bail!(
+                    "library tool {command:?} has an invalid module name: {:?}",
+                    library.module
+                );
+            }
+
+            if let Some(container) = tool.container.as_ref()
+                && !is_valid_image_reference(&container.image)
+            {
+                bail!(
+                    "container tool {command:?} has an invalid image reference: {:?}",
+                    container.image
+                );
+            }
+
+            if let Some(key) = tool.env.keys().find(|v| !is_valid_env_key(v)) {
+                bail!("tool {command:?} has an invalid environment variable name: {key:?}");
+            }
+
+            // Only executable tools are run as a process of their own.
+            if tool.kind != ToolType::Executable && (!tool.env.is_empty() || tool.cwd.is_some()) {
+                bail!("only executable tools can set env or cwd, but {command:?} is not one");
+            }
+
+            #[cfg(feature = "auto-install-tools")]
+            for download in tool.downloads.iter() {
+                download
+                    .validate()
+                    .with_context(|| format!("tool {command:?} has an invalid download"))?;
+            }
+
+            #[cfg(feature = "auto-install-tools")]
+            if let Some(source) = tool.source.as_ref() {
+                source
+                    .validate()
+                    .with_context(|| format!("tool {command:?} has an invalid source"))?;
+            }
+
+            for option in tool.options.iter() {
+                option
+                    .validate()
+                    .with_context(|| format!("tool {command:?} has an invalid option"))?;
             }
 
             // Use the associated key for a name if the name field feels empty.
@@ -58,7 +153,6 @@ impl Toolkit {
                 tool.name = command.clone();
             }
 
-            tool.kind = kind;
             tool.command = command;
             tool.description = tool.description.trim().to_string();
             tools.push(tool);
@@ -98,6 +192,31 @@ impl Toolkit {
         &INNER_VALUE
     }
 
+    /// Loads the built-in catalog of well-known CTF tools that are
+    /// not part of the [default toolkit](Toolkit::default).
+    ///
+    /// It is used to discover tools that the user already has installed
+    /// so they can be added into the user's own toolkit.
+    ///
+    /// This function may panic if there's something wrong with the
+    /// deserialization process from `assets/default/catalog.yml` file.
+    #[must_use]
+    pub fn catalog() -> &'static Self {
+        static INNER_VALUE: LazyLock<Toolkit> = LazyLock::new(|| {
+            let toolkit = Toolkit::from_yaml(include_str!("../../assets/default/catalog.yml"))
+                .context("failed to load built-in catalog.yml")
+                .unwrap();
+
+            debug!(
+                "successfully loaded built-in catalog; loaded {} tool(s)",
+                toolkit.tools().len()
+            );
+            toolkit
+        });
+
+        &INNER_VALUE
+    }
+
     /// Returns the list of tools defined in this toolkit.
     ///
     /// Provides read-only access to all tool metadata entries. Each entry
@@ -107,58 +226,119 @@ impl Toolkit {
         &self.tools
     }
 
+    /// Looks up a tool by the command used to run it.
+    #[must_use]
+    pub fn find_tool(&self, command: &str) -> Option<&ToolMetadata> {
+        self.tools.iter().find(|tool| tool.command == command)
+    }
+
+    /// Extends this toolkit with the tools from another toolkit.
+    ///
+    /// Tools from `other` replace existing tools that share the same
+    /// command, so the user can override any built-in tool definition.
+    pub fn merge(&mut self, other: Toolkit) {
+        for tool in other.tools {
+            match self.tools.iter_mut().find(|v| v.command == tool.command) {
+                Some(existing) => *existing = tool,
+                None => self.tools.push(tool),
+            }
+        }
+        self.tools.sort_by(|a, b| a.command.cmp(&b.command));
+    }
+
     /// Attempts to serialize into a format that follows with
     /// `assets/default/toolkit.yml` in the program repository.
     #[must_use]
     pub fn serialize_into_yml(&self) -> String {
         let mut map = BTreeMap::new();
         for tool in self.tools.iter() {
-            #[cfg(not(feature = "auto-install-tools"))]
-            let mut value = {
-                let mut tool_map = serde_yml::Mapping::new();
-                tool_map.insert("description".into(), tool.description.clone().into());
-                tool_map.insert(
-                    "windows".into(),
-                    serde_yml::to_value(&tool.windows).unwrap(),
-                );
-                tool_map
-            };
-
-            #[cfg(feature = "auto-install-tools")]
-            let mut value = {
-                let mut tool_map = serde_yml::Mapping::new();
-                tool_map.insert("description".into(), tool.description.clone().into());
-                tool_map.insert(
-                    "packages".into(),
-                    serde_yml::to_value(&tool.packages).unwrap(),
-                );
-                tool_map.insert(
-                    "windows".into(),
-                    serde_yml::to_value(&tool.windows).unwrap(),
-                );
-                tool_map.insert(
-                    "downloads".into(),
-                    serde_yml::to_value(&tool.downloads).unwrap(),
-                );
-                tool_map
-            };
-
-            if !tool.name.is_empty() {
-                value.insert("name".into(), tool.name.clone().into());
-            }
-
-            map.insert(tool.command.clone(), value);
+            map.insert(tool.command.clone(), tool.to_yaml_mapping());
         }
         serde_yml::to_string(&map).unwrap()
     }
 }
 
 /// This represents what kind of tool.
-#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+///
+/// It can be declared explicitly in the toolkit with the `kind` field.
+/// Otherwise, a tool is considered as a website if it has a `url` and
+/// nothing else describing how to run or install it.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
 pub enum ToolType {
+    /// A program that can be run from the user's system.
     #[default]
     Executable,
+
+    /// A web application that can be opened in the user's browser.
     Website,
+
+    /// A library of a programming language (e.g. pwntools for Python)
+    /// that is imported from an interpreter instead of being run.
+    Library,
+
+    /// A program shipped as a container image that runs with
+    /// Docker or Podman.
+    Container,
+}
+
+/// Infers the kind of a tool that does not declare it explicitly.
+///
+/// `runnable` tells whether the tool has any of the [`EXECUTABLE_KEYS`].
+fn infer_kind(tool: &ToolMetadata, runnable: bool) -> ToolType {
+    if tool.library.is_some() {
+        ToolType::Library
+    } else if tool.container.is_some() {
+        ToolType::Container
+    } else if tool.url.is_some() && !runnable {
+        ToolType::Website
+    } else {
+        ToolType::Executable
+    }
+}
+
+/// Checks whether a tool command can be used as the name of a single
+/// directory, such as the one its archive is extracted into.
+#[must_use]
+pub fn is_valid_command(command: &str) -> bool {
+    let mut components = Path::new(command).components();
+    matches!(components.next(), Some(Component::Normal(..)))
+        && components.next().is_none()
+        && !command.contains(['/', '\\', '\0'])
+}
+
+/// Checks whether an image reference cannot be mistaken
+/// as an option of the container runtime.
+fn is_valid_image_reference(image: &str) -> bool {
+    !image.is_empty() && !image.starts_with('-') && !image.chars().any(char::is_whitespace)
+}
+
+/// Checks whether an environment variable name can be set
+/// in every supported operating system.
+fn is_valid_env_key(key: &str) -> bool {
+    !key.is_empty() && !key.contains(['=', '\0'])
+}
+
+/// Checks whether a module name is safe to be embedded in
+/// an import statement of an interpreter.
+///
+/// Python modules are dotted identifiers (e.g. `Crypto.Cipher`), while
+/// Ruby libraries are required by their path (e.g. `ronin/support`).
+fn is_valid_module_name(language: LibraryLanguage, module: &str) -> bool {
+    match language {
+        LibraryLanguage::Python => module.split('.').all(|part| {
+            part.chars()
+                .next()
+                .is_some_and(|v| v.is_ascii_alphabetic() || v == '_')
+                && part.chars().all(|v| v.is_ascii_alphanumeric() || v == '_')
+        }),
+        LibraryLanguage::Ruby => {
+            !module.is_empty()
+                && module
+                    .chars()
+                    .all(|v| v.is_ascii_alphanumeric() || matches!(v, '_' | '.' | '/' | '-'))
+        }
+    }
 }
 
 /// Metadata describing a tool provided by a toolkit.
@@ -173,7 +353,7 @@ pub struct ToolMetadata {
 
     /// Type of a tool.
     #[builder(default)]
-    #[serde(skip)]
+    #[serde(default)]
     pub kind: ToolType,
 
     /// The command or invocation used to run the tool
@@ -185,15 +365,52 @@ pub struct ToolMetadata {
     #[serde(default)]
     pub examples: Vec<String>,
 
+    /// Commonly used options of the tool that the user can fill
+    /// in from a form to build the arguments to run the tool with.
+    #[builder(default)]
+    #[serde(default)]
+    pub options: Vec<ToolOption>,
+
+    /// Environment variables set when running the tool.
+    ///
+    /// Their values may refer to the user's home directory with `~`
+    /// and other environment variables with `$VAR` or `${VAR}`.
+    #[builder(default)]
+    #[serde(default)]
+    pub env: BTreeMap<String, String>,
+
+    /// The directory to run the tool from. It is expanded
+    /// the same way as the values of [`env`](ToolMetadata::env).
+    #[serde(default)]
+    pub cwd: Option<String>,
+
     /// A short, human-readable description summarizing the tool
     #[builder(default)]
     pub description: String,
 
     /// A URL that the program can use to automatically open
     /// the user's browser and load a particular webpage.
+    ///
+    /// This is only used by [website tools](ToolType::Website).
     #[serde(default)]
     pub url: Option<String>,
 
+    /// A link to the tool's homepage.
+    #[serde(default)]
+    pub homepage: Option<String>,
+
+    /// A link to the tool's documentation (e.g. manual or wiki).
+    #[serde(default)]
+    pub docs: Option<String>,
+
+    /// Describes how to import the tool if it is a [library](ToolType::Library).
+    #[serde(default)]
+    pub library: Option<ToolLibraryMetadata>,
+
+    /// Describes which image to run if it is a [container tool](ToolType::Container).
+    #[serde(default)]
+    pub container: Option<ToolContainerMetadata>,
+
     /// A mapping from package manager identifier as a key to its
     /// equivalent package manager that provides the tool for that
     /// package manager.
@@ -218,6 +435,231 @@ pub struct ToolMetadata {
     #[builder(default)]
     #[serde(default)]
     pub downloads: ToolPlatformDownloads,
+
+    /// Describes how to build the tool from its source code if
+    /// it cannot be installed from a package or a download.
+    #[cfg(feature = "auto-install-tools")]
+    #[serde(default)]
+    pub source: Option<ToolSourceMetadata>,
+}
+
+impl ToolMetadata {
+    /// Returns the environment variables of the tool with
+    /// their values [expanded](expand_vars).
+    pub fn effective_env(&self) -> Result<Vec<(String, String)>> {
+        self.env
+            .iter()
+            .map(|(key, value)| {
+                let value = expand_vars(value)
+                    .with_context(|| format!("could not expand {key} environment variable"))?;
+                Ok((key.clone(), value))
+            })
+            .collect()
+    }
+
+    /// Returns the [expanded](expand_vars) directory to run the tool from, if any.
+    pub fn effective_cwd(&self) -> Result<Option<PathBuf>> {
+        self.cwd
+            .as_deref()
+            .map(|cwd| {
+                expand_vars(cwd)
+                    .map(PathBuf::from)
+                    .with_context(|| format!("could not expand working directory {cwd:?}"))
+            })
+            .transpose()
+    }
+
+    /// Converts this tool into a YAML mapping that follows the schema of
+    /// `assets/default/toolkit.yml`, excluding the command key itself.
+    ///
+    /// Fields left with their default values are omitted.
+    #[must_use]
+    pub fn to_yaml_mapping(&self) -> serde_yml::Mapping {
+        let mut map = serde_yml::Mapping::new();
+        if !self.name.is_empty() {
+            map.insert("name".into(), self.name.clone().into());
+        }
+        map.insert("description".into(), self.description.clone().into());
+
+        if !self.examples.is_empty() {
+            map.insert(
+                "examples".into(),
+                serde_yml::to_value(&self.examples).unwrap(),
+            );
+        }
+
+        if !self.options.is_empty() {
+            map.insert(
+                "options".into(),
+                serde_yml::to_value(&self.options).unwrap(),
+            );
+        }
+
+        if !self.env.is_empty() {
+            map.insert("env".into(), serde_yml::to_value(&self.env).unwrap());
+        }
+
+        if let Some(cwd) = self.cwd.as_ref() {
+            map.insert("cwd".into(), cwd.clone().into());
+        }
+
+        if let Some(url) = self.url.as_ref() {
+            map.insert("url".into(), url.clone().into());
+        }
+
+        if let Some(homepage) = self.homepage.as_ref() {
+            map.insert("homepage".into(), homepage.clone().into());
+        }
+
+        if let Some(docs) = self.docs.as_ref() {
+            map.insert("docs".into(), docs.clone().into());
+        }
+
+        if let Some(library) = self.library.as_ref() {
+            map.insert("library".into(), serde_yml::to_value(library).unwrap());
+        }
+
+        if let Some(container) = self.container.as_ref() {
+            map.insert("container".into(), serde_yml::to_value(container).unwrap());
+        }
+
+        #[cfg(feature = "auto-install-tools")]
+        if !self.packages.is_empty() {
+            // Sort the packages so the output stays stable across runs.
+            let packages = self.packages.iter().collect::<BTreeMap<_, _>>();
+            map.insert("packages".into(), serde_yml::to_value(packages).unwrap());
+        }
+
+        if self.windows != ToolWindowsMetadata::default() {
+            map.insert(
+                "windows".into(),
+                serde_yml::to_value(&self.windows).unwrap(),
+            );
+        }
+
+        #[cfg(feature = "auto-install-tools")]
+        if self.downloads != ToolPlatformDownloads::default() {
+            map.insert(
+                "downloads".into(),
+                serde_yml::to_value(&self.downloads).unwrap(),
+            );
+        }
+
+        #[cfg(feature = "auto-install-tools")]
+        if let Some(source) = self.source.as_ref() {
+            map.insert("source".into(), serde_yml::to_value(source).unwrap());
+        }
+
+        // Only declare the kind of the tool if it cannot be inferred.
+        let runnable = EXECUTABLE_KEYS.iter().any(|key| map.contains_key(*key));
+        if infer_kind(self, runnable) != self.kind {
+            map.insert("kind".into(), serde_yml::to_value(self.kind).unwrap());
+        }
+
+        map
+    }
+}
+
+/// Describes how a [library tool](ToolType::Library) is imported.
+#[derive(Debug, Deserialize, Builder, Clone, PartialEq, Eq, Serialize)]
+pub struct ToolLibraryMetadata {
+    /// Programming language of the library.
+    pub language: LibraryLanguage,
+
+    /// Name of the module to import (e.g. `pwn` for pwntools).
+    pub module: String,
+
+    /// The interpreter used to import the module. It defaults to
+    /// the usual interpreter of the language (e.g. `python3`).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub interpreter: Option<String>,
+}
+
+impl ToolLibraryMetadata {
+    /// Returns the interpreter used to import the library.
+    #[must_use]
+    pub fn interpreter(&self) -> &str {
+        self.interpreter
+            .as_deref()
+            .unwrap_or_else(|| self.language.default_interpreter())
+    }
+
+    /// Returns the interpreter arguments that exit successfully
+    /// only if the library can be imported.
+    #[must_use]
+    pub fn import_probe_args(&self) -> Vec<String> {
+        match self.language {
+            LibraryLanguage::Python => vec!["-c".into(), format!("import {}", self.module)],
+            LibraryLanguage::Ruby => vec!["-e".into(), format!("require '{}'", self.module)],
+        }
+    }
+
+    /// Returns the interpreter arguments that start an interactive
+    /// session with the library already imported.
+    #[must_use]
+    pub fn interactive_args(&self) -> Vec<String> {
+        match self.language {
+            LibraryLanguage::Python => {
+                vec!["-i".into(), "-c".into(), format!("import {}", self.module)]
+            }
+            LibraryLanguage::Ruby => vec![
+                format!("-r{}", self.module),
+                "-rirb".into(),
+                "-e".into(),
+                "binding.irb".into(),
+            ],
+        }
+    }
+
+    /// Returns the statement used to import the library from its language.
+    #[must_use]
+    pub fn import_statement(&self) -> String {
+        match self.language {
+            LibraryLanguage::Python => format!("import {}", self.module),
+            LibraryLanguage::Ruby => format!("require '{}'", self.module),
+        }
+    }
+}
+
+/// Describes how a [container tool](ToolType::Container) is run.
+#[derive(Debug, Deserialize, Builder, Clone, PartialEq, Eq, Serialize)]
+pub struct ToolContainerMetadata {
+    /// Reference of the image to run (e.g. `sagemath/sagemath:9.5`).
+    pub image: String,
+
+    /// Arguments always passed to the image before the user's arguments.
+    #[builder(default)]
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub args: Vec<String>,
+}
+
+/// Programming languages supported by [library tools](ToolType::Library).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LibraryLanguage {
+    Python,
+    Ruby,
+}
+
+impl LibraryLanguage {
+    /// Returns a human-friendly name for this language.
+    #[must_use]
+    pub fn as_display_name(&self) -> &'static str {
+        match self {
+            Self::Python => "Python",
+            Self::Ruby => "Ruby",
+        }
+    }
+
+    /// Returns the interpreter commonly used for this language.
+    #[must_use]
+    pub fn default_interpreter(&self) -> &'static str {
+        match self {
+            Self::Python if cfg!(windows) => "python",
+            Self::Python => "python3",
+            Self::Ruby => "ruby",
+        }
+    }
 }
 
 /// Windows-specific metadata on how a tool should run in Windows.
@@ -247,26 +689,806 @@ pub struct ToolPlatformDownloads {
     pub linux: Option<ToolDownloadInstructions>,
 }
 
+impl ToolPlatformDownloads {
+    /// Gets the download instructions for an operating system, named
+    /// the same way as [`std::env::consts::OS`] (e.g. `linux`).
+    #[must_use]
+    pub fn for_os(&self, os: &str) -> Option<&ToolDownloadInstructions> {
+        match os {
+            "windows" => self.windows.as_ref(),
+            "macos" => self.macos.as_ref(),
+            "linux" => self.linux.as_ref(),
+            _ => None,
+        }
+    }
+
+    /// Iterates over the download instructions of every platform.
+    pub fn iter(&self) -> impl Iterator<Item = &ToolDownloadInstructions> {
+        [&self.windows, &self.macos, &self.linux]
+            .into_iter()
+            .flatten()
+    }
+}
+
+/// Formats of a file downloaded to install a tool.
 #[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
 #[serde(rename_all = "lowercase")]
 pub enum DownloadFileFormat {
     ZIP,
+
+    /// A tar archive compressed with gzip.
+    #[serde(rename = "tar.gz")]
+    TarGz,
+
+    /// A tar archive compressed with xz.
+    #[serde(rename = "tar.xz")]
+    TarXz,
+
+    /// A tar archive compressed with Zstandard.
+    #[serde(rename = "tar.zst")]
+    TarZst,
+
+    /// A self-contained Linux application that runs without being installed.
+    AppImage,
+
+    /// A standalone executable of the tool itself.
+    Binary,
+
+    /// A Debian package installed with APT or dpkg.
+    Deb,
+
+    /// An RPM package installed with DNF or rpm.
+    Rpm,
+
+    /// An installer that is run once it is downloaded.
     #[serde(rename = "exe")]
     Executable,
 }
 
+impl DownloadFileFormat {
+    /// Whether the downloaded file is an archive that is
+    /// extracted instead of being run.
+    #[must_use]
+    pub fn is_archive(&self) -> bool {
+        matches!(self, Self::ZIP | Self::TarGz | Self::TarXz | Self::TarZst)
+    }
+
+    /// Returns the file name extension of this format, if any.
+    #[must_use]
+    pub fn extension(&self) -> Option<&'static str> {
+        match self {
+            Self::ZIP => Some("zip"),
+            Self::TarGz => Some("tar.gz"),
+            Self::TarXz => Some("tar.xz"),
+            Self::TarZst => Some("tar.zst"),
+            Self::AppImage => Some("AppImage"),
+            Self::Deb => Some("deb"),
+            Self::Rpm => Some("rpm"),
+            Self::Executable | Self::Binary if cfg!(windows) => Some("exe"),
+            Self::Executable | Self::Binary => None,
+        }
+    }
+}
+
 #[derive(Debug, Builder, Clone, PartialEq, Eq, Deserialize, Serialize)]
 pub struct ToolDownloadInstructions {
     pub format: DownloadFileFormat,
     pub url: String,
+
+    /// Other URLs where the same file can be downloaded from.
+    ///
+    /// They are tried in order if the download from `url` fails.
+    #[builder(default)]
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub mirrors: Vec<String>,
+
+    /// Paths of the executables to expose from an extracted archive.
+    ///
+    /// They are relative to the root of the archive or to its only
+    /// top-level directory. If none is given, the executable named
+    /// after the tool's command is exposed.
+    #[builder(default)]
+    #[serde(
+        default,
+        alias = "entrypoint",
+        deserialize_with = "one_or_many",
+        skip_serializing_if = "Vec::is_empty"
+    )]
+    pub bin: Vec<String>,
+
+    /// Expected SHA-256 checksum of the downloaded file, in hexadecimal.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sha256: Option<String>,
+
+    /// Expected SHA-512 checksum of the downloaded file, in hexadecimal.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sha512: Option<String>,
+
+    /// Detached signature published next to the downloaded file.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub signature: Option<DownloadSignature>,
+}
+
+impl ToolDownloadInstructions {
+    /// Every URL where the file can be downloaded from,
+    /// starting with `url` and followed by its mirrors.
+    pub fn urls(&self) -> impl Iterator<Item = &str> {
+        std::iter::once(self.url.as_str()).chain(self.mirrors.iter().map(String::as_str))
+    }
+
+    /// Whether the downloaded file can be verified against a checksum.
+    #[must_use]
+    pub fn has_checksum(&self) -> bool {
+        self.sha256.is_some() || self.sha512.is_some()
+    }
+
+    /// Whether the downloaded file can be verified against
+    /// a checksum or a signature.
+    #[must_use]
+    pub fn is_verifiable(&self) -> bool {
+        self.has_checksum() || self.signature.is_some()
+    }
+
+    /// Checks whether every [executable to expose](ToolDownloadInstructions::bin)
+    /// stays inside the extracted archive, whether every mirror has a URL,
+    /// whether the checksums are written in hexadecimal with the length
+    /// of their digest and whether the signature has a valid public key.
+    pub fn validate(&self) -> Result<()> {
+        if !self.bin.is_empty() && !self.format.is_archive() {
+            bail!("only archives can declare executables to expose");
+        }
+
+        if self.mirrors.iter().any(|v| v.trim().is_empty()) {
+            bail!("mirror URLs must not be empty");
+        }
+
+        let checksums = [("sha256", &self.sha256, 64), ("sha512", &self.sha512, 128)];
+        for (name, checksum, len) in checksums {
+            if let Some(checksum) = checksum
+                && (checksum.len() != len || !checksum.chars().all(|v| v.is_ascii_hexdigit()))
+            {
+                bail!("invalid {name} checksum: {checksum:?}");
+            }
+        }
+
+        if let Some(signature) = self.signature.as_ref() {
+            signature.validate()?;
+        }
+
+        for bin in self.bin.iter() {
+            let path = std::path::Path::new(bin);
+            let is_inside = path
+                .components()
+                .all(|v| matches!(v, std::path::Component::Normal(..)));
+
+            if bin.is_empty() || !is_inside {
+                bail!("invalid executable path: {bin:?}");
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// A detached signature of a downloaded file along with the
+/// public key of its publisher that it must be signed with.
+///
+/// It is written in the toolkit as the `url` of the signature
+/// and either a `minisign` or a `pgp` public key.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(try_from = "RawDownloadSignature", into = "RawDownloadSignature")]
+pub struct DownloadSignature {
+    /// URL of the signature file.
+    pub url: String,
+
+    /// Pinned public key of the publisher.
+    pub key: SignatureKey,
+}
+
+/// Public keys that a download can be signed with.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SignatureKey {
+    /// A [minisign](https://jedisct1.github.io/minisign/) public key,
+    /// either in base64 or in the `minisign.pub` file format.
+    Minisign(String),
+
+    /// An ASCII-armored OpenPGP public key. Signatures made with
+    /// it are verified with GnuPG.
+    OpenPgp(String),
+}
+
+#[derive(Deserialize, Serialize)]
+struct RawDownloadSignature {
+    url: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    minisign: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pgp: Option<String>,
+}
+
+impl TryFrom<RawDownloadSignature> for DownloadSignature {
+    type Error = String;
+
+    fn try_from(raw: RawDownloadSignature) -> Result<Self, Self::Error> {
+        let key = match (raw.minisign, raw.pgp) {
+            (Some(key), None) => SignatureKey::Minisign(key),
+            (None, Some(key)) => SignatureKey::OpenPgp(key),
+            _ => return Err("signature must have either a minisign or a pgp key".into()),
+        };
+        Ok(Self { url: raw.url, key })
+    }
+}
+
+impl From<DownloadSignature> for RawDownloadSignature {
+    fn from(signature: DownloadSignature) -> Self {
+        let (minisign, pgp) = match signature.key {
+            SignatureKey::Minisign(key) => (Some(key), None),
+            SignatureKey::OpenPgp(key) => (None, Some(key)),
+        };
+        Self {
+            url: signature.url,
+            minisign,
+            pgp,
+        }
+    }
+}
+
+impl DownloadSignature {
+    /// Checks whether the signature has a URL and a valid public key.
+    pub fn validate(&self) -> Result<()> {
+        if self.url.is_empty() {
+            bail!("signature must have a URL");
+        }
+
+        match &self.key {
+            SignatureKey::Minisign(..) => {
+                self.key.minisign_public_key()?;
+            }
+            SignatureKey::OpenPgp(key) => {
+                if !key.contains("-----BEGIN PGP PUBLIC KEY BLOCK-----") {
+                    bail!("pgp key must be an ASCII-armored public key");
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl SignatureKey {
+    /// Decodes the minisign public key.
+    ///
+    /// It fails if the key is not a minisign key or if it is malformed.
+    pub fn minisign_public_key(&self) -> Result<minisign_verify::PublicKey> {
+        let Self::Minisign(key) = self else {
+            bail!("not a minisign public key");
+        };
+
+        let key = key.trim();
+        let decoded = if key.contains('\n') {
+            minisign_verify::PublicKey::decode(key)
+        } else {
+            minisign_verify::PublicKey::from_base64(key)
+        };
+        decoded.map_err(|error| anyhow::anyhow!("invalid minisign public key: {error}"))
+    }
+}
+
+/// Deserializes either a single string or a list of strings.
+fn one_or_many<'de, D>(deserializer: D) -> Result<Vec<String>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum OneOrMany {
+        One(String),
+        Many(Vec<String>),
+    }
+
+    Ok(match OneOrMany::deserialize(deserializer)? {
+        OneOrMany::One(value) => vec![value],
+        OneOrMany::Many(values) => values,
+    })
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::registry::Toolkit;
+    use pretty_assertions::assert_eq;
+    use std::path::PathBuf;
+
+    use crate::registry::{LibraryLanguage, OptionValueType, ToolType, Toolkit};
 
     #[test]
     fn should_load_builtin_toolkit() {
         _ = Toolkit::default();
     }
+
+    #[test]
+    fn builtin_catalog_should_not_overlap_default_toolkit() {
+        let catalog = Toolkit::catalog();
+        assert!(!catalog.tools().is_empty());
+
+        for tool in catalog.tools() {
+            assert!(
+                Toolkit::default().find_tool(&tool.command).is_none(),
+                "{:?} is already in the default toolkit",
+                tool.command
+            );
+        }
+    }
+
+    #[test]
+    fn should_load_empty_toolkit() {
+        assert!(Toolkit::from_yaml("").unwrap().tools().is_empty());
+        assert!(
+            Toolkit::from_yaml("# nothing here")
+                .unwrap()
+                .tools()
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn should_infer_tool_kinds() {
+        let toolkit = Toolkit::from_yaml(
+            "
+            site:
+              description: A website
+              url: https://example.com
+            cli:
+              description: An executable with a homepage
+              url: https://example.com
+              examples: [cli --help]
+            explicit:
+              kind: executable
+              description: Another executable with a homepage
+              url: https://example.com
+            ",
+        )
+        .unwrap();
+
+        let cli = toolkit.find_tool("cli").unwrap();
+        assert_eq!(cli.kind, ToolType::Executable);
+        assert_eq!(cli.url, None);
+        assert_eq!(cli.homepage.as_deref(), Some("https://example.com"));
+
+        let explicit = toolkit.find_tool("explicit").unwrap();
+        assert_eq!(explicit.kind, ToolType::Executable);
+        assert_eq!(explicit.homepage.as_deref(), Some("https://example.com"));
+
+        let site = toolkit.find_tool("site").unwrap();
+        assert_eq!(site.kind, ToolType::Website);
+        assert_eq!(site.url.as_deref(), Some("https://example.com"));
+    }
+
+    #[test]
+    fn should_keep_links_of_executable_tools() {
+        let toolkit = Toolkit::from_yaml(
+            "
+            hashcat:
+              description: Password cracker
+              homepage: https://hashcat.net/hashcat/
+              docs: https://hashcat.net/wiki/
+            ",
+        )
+        .unwrap();
+
+        let hashcat = toolkit.find_tool("hashcat").unwrap();
+        assert_eq!(hashcat.kind, ToolType::Executable);
+        assert_eq!(hashcat.docs.as_deref(), Some("https://hashcat.net/wiki/"));
+
+        // Serializing it back should not change anything.
+        let roundtrip = Toolkit::from_yaml(&toolkit.serialize_into_yml()).unwrap();
+        assert_eq!(roundtrip, toolkit);
+    }
+
+    #[test]
+    fn should_fail_on_website_without_url() {
+        assert!(Toolkit::from_yaml("foo:\n  kind: website\n  description: x").is_err());
+    }
+
+    #[test]
+    fn should_load_library_tools() {
+        let toolkit = Toolkit::from_yaml(
+            "
+            pwntools:
+              description: CTF framework and exploit development library
+              homepage: https://github.com/Gallopsled/pwntools
+              library:
+                language: python
+                module: pwn
+            ronin:
+              kind: library
+              description: Ruby toolkit for security research
+              library:
+                language: ruby
+                module: ronin/support
+                interpreter: ruby3.2
+            ",
+        )
+        .unwrap();
+
+        let pwntools = toolkit.find_tool("pwntools").unwrap();
+        assert_eq!(pwntools.kind, ToolType::Library);
+
+        let library = pwntools.library.as_ref().unwrap();
+        assert_eq!(library.language, LibraryLanguage::Python);
+        assert_eq!(library.import_probe_args(), ["-c", "import pwn"]);
+
+        let ronin = toolkit
+            .find_tool("ronin")
+            .unwrap()
+            .library
+            .as_ref()
+            .unwrap();
+        assert_eq!(ronin.interpreter(), "ruby3.2");
+        assert_eq!(ronin.import_probe_args(), ["-e", "require 'ronin/support'"]);
+
+        let roundtrip = Toolkit::from_yaml(&toolkit.serialize_into_yml()).unwrap();
+        assert_eq!(roundtrip, toolkit);
+    }
+
+    #[test]
+    fn should_fail_on_invalid_library_tools() {
+        assert!(Toolkit::from_yaml("foo:\n  kind: library\n  description: x").is_err());
+        assert!(
+            Toolkit::from_yaml(
+                "foo:\n  library:\n    language: python\n    module: \"os; os.system('id')\""
+            )
+            .is_err()
+        );
+    }
+
+    #[test]
+    fn test_is_valid_module_name() {
+        use super::is_valid_module_name;
+
+        assert!(is_valid_module_name(LibraryLanguage::Python, "pwn"));
+        assert!(is_valid_module_name(
+            LibraryLanguage::Python,
+            "_private.mod2"
+        ));
+        // Neither `-` nor `/` can appear in an importable Python module.
+        assert!(!is_valid_module_name(LibraryLanguage::Python, "pwn-tools"));
+        assert!(!is_valid_module_name(
+            LibraryLanguage::Python,
+            "Crypto/Cipher"
+        ));
+        assert!(!is_valid_module_name(
+            LibraryLanguage::Python,
+            "Crypto..Cipher"
+        ));
+        assert!(!is_valid_module_name(LibraryLanguage::Python, "1pwn"));
+        assert!(is_valid_module_name(LibraryLanguage::Ruby, "ronin/support"));
+        assert!(is_valid_module_name(LibraryLanguage::Ruby, "net-ssh"));
+        assert!(!is_valid_module_name(
+            LibraryLanguage::Ruby,
+            "a'; system('id')"
+        ));
+    }
+
+    #[test]
+    fn should_load_container_tools() {
+        let toolkit = Toolkit::from_yaml(
+            "
+            sage:
+              description: SageMath 9.5
+              container:
+                image: sagemath/sagemath:9.5
+                args: [sage]
+            ",
+        )
+        .unwrap();
+
+        let sage = toolkit.find_tool("sage").unwrap();
+        assert_eq!(sage.kind, ToolType::Container);
+
+        let container = sage.container.as_ref().unwrap();
+        assert_eq!(container.image, "sagemath/sagemath:9.5");
+        assert_eq!(container.args, ["sage"]);
+
+        let roundtrip = Toolkit::from_yaml(&toolkit.serialize_into_yml()).unwrap();
+        assert_eq!(roundtrip, toolkit);
+    }
+
+    #[test]
+    fn should_fail_on_invalid_container_tools() {
+        assert!(Toolkit::from_yaml("foo:\n  kind: container\n  description: x").is_err());
+        assert!(Toolkit::from_yaml("foo:\n  container:\n    image: --privileged").is_err());
+    }
+
+    #[test]
+    fn should_load_tool_options() {
+        let toolkit = Toolkit::from_yaml(
+            "
+            hashcat:
+              description: hashcat
+              options:
+                - flag: -a
+                  description: Attack mode
+                  value: number
+                  choices:
+                    - value: '0'
+                      description: Straight
+                    - '3'
+                - flag: --force
+                  description: Ignore warnings
+                - description: Hash file
+                  value: path
+                  required: true
+            ",
+        )
+        .unwrap();
+
+        let hashcat = toolkit.find_tool("hashcat").unwrap();
+        assert_eq!(hashcat.kind, ToolType::Executable);
+        assert_eq!(hashcat.options.len(), 3);
+
+        let attack_mode = &hashcat.options[0];
+        assert_eq!(attack_mode.value, Some(OptionValueType::Number));
+        assert_eq!(
+            attack_mode.choices[0].description.as_deref(),
+            Some("Straight")
+        );
+        assert_eq!(attack_mode.choices[1].value, "3");
+        assert!(hashcat.options[1].is_switch());
+        assert!(hashcat.options[2].required);
+
+        let roundtrip = Toolkit::from_yaml(&toolkit.serialize_into_yml()).unwrap();
+        assert_eq!(roundtrip, toolkit);
+    }
+
+    #[test]
+    fn should_fail_on_invalid_tool_options() {
+        assert!(Toolkit::from_yaml("foo:\n  options:\n    - description: x").is_err());
+        assert!(
+            Toolkit::from_yaml("foo:\n  options:\n    - flag: x\n      description: x").is_err()
+        );
+    }
+
+    #[test]
+    fn should_load_env_and_cwd() {
+        let toolkit = Toolkit::from_yaml(
+            "
+            john:
+              description: John the Ripper
+              env:
+                JOHN: $CARGO_MANIFEST_DIR/run
+              cwd: ${CARGO_MANIFEST_DIR}
+            ",
+        )
+        .unwrap();
+
+        let john = toolkit.find_tool("john").unwrap();
+        assert_eq!(john.kind, ToolType::Executable);
+
+        let manifest_dir = env!("CARGO_MANIFEST_DIR");
+        assert_eq!(
+            john.effective_env().unwrap(),
+            [("JOHN".to_string(), format!("{manifest_dir}/run"))]
+        );
+        assert_eq!(
+            john.effective_cwd().unwrap(),
+            Some(PathBuf::from(manifest_dir))
+        );
+
+        let roundtrip = Toolkit::from_yaml(&toolkit.serialize_into_yml()).unwrap();
+        assert_eq!(roundtrip, toolkit);
+
+        assert!(Toolkit::from_yaml("foo:\n  env:\n    A=B: c").is_err());
+        assert!(
+            Toolkit::from_yaml("foo:\n  kind: website\n  url: https://a.b\n  cwd: /tmp").is_err()
+        );
+        assert!(
+            Toolkit::from_yaml(
+                "foo:\n  kind: library\n  library:\n    language: python\n    module: foo\n  env:\n    A: b"
+            )
+            .is_err()
+        );
+    }
+
+    #[cfg(feature = "auto-install-tools")]
+    #[test]
+    fn should_load_tool_source() {
+        use crate::registry::BuildSystem;
+
+        let toolkit = Toolkit::from_yaml(
+            "
+            stegseek:
+              description: StegSeek
+              source:
+                git: https://github.com/RickdeJager/stegseek
+                ref: v0.6
+                build: cmake
+            ",
+        )
+        .unwrap();
+
+        let stegseek = toolkit.find_tool("stegseek").unwrap();
+        assert_eq!(stegseek.kind, ToolType::Executable);
+
+        let source = stegseek.source.as_ref().unwrap();
+        assert_eq!(source.git_ref.as_deref(), Some("v0.6"));
+        assert_eq!(source.build_system, BuildSystem::CMake);
+
+        let roundtrip = Toolkit::from_yaml(&toolkit.serialize_into_yml()).unwrap();
+        assert_eq!(roundtrip, toolkit);
+
+        let source = |source: &str| {
+            Toolkit::from_yaml(&format!("foo:\n  description: x\n  source:\n    {source}"))
+        };
+        assert!(source("git: -x\n    build: make").is_err());
+        assert!(source("git: x\n    build: ninja").is_err());
+        source("git: x\n    build: make").unwrap();
+    }
+
+    #[cfg(feature = "auto-install-tools")]
+    #[test]
+    fn should_load_archive_downloads() {
+        use crate::registry::DownloadFileFormat;
+
+        let toolkit = Toolkit::from_yaml(
+            "
+            ghidra:
+              description: Ghidra
+              downloads:
+                linux:
+                  format: tar.gz
+                  url: https://foo.local/ghidra.tar.gz
+                  bin: ghidraRun
+                windows:
+                  format: zip
+                  url: https://foo.local/ghidra.zip
+                  entrypoint: [ghidraRun.bat, support/analyzeHeadless.bat]
+            ",
+        )
+        .unwrap();
+
+        let downloads = &toolkit.find_tool("ghidra").unwrap().downloads;
+        let linux = downloads.linux.as_ref().unwrap();
+        assert_eq!(linux.format, DownloadFileFormat::TarGz);
+        assert_eq!(linux.bin, ["ghidraRun"]);
+
+        let windows = downloads.windows.as_ref().unwrap();
+        assert_eq!(windows.format, DownloadFileFormat::ZIP);
+        assert_eq!(windows.bin.len(), 2);
+
+        let roundtrip = Toolkit::from_yaml(&toolkit.serialize_into_yml()).unwrap();
+        assert_eq!(roundtrip, toolkit);
+
+        for (name, format) in [
+            ("appimage", DownloadFileFormat::AppImage),
+            ("binary", DownloadFileFormat::Binary),
+            ("deb", DownloadFileFormat::Deb),
+            ("rpm", DownloadFileFormat::Rpm),
+            ("tar.xz", DownloadFileFormat::TarXz),
+        ] {
+            assert_eq!(
+                serde_yml::from_str::<DownloadFileFormat>(name).unwrap(),
+                format
+            );
+        }
+
+        let invalid = |bin: &str| {
+            Toolkit::from_yaml(&format!(
+                "foo:\n  description: x\n  downloads:\n    linux:\n      format: zip\n      url: x\n      bin: {bin}"
+            ))
+        };
+        assert!(invalid("../escape").is_err());
+        assert!(invalid("/usr/bin/escape").is_err());
+        invalid("bin/ok").unwrap();
+    }
+
+    #[cfg(feature = "auto-install-tools")]
+    #[test]
+    fn should_load_download_checksums() {
+        use crate::registry::{DownloadSignature, SignatureKey};
+
+        let sha256 = "9F86D081884C7D659A2FEAA0C55AD015A3BF4F1B2B0B822CD15D6C15B0F00A08";
+        let toolkit = Toolkit::from_yaml(&format!(
+            "foo:\n  description: x\n  downloads:\n    linux:\n      format: binary\n      url: x\n      sha256: {sha256}"
+        ))
+        .unwrap();
+
+        let linux = toolkit.find_tool("foo").unwrap().downloads.linux.clone();
+        let linux = linux.unwrap();
+        assert_eq!(linux.sha256.as_deref(), Some(sha256));
+        assert_eq!(linux.sha512, None);
+        assert!(linux.has_checksum());
+
+        let roundtrip = Toolkit::from_yaml(&toolkit.serialize_into_yml()).unwrap();
+        assert_eq!(roundtrip, toolkit);
+
+        let invalid = |checksum: &str| {
+            Toolkit::from_yaml(&format!(
+                "foo:\n  description: x\n  downloads:\n    linux:\n      format: binary\n      url: x\n      {checksum}"
+            ))
+        };
+        assert!(invalid("sha256: abc").is_err());
+        assert!(invalid(&format!("sha256: {}", "g".repeat(64))).is_err());
+        assert!(invalid(&format!("sha512: {sha256}")).is_err());
+        invalid(&format!("sha512: {}", "0".repeat(128))).unwrap();
+
+        let key = "RWQf6LRCGA9i53mlYecO4IzT51TGPpvWucNSCh1CBM0QTaLn73Y7GFO3";
+        let toolkit = invalid(&format!(
+            "signature:\n        url: x.minisig\n        minisign: {key}"
+        ))
+        .unwrap();
+        let linux = toolkit.find_tool("foo").unwrap().downloads.linux.as_ref();
+        assert_eq!(
+            linux.unwrap().signature,
+            Some(DownloadSignature {
+                url: "x.minisig".into(),
+                key: SignatureKey::Minisign(key.into()),
+            })
+        );
+        assert!(linux.unwrap().is_verifiable());
+
+        let roundtrip = Toolkit::from_yaml(&toolkit.serialize_into_yml()).unwrap();
+        assert_eq!(roundtrip, toolkit);
+
+        assert!(invalid("signature:\n        url: x.minisig\n        minisign: x").is_err());
+        assert!(invalid("signature:\n        url: x.asc\n        pgp: x").is_err());
+        assert!(invalid("signature:\n        url: x.asc").is_err());
+        assert!(
+            invalid(&format!(
+                "signature:\n        url: x\n        minisign: {key}\n        pgp: {key}"
+            ))
+            .is_err()
+        );
+    }
+
+    #[cfg(feature = "auto-install-tools")]
+    #[test]
+    fn should_load_download_mirrors() {
+        let toolkit = Toolkit::from_yaml(
+            "
+            foo:
+              description: x
+              downloads:
+                linux:
+                  format: binary
+                  url: https://foo.local/tool
+                  mirrors:
+                    - https://mirror-a.local/tool
+                    - https://mirror-b.local/tool
+            ",
+        )
+        .unwrap();
+
+        let linux = toolkit.find_tool("foo").unwrap().downloads.linux.as_ref();
+        assert_eq!(
+            linux.unwrap().urls().collect::<Vec<_>>(),
+            [
+                "https://foo.local/tool",
+                "https://mirror-a.local/tool",
+                "https://mirror-b.local/tool",
+            ]
+        );
+
+        let roundtrip = Toolkit::from_yaml(&toolkit.serialize_into_yml()).unwrap();
+        assert_eq!(roundtrip, toolkit);
+
+        assert!(
+            Toolkit::from_yaml(
+                "foo:\n  description: x\n  downloads:\n    linux:\n      format: binary\n      url: x\n      mirrors: ['']"
+            )
+            .is_err()
+        );
+    }
+
+    #[test]
+    fn should_fail_on_invalid_command() {
+        for command in ["..", ".", "foo/bar", "foo\\bar", "/foo", "foo/"] {
+            let yaml = format!("{command:?}:\n  description: x");
+            assert!(Toolkit::from_yaml(&yaml).is_err(), "{command:?}");
+        }
+        Toolkit::from_yaml("foo.bar:\n  description: x").unwrap();
+    }
+
+    #[test]
+    fn should_fail_on_malformed_tool() {
+        assert!(Toolkit::from_yaml("foo:\n  examples: 1").is_err());
+    }
 }
@@ -0,0 +1,233 @@
+use anyhow::{Context, Result, bail};
+use log::{debug, warn};
+use minisign_verify::{PublicKey, Signature};
+use std::ffi::OsString;
+use std::path::{Path, PathBuf};
+use thiserror::Error;
+
+/// File extension of the public keys stored in the trust store.
+const PUBLIC_KEY_EXTENSION: &str = "pub";
+
+/// A collection of publisher keys that the user trusts to sign
+/// toolkit files shared by others.
+///
+/// Keys are stored as [minisign] public key files (`<name>.pub`)
+/// inside a directory, usually `<config dir>/trusted-keys`.
+///
+/// [minisign]: https://jedisct1.github.io/minisign/
+#[derive(Debug, Clone, Default)]
+pub struct TrustStore {
+    keys: Vec<TrustedKey>,
+}
+
+#[derive(Debug, Clone)]
+struct TrustedKey {
+    name: String,
+    key: PublicKey,
+}
+
+/// Errors that can occur while verifying a signed toolkit file.
+#[derive(Debug, Error)]
+pub enum SignatureError {
+    /// The signature file could not be parsed.
+    #[error("malformed signature: {0}")]
+    Malformed(minisign_verify::Error),
+
+    /// The signature was not made by any of the trusted keys.
+    #[error("signed by a key that is not trusted")]
+    Untrusted,
+
+    /// The signature does not match the contents of the file, which
+    /// means the file has been tampered with.
+    #[error("bad signature: {0}")]
+    Invalid(minisign_verify::Error),
+}
+
+impl TrustStore {
+    /// Loads every trusted key from a directory.
+    ///
+    /// It returns an empty trust store if the directory does not exist.
+    pub fn load(dir: &Path) -> Result<Self> {
+        let entries = match std::fs::read_dir(dir) {
+            Ok(entries) => entries,
+            Err(error) if error.kind() == std::io::ErrorKind::NotFound => {
+                return Ok(Self::default());
+            }
+            Err(error) => {
+                return Err(error).with_context(|| format!("could not read {}", dir.display()));
+            }
+        };
+
+        let mut store = Self::default();
+        for entry in entries {
+            let path = entry?.path();
+            if path.extension().is_none_or(|v| v != PUBLIC_KEY_EXTENSION) {
+                continue;
+            }
+
+            let Some(name) = path.file_stem().and_then(|v| v.to_str()) else {
+                warn!("ignoring trusted key with invalid name: {}", path.display());
+                continue;
+            };
+
+            let key = PublicKey::from_file(&path).with_context(|| {
+                format!(
+                    "could not load trusted key {name:?} from {}",
+                    path.display()
+                )
+            })?;
+
+            debug!("loaded trusted key {name:?}");
+            store.add_key(name, key);
+        }
+
+        // Keep the order stable regardless of the file system.
+        store.keys.sort_by(|a, b| a.name.cmp(&b.name));
+        Ok(store)
+    }
+
+    /// Trusts a public key with the given name.
+    pub fn add_key(&mut self, name: &str, key: PublicKey) {
+        self.keys.push(TrustedKey {
+            name: name.to_string(),
+            key,
+        });
+    }
+
+    /// Returns the names of the trusted keys.
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.keys.iter().map(|v| v.name.as_str())
+    }
+
+    /// Verifies the contents of a file with its minisign signature.
+    ///
+    /// Returns the name of the trusted key that made the signature.
+    pub fn verify(&self, contents: &[u8], signature: &str) -> Result<&str, SignatureError> {
+        let signature = Signature::decode(signature).map_err(SignatureError::Malformed)?;
+        for trusted in self.keys.iter() {
+            match trusted.key.verify(contents, &signature, false) {
+                Ok(()) => return Ok(&trusted.name),
+                Err(minisign_verify::Error::UnexpectedKeyId) => continue,
+                Err(error) => return Err(SignatureError::Invalid(error)),
+            }
+        }
+        Err(SignatureError::Untrusted)
+    }
+
+    /// Saves a minisign public key file into the trust store located at `dir`.
+    ///
+    /// Returns the path where the key is saved.
+    pub fn install_key(dir: &Path, name: &str, contents: &str) -> Result<PathBuf> {
+        if name.is_empty()
+            || !name
+                .chars()
+                .all(|v| v.is_ascii_alphanumeric() || matches!(v, '_' | '-' | '.'))
+        {
+            bail!("{name:?} is not a valid key name");
+        }
+
+        PublicKey::decode(contents).context("invalid minisign public key")?;
+
+        let path = dir.join(format!("{name}.{PUBLIC_KEY_EXTENSION}"));
+        if path.exists() {
+            bail!("a key named {name:?} is already trusted");
+        }
+
+        std::fs::create_dir_all(dir)
+            .with_context(|| format!("could not create directory {}", dir.display()))?;
+
+        std::fs::write(&path, contents)
+            .with_context(|| format!("could not write into {}", path.display()))?;
+
+        Ok(path)
+    }
+}
+
+/// Returns the path to the detached signature of a toolkit file,
+/// which is the file path with `.minisig` appended to it.
+#[must_use]
+pub fn signature_path(path: &Path) -> PathBuf {
+    let mut path = OsString::from(path.as_os_str());
+    path.push(".minisig");
+    PathBuf::from(path)
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use minisign::KeyPair;
+    use std::io::Cursor;
+    use std::path::Path;
+    use tempdir::TempDir;
+
+    use super::{SignatureError, TrustStore, signature_path};
+
+    /// Generates a new key pair and returns it along with its
+    /// public key in the `minisign.pub` file format.
+    pub(crate) fn generate_key() -> (KeyPair, String) {
+        let keypair = KeyPair::generate_unencrypted_keypair().unwrap();
+        let public_key = keypair.pk.to_box().unwrap().into_string();
+        (keypair, public_key)
+    }
+
+    /// Signs the contents with a key pair and returns the signature
+    /// in the `.minisig` file format.
+    pub(crate) fn sign(keypair: &KeyPair, contents: &[u8]) -> String {
+        minisign::sign(None, &keypair.sk, Cursor::new(contents), None, None)
+            .unwrap()
+            .into_string()
+    }
+
+    #[test]
+    fn test_verify_signatures() {
+        let (publisher, publisher_pk) = generate_key();
+        let (stranger, _) = generate_key();
+
+        let dir = TempDir::new("ctftools_test").unwrap();
+        TrustStore::install_key(dir.path(), "publisher", &publisher_pk).unwrap();
+
+        let store = TrustStore::load(dir.path()).unwrap();
+        assert_eq!(store.names().collect::<Vec<_>>(), ["publisher"]);
+
+        let contents = b"foo:\n  description: foo\n";
+        let signature = sign(&publisher, contents);
+        assert_eq!(store.verify(contents, &signature).unwrap(), "publisher");
+
+        let tampered = b"foo:\n  description: bar\n";
+        assert!(matches!(
+            store.verify(tampered, &signature),
+            Err(SignatureError::Invalid(..))
+        ));
+
+        let signature = sign(&stranger, contents);
+        assert!(matches!(
+            store.verify(contents, &signature),
+            Err(SignatureError::Untrusted)
+        ));
+
+        assert!(matches!(
+            store.verify(contents, "not a signature"),
+            Err(SignatureError::Malformed(..))
+        ));
+    }
+
+    #[test]
+    fn test_install_key() {
+        let (_, public_key) = generate_key();
+        let dir = TempDir::new("ctftools_test").unwrap();
+
+        assert!(TrustStore::install_key(dir.path(), "../evil", &public_key).is_err());
+        assert!(TrustStore::install_key(dir.path(), "bad", "not a key").is_err());
+
+        let path = TrustStore::install_key(dir.path(), "good", &public_key).unwrap();
+        assert_eq!(path, dir.path().join("good.pub"));
+        assert!(TrustStore::install_key(dir.path(), "good", &public_key).is_err());
+    }
+
+    #[test]
+    fn test_signature_path() {
+        assert_eq!(
+            signature_path(Path::new("/tmp/toolkit.yml")),
+            Path::new("/tmp/toolkit.yml.minisig")
+        );
+    }
+}
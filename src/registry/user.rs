@@ -0,0 +1,167 @@
+use anyhow::{Context, Result, bail};
+use std::collections::BTreeMap;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use crate::registry::{ToolMetadata, Toolkit};
+
+/// The user's own toolkit file that extends the built-in toolkit.
+///
+/// It uses the same schema as `assets/default/toolkit.yml` and its tools
+/// override built-in tools that share the same command.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UserToolkit {
+    path: PathBuf,
+}
+
+impl UserToolkit {
+    /// Creates a [`UserToolkit`] pointing to the given file path.
+    ///
+    /// The file does not have to exist yet.
+    #[must_use]
+    pub fn new<T: Into<PathBuf>>(path: T) -> Self {
+        Self { path: path.into() }
+    }
+
+    /// Path to the user's toolkit file.
+    #[must_use]
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Loads the toolkit from the user's toolkit file.
+    ///
+    /// Returns `Ok(None)` if the file does not exist.
+    pub fn load(&self) -> Result<Option<Toolkit>> {
+        let Some(contents) = self.read_contents()? else {
+            return Ok(None);
+        };
+
+        let toolkit = Toolkit::from_yaml(&contents)
+            .with_context(|| format!("could not load toolkit from {}", self.path.display()))?;
+
+        Ok(Some(toolkit))
+    }
+
+    /// Appends a new tool at the end of the user's toolkit file.
+    ///
+    /// The existing contents of the file (including comments and the
+    /// formatting of other entries) are left untouched. It creates the
+    /// file along with its parent directories if they don't exist.
+    ///
+    /// It fails if the file already defines a tool with the same command.
+    pub fn add_tool(&self, tool: &ToolMetadata) -> Result<()> {
+        let contents = self.read_contents()?.unwrap_or_default();
+        if let Some(toolkit) = self.load()?
+            && toolkit.find_tool(&tool.command).is_some()
+        {
+            bail!(
+                "{:?} is already defined in {}",
+                tool.command,
+                self.path.display()
+            );
+        }
+
+        let mut entry = BTreeMap::new();
+        entry.insert(tool.command.clone(), tool.to_yaml_mapping());
+
+        let mut appended = String::new();
+        if !contents.is_empty() {
+            if !contents.ends_with('\n') {
+                appended.push('\n');
+            }
+            appended.push('\n');
+        }
+        appended.push_str(&serde_yml::to_string(&entry).context("failed to serialize tool")?);
+
+        if let Some(parent) = self.path.parent() {
+            std::fs::create_dir_all(parent)
+                .with_context(|| format!("could not create directory {}", parent.display()))?;
+        }
+
+        let mut file = std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)
+            .with_context(|| format!("could not open {}", self.path.display()))?;
+
+        file.write_all(appended.as_bytes())
+            .with_context(|| format!("could not write into {}", self.path.display()))?;
+
+        Ok(())
+    }
+
+    fn read_contents(&self) -> Result<Option<String>> {
+        match std::fs::read_to_string(&self.path) {
+            Ok(contents) => Ok(Some(contents)),
+            Err(error) if error.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(error) => {
+                Err(error).with_context(|| format!("could not read {}", self.path.display()))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+    use tempdir::TempDir;
+
+    use crate::registry::{ToolMetadata, UserToolkit};
+
+    fn sample_tool(command: &str) -> ToolMetadata {
+        ToolMetadata::builder()
+            .name(format!("The {command} tool"))
+            .command(command.to_string())
+            .description("Does something useful.".into())
+            .examples(vec![format!("{command} --help")])
+            .build()
+    }
+
+    #[test]
+    fn test_load_missing_file() {
+        let dir = TempDir::new("ctftools_test").unwrap();
+        let toolkit = UserToolkit::new(dir.path().join("toolkit.yml"));
+        assert_eq!(toolkit.load().unwrap(), None);
+    }
+
+    #[test]
+    fn test_add_tool_creates_file() {
+        let dir = TempDir::new("ctftools_test").unwrap();
+        let toolkit = UserToolkit::new(dir.path().join("nested").join("toolkit.yml"));
+        toolkit.add_tool(&sample_tool("foo")).unwrap();
+
+        let loaded = toolkit.load().unwrap().unwrap();
+        assert_eq!(loaded.tools(), &[sample_tool("foo")]);
+    }
+
+    #[test]
+    fn test_add_tool_keeps_other_entries() {
+        let dir = TempDir::new("ctftools_test").unwrap();
+        let path = dir.path().join("toolkit.yml");
+        let existing = "# My own tools\nbar:\n  name: Bar   # keep me\n  description: bar";
+        std::fs::write(&path, existing).unwrap();
+
+        let toolkit = UserToolkit::new(&path);
+        toolkit.add_tool(&sample_tool("foo")).unwrap();
+
+        let contents = std::fs::read_to_string(&path).unwrap();
+        assert!(contents.starts_with(&format!("{existing}\n\n")));
+
+        let loaded = toolkit.load().unwrap().unwrap();
+        let commands = loaded
+            .tools()
+            .iter()
+            .map(|v| v.command.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(commands, ["bar", "foo"]);
+    }
+
+    #[test]
+    fn test_add_tool_rejects_duplicates() {
+        let dir = TempDir::new("ctftools_test").unwrap();
+        let toolkit = UserToolkit::new(dir.path().join("toolkit.yml"));
+        toolkit.add_tool(&sample_tool("foo")).unwrap();
+        assert!(toolkit.add_tool(&sample_tool("foo")).is_err());
+    }
+}
@@ -0,0 +1,123 @@
+//! Helpers shared by the tests of this crate.
+//!
+//! Integration tests include this file with `#[path]` as well,
+//! so it must only depend on the standard library.
+
+use std::io::{BufRead, BufReader, Write};
+use std::net::TcpListener;
+#[cfg(unix)]
+use std::path::{Path, PathBuf};
+use std::thread::JoinHandle;
+
+/// An HTTP request received by [`serve`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Request {
+    /// The request line (e.g. `GET /tool HTTP/1.1`).
+    pub line: String,
+
+    /// Headers of the request with their names in lowercase.
+    pub headers: Vec<(String, String)>,
+}
+
+impl Request {
+    /// Gets the path of the request, including its query.
+    #[must_use]
+    pub fn path(&self) -> &str {
+        self.line.split(' ').nth(1).unwrap_or_default()
+    }
+
+    /// Gets the value of a header by its lowercase name.
+    #[must_use]
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(key, _)| key == name)
+            .map(|(_, value)| value.as_str())
+    }
+}
+
+/// An HTTP response sent by [`serve`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Response {
+    /// The status line without its HTTP version (e.g. `200 OK`).
+    pub status: &'static str,
+    pub body: Vec<u8>,
+}
+
+impl Response {
+    /// Creates a `200 OK` response with the given body.
+    #[must_use]
+    pub fn ok<T: Into<Vec<u8>>>(body: T) -> Self {
+        Self {
+            status: "200 OK",
+            body: body.into(),
+        }
+    }
+
+    /// Creates an empty response with the given status.
+    #[must_use]
+    pub fn status(status: &'static str) -> Self {
+        Self {
+            status,
+            body: Vec::new(),
+        }
+    }
+}
+
+/// Serves the given number of HTTP requests from a local port in the
+/// background, answering each of them with `handler`.
+///
+/// It returns the URL of the server (e.g. `http://127.0.0.1:1234`) and a
+/// handle that yields the received requests once all of them are served.
+pub fn serve<F>(requests: usize, mut handler: F) -> (String, JoinHandle<Vec<Request>>)
+where
+    F: FnMut(&Request) -> Response + Send + 'static,
+{
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("http://{}", listener.local_addr().unwrap());
+
+    let handle = std::thread::spawn(move || {
+        let mut received = Vec::new();
+        for stream in listener.incoming().take(requests) {
+            let mut stream = stream.unwrap();
+            let mut lines = BufReader::new(&stream).lines().map_while(Result::ok);
+
+            let line = lines.next().unwrap_or_default();
+            let headers = lines
+                .take_while(|v| !v.is_empty())
+                .filter_map(|v| {
+                    let (name, value) = v.split_once(':')?;
+                    Some((name.trim().to_ascii_lowercase(), value.trim().to_string()))
+                })
+                .collect();
+
+            let request = Request { line, headers };
+            let response = handler(&request);
+
+            // The client may hang up early (e.g. when it times out).
+            _ = write!(
+                stream,
+                "HTTP/1.1 {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+                response.status,
+                response.body.len()
+            );
+            _ = stream.write_all(&response.body);
+            received.push(request);
+        }
+        received
+    });
+
+    (url, handle)
+}
+
+/// Writes an executable shell script named `name` into `dir`,
+/// which is mostly used to fake the programs ctftools runs.
+#[cfg(unix)]
+pub fn write_script(dir: &Path, name: &str, script: &str) -> PathBuf {
+    use std::os::unix::fs::PermissionsExt;
+
+    let path = dir.join(name);
+    std::fs::write(&path, format!("#!/bin/sh\n{script}")).unwrap();
+    std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o755)).unwrap();
+    path
+}
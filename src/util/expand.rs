@@ -0,0 +1,108 @@
+use anyhow::{Result, bail};
+use std::path::Path;
+
+/// Expands a leading `~` into the user's home directory and every
+/// `$VAR` or `${VAR}` into the value of that environment variable.
+///
+/// `$$` is left as a single `$`. It fails if a referenced environment
+/// variable is not set or if the home directory cannot be found.
+pub fn expand_vars(input: &str) -> Result<String> {
+    expand_vars_with(input, dirs::home_dir().as_deref(), |name| {
+        std::env::var(name).ok()
+    })
+}
+
+/// Like [`expand_vars`] but with the home directory and the
+/// environment variables provided by the caller.
+pub fn expand_vars_with(
+    input: &str,
+    home: Option<&Path>,
+    lookup: impl Fn(&str) -> Option<String>,
+) -> Result<String> {
+    let mut output = String::with_capacity(input.len());
+
+    let mut rest = input;
+    if let Some(after) = rest.strip_prefix('~')
+        && (after.is_empty() || after.starts_with(['/', '\\']))
+    {
+        let Some(home) = home else {
+            bail!("could not find the home directory to expand {input:?}");
+        };
+        output.push_str(&home.to_string_lossy());
+        rest = after;
+    }
+
+    while let Some(idx) = rest.find('$') {
+        output.push_str(&rest[..idx]);
+        rest = &rest[idx + 1..];
+
+        if let Some(after) = rest.strip_prefix('$') {
+            output.push('$');
+            rest = after;
+            continue;
+        }
+
+        let (name, after) = if let Some(braced) = rest.strip_prefix('{') {
+            let Some(end) = braced.find('}') else {
+                bail!("unclosed `${{` in {input:?}");
+            };
+            (&braced[..end], &braced[end + 1..])
+        } else {
+            let end = rest
+                .find(|v: char| !(v.is_ascii_alphanumeric() || v == '_'))
+                .unwrap_or(rest.len());
+            (&rest[..end], &rest[end..])
+        };
+
+        // A lone `$` is kept as is.
+        if name.is_empty() {
+            output.push('$');
+            continue;
+        }
+
+        let Some(value) = lookup(name) else {
+            bail!("environment variable {name:?} is not set");
+        };
+        output.push_str(&value);
+        rest = after;
+    }
+    output.push_str(rest);
+
+    Ok(output)
+}
+
+#[cfg(test)]
+mod tests {
+    use maplit::hashmap;
+    use pretty_assertions::assert_eq;
+    use std::path::Path;
+
+    use super::expand_vars_with;
+
+    #[test]
+    fn test_expand_vars() {
+        let vars = hashmap! {
+            "JAVA" => "/opt/java",
+            "USER_1" => "ctf",
+        };
+        let expand = |input: &str| {
+            expand_vars_with(input, Some(Path::new("/home/ctf")), |name| {
+                vars.get(name).map(|v| v.to_string())
+            })
+        };
+
+        assert_eq!(expand("~").unwrap(), "/home/ctf");
+        assert_eq!(expand("~/john/run").unwrap(), "/home/ctf/john/run");
+        assert_eq!(expand("a~b").unwrap(), "a~b");
+        assert_eq!(expand("~other").unwrap(), "~other");
+
+        assert_eq!(expand("$JAVA/bin").unwrap(), "/opt/java/bin");
+        assert_eq!(expand("${USER_1}s").unwrap(), "ctfs");
+        assert_eq!(expand("~/$USER_1").unwrap(), "/home/ctf/ctf");
+        assert_eq!(expand("cost: $$5 $").unwrap(), "cost: $5 $");
+
+        assert!(expand("$MISSING").is_err());
+        assert!(expand("${JAVA").is_err());
+        assert!(expand_vars_with("~/x", None, |_| None).is_err());
+    }
+}
@@ -4,6 +4,12 @@ use std::ffi::OsStr;
 use std::path::PathBuf;
 use std::process::Command;
 
+pub mod expand;
+pub mod paths;
+
+pub use self::expand::expand_vars;
+pub use self::paths::ProgramDirs;
+
 /// Returns true if the current process was most likely started by a user
 /// double-clicking an application icon (i.e. launched from a graphical file
 /// manager) rather than being started from an interactive terminal/shell.
@@ -100,6 +106,18 @@ pub fn which_opt<T: AsRef<OsStr>>(name: T) -> Result<Option<PathBuf>> {
     }
 }
 
+/// Searches the system for the first matching binary from
+/// the `candidates` list of binary names and their values.
+pub fn find_first_match<T: Copy>(candidates: &[(&str, T)]) -> Result<Option<(T, PathBuf)>> {
+    for (cmd, value) in candidates {
+        if let Some(path) = which_opt(cmd)? {
+            return Ok(Some((*value, path)));
+        }
+    }
+
+    Ok(None)
+}
+
 /// Checks if the current operating system allows escalating
 /// process privileges on demand.
 #[must_use]
@@ -169,3 +187,40 @@ pub fn running_in_elevation() -> bool {
         }
     }
 }
+
+/// Formats an amount of bytes in binary units (e.g. `1.5 MiB`).
+#[must_use]
+pub fn format_bytes(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["KiB", "MiB", "GiB", "TiB"];
+
+    if bytes < 1024 {
+        return format!("{bytes} B");
+    }
+
+    let mut value = bytes as f64 / 1024.0;
+    let mut unit = UNITS[0];
+    for next in UNITS.iter().skip(1) {
+        if value < 1024.0 {
+            break;
+        }
+        value /= 1024.0;
+        unit = next;
+    }
+    format!("{value:.1} {unit}")
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::format_bytes;
+
+    #[test]
+    fn test_format_bytes() {
+        assert_eq!(format_bytes(0), "0 B");
+        assert_eq!(format_bytes(1023), "1023 B");
+        assert_eq!(format_bytes(1536), "1.5 KiB");
+        assert_eq!(format_bytes(40 * 1024 * 1024), "40.0 MiB");
+        assert_eq!(format_bytes(3 * 1024 * 1024 * 1024), "3.0 GiB");
+    }
+}
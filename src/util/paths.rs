@@ -0,0 +1,123 @@
+use std::path::{Path, PathBuf};
+
+/// Well-known directories where ctftools stores the user's own files.
+///
+/// By default, these directories follow the platform's conventions
+/// (e.g. `~/.config/ctftools` on Linux). Setting the `CTFTOOLS_HOME`
+/// environment variable overrides all of them with a single root
+/// directory which is useful for portable setups and testing.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProgramDirs {
+    config_dir: PathBuf,
+    data_dir: Option<PathBuf>,
+    cache_dir: Option<PathBuf>,
+}
+
+impl ProgramDirs {
+    /// Name of the environment variable that overrides every
+    /// directory used by ctftools.
+    pub const HOME_ENV_VAR: &str = "CTFTOOLS_HOME";
+
+    /// Detects the directories used by ctftools in the current system.
+    ///
+    /// Returns `None` if the platform does not have a well-known
+    /// configuration directory and `CTFTOOLS_HOME` is not set. The data
+    /// and cache directories may still be missing, in which case only
+    /// the features that need them are unavailable.
+    #[must_use]
+    pub fn detect() -> Option<Self> {
+        if let Some(root) = std::env::var_os(Self::HOME_ENV_VAR).filter(|v| !v.is_empty()) {
+            return Some(Self::from_root(root));
+        }
+
+        Some(Self {
+            config_dir: dirs::config_dir()?.join("ctftools"),
+            data_dir: dirs::data_dir().map(|v| v.join("ctftools")),
+            cache_dir: dirs::cache_dir().map(|v| v.join("ctftools")),
+        })
+    }
+
+    /// Creates [`ProgramDirs`] where every directory is placed
+    /// inside the given root directory.
+    #[must_use]
+    pub fn from_root<T: Into<PathBuf>>(root: T) -> Self {
+        let root = root.into();
+        Self {
+            config_dir: root.join("config"),
+            data_dir: Some(root.join("data")),
+            cache_dir: Some(root.join("cache")),
+        }
+    }
+
+    /// Directory where the user's configuration files are stored.
+    #[must_use]
+    pub fn config_dir(&self) -> &Path {
+        &self.config_dir
+    }
+
+    /// Directory where the files managed by ctftools are stored,
+    /// if the platform has one.
+    #[must_use]
+    pub fn data_dir(&self) -> Option<&Path> {
+        self.data_dir.as_deref()
+    }
+
+    /// Directory where files that can be safely removed are
+    /// stored, if the platform has one.
+    #[must_use]
+    pub fn cache_dir(&self) -> Option<&Path> {
+        self.cache_dir.as_deref()
+    }
+
+    /// Path to the user's configuration file.
+    #[must_use]
+    pub fn config_file(&self) -> PathBuf {
+        self.config_dir.join("config.yml")
+    }
+
+    /// Path to the user's own toolkit file that extends the built-in toolkit.
+    #[must_use]
+    pub fn user_toolkit_file(&self) -> PathBuf {
+        self.config_dir.join("toolkit.yml")
+    }
+
+    /// Directory where the public keys of trusted toolkit publishers are stored.
+    #[must_use]
+    pub fn trusted_keys_dir(&self) -> PathBuf {
+        self.config_dir.join("trusted-keys")
+    }
+
+    /// Directory where the git repositories of tools built
+    /// from source are cloned into.
+    #[must_use]
+    pub fn sources_cache_dir(&self) -> Option<PathBuf> {
+        self.cache_dir().map(|v| v.join("sources"))
+    }
+
+    /// Directory where downloaded files with a checksum are kept so
+    /// they can be resumed or reused when the tool is reinstalled.
+    #[must_use]
+    pub fn downloads_cache_dir(&self) -> Option<PathBuf> {
+        self.cache_dir().map(|v| v.join("downloads"))
+    }
+
+    /// Installation prefix of the tools built from source. Their
+    /// executables are placed in its `bin` subdirectory.
+    #[must_use]
+    pub fn prefix_dir(&self) -> Option<PathBuf> {
+        self.data_dir().map(|v| v.join("prefix"))
+    }
+
+    /// Directory where the executables installed by ctftools are exposed.
+    #[must_use]
+    pub fn bin_dir(&self) -> Option<PathBuf> {
+        self.prefix_dir().map(|v| v.join("bin"))
+    }
+
+    /// Directory where the archives of downloaded tools are extracted into,
+    /// with a subdirectory for each tool.
+    #[must_use]
+    pub fn tools_dir(&self) -> Option<PathBuf> {
+        self.data_dir().map(|v| v.join("tools"))
+    }
+}
@@ -49,5 +49,11 @@ fn test_missing_tools_from_toolkit() {
 
     let cmd = run_check_tools(&["tangible"], &toolkit);
     let output = String::from_utf8_lossy(&cmd.stdout);
-    insta::assert_snapshot!(output);
+
+    // The hint for missing tools differs if ctftools can install them.
+    if cfg!(feature = "auto-install-tools") {
+        insta::assert_snapshot!("missing_tools_from_toolkit_auto_install", output);
+    } else {
+        insta::assert_snapshot!(output);
+    }
 }
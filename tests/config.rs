@@ -0,0 +1,25 @@
+use assert_cmd::{Command, cargo_bin};
+use tempdir::TempDir;
+
+#[test]
+fn test_malformed_config_falls_back_to_defaults() {
+    let home = TempDir::new("ctftools_test").unwrap();
+    let config_dir = home.path().join("config");
+    std::fs::create_dir_all(&config_dir).unwrap();
+    std::fs::write(config_dir.join("config.yml"), "downloads: [").unwrap();
+
+    let output = Command::new(cargo_bin!("ctftools"))
+        .env("CTFTOOLS_HOME", home.path())
+        .arg("check")
+        .output()
+        .unwrap();
+
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains("Your configuration could not be loaded"),
+        "{stderr}"
+    );
+    assert!(stderr.contains("no tools will be installed"), "{stderr}");
+    assert!(stderr.contains("config.yml"), "{stderr}");
+    assert!(stderr.contains("Built-in Tools"), "{stderr}");
+}
@@ -0,0 +1,36 @@
+use assert_cmd::{Command, cargo_bin};
+use ctftools::registry::{ToolMetadata, Toolkit};
+use std::process::Output;
+
+fn run_discover(installed_tools: &[&str], toolkit: &Toolkit) -> Output {
+    let toolkit = toolkit.serialize_into_yml();
+    let installed_tools = installed_tools.join(",");
+
+    Command::new(cargo_bin!("ctftools"))
+        .args(["--custom-toolkit", &*toolkit])
+        .args(["--mock-installed-tools", &*installed_tools])
+        .arg("discover")
+        .unwrap()
+}
+
+#[test]
+fn test_nothing_discovered() {
+    let cmd = run_discover(&["foo"], &Toolkit::new(Vec::new()));
+    let stdout = String::from_utf8_lossy(&cmd.stdout);
+    insta::assert_snapshot!(stdout);
+}
+
+#[test]
+fn test_discover_tools_missing_from_toolkit() {
+    let toolkit = Toolkit::new(vec![
+        ToolMetadata::builder()
+            .name("Nmap".into())
+            .command("nmap".into())
+            .build(),
+    ]);
+
+    // `foo` is not a known tool and `nmap` is already in the toolkit.
+    let cmd = run_discover(&["foo", "gdb", "nmap", "sqlmap"], &toolkit);
+    let stdout = String::from_utf8_lossy(&cmd.stdout);
+    insta::assert_snapshot!(stdout);
+}
@@ -0,0 +1,46 @@
+use cfg_if::cfg_if;
+
+cfg_if! {
+    if #[cfg(feature = "auto-install-tools")] {
+        use assert_cmd::{Command, cargo_bin};
+        use ctftools::install::cache::DownloadCache;
+        use ctftools::registry::{DownloadFileFormat, ToolDownloadInstructions};
+        use std::path::Path;
+        use tempdir::TempDir;
+
+        fn ctftools(home: &Path) -> Command {
+            let mut cmd = Command::new(cargo_bin!("ctftools"));
+            cmd.env("CTFTOOLS_HOME", home);
+            cmd
+        }
+
+        #[test]
+        fn test_list_and_clean_cache() {
+            let home = TempDir::new("ctftools_test").unwrap();
+
+            let output = ctftools(home.path()).args(["cache", "list"]).output().unwrap();
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            assert!(stderr.contains("The download cache is empty."));
+
+            let instructions = ToolDownloadInstructions::builder()
+                .format(DownloadFileFormat::TarGz)
+                .url("https://foo.local/tool.tar.gz".into())
+                .sha256("0".repeat(64))
+                .build();
+
+            let cache = DownloadCache::new(home.path().join("cache").join("downloads"));
+            let path = cache.path_of(&instructions).unwrap();
+            std::fs::write(cache.begin(&instructions, &path).unwrap(), b"hello\n").unwrap();
+
+            let output = ctftools(home.path()).args(["cache", "list"]).output().unwrap();
+            let stdout = String::from_utf8_lossy(&output.stdout);
+            let stdout = anstream::adapter::strip_str(&stdout).to_string();
+            assert_eq!(stdout, "* https://foo.local/tool.tar.gz (6 B) (partial)\n");
+
+            let output = ctftools(home.path()).args(["cache", "clean"]).output().unwrap();
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            assert!(stderr.contains("Cleaned the download cache (6 B freed)"));
+            assert_eq!(cache.list().unwrap(), []);
+        }
+    }
+}
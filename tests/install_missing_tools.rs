@@ -3,12 +3,11 @@ use cfg_if::cfg_if;
 cfg_if! {
     if #[cfg(feature = "auto-install-tools")] {
         use assert_cmd::{Command, cargo_bin};
-        use cfg_if::cfg_if;
         use ctftools::registry::{ToolMetadata, Toolkit};
         use std::process::Output;
 
         fn run_install_tools(installed_tools: &[&str], toolkit: &Toolkit) -> Output {
-            let toolkit = toolkit.serialize_into_json();
+            let toolkit = toolkit.serialize_into_yml();
             let installed_tools = installed_tools.join(",");
 
             Command::new(cargo_bin!("ctftools"))
@@ -0,0 +1,89 @@
+use cfg_if::cfg_if;
+
+cfg_if! {
+    if #[cfg(all(unix, feature = "auto-install-tools"))] {
+        use assert_cmd::{Command, cargo_bin};
+        use ctftools::install::checksum::to_hex;
+        use ctftools::registry::{
+            DownloadFileFormat, ToolDownloadInstructions, ToolMetadata, ToolPlatformDownloads,
+            Toolkit,
+        };
+        use sha2::{Digest, Sha256};
+        use std::path::Path;
+        use tempdir::TempDir;
+
+        #[allow(dead_code)]
+        #[path = "../src/testing.rs"]
+        mod testing;
+
+        const SCRIPT: &[u8] = b"#!/bin/sh\necho bundled\n";
+
+        /// Serves the script to a single request and returns its URL.
+        fn serve_script() -> String {
+            let (url, _) = testing::serve(1, |_| testing::Response::ok(SCRIPT));
+            format!("{url}/bundled-tool")
+        }
+
+        fn ctftools(home: &Path, toolkit: &str) -> Command {
+            let mut cmd = Command::new(cargo_bin!("ctftools"));
+            cmd.env("CTFTOOLS_HOME", home)
+                .args(["--custom-toolkit", toolkit]);
+            cmd
+        }
+
+        #[test]
+        fn test_install_from_bundle() {
+            let instructions = ToolDownloadInstructions::builder()
+                .format(DownloadFileFormat::Binary)
+                .url(serve_script())
+                .sha256(to_hex(&Sha256::digest(SCRIPT)))
+                .build();
+
+            let tool = ToolMetadata::builder()
+                .name("Bundled Tool".into())
+                .description("A tool installed from a bundle".into())
+                .command("ctftools-bundled-tool".into())
+                .downloads(
+                    ToolPlatformDownloads::builder()
+                        .linux(instructions.clone())
+                        .macos(instructions)
+                        .build(),
+                )
+                .build();
+            let toolkit = Toolkit::new(vec![tool]).serialize_into_yml();
+
+            let dir = TempDir::new("ctftools_test").unwrap();
+            let bundle = dir.path().join("bundle.tar");
+
+            let output = ctftools(&dir.path().join("home1"), &toolkit)
+                .args(["bundle", "create", "-o"])
+                .arg(&bundle)
+                .output()
+                .unwrap();
+            let stdout = String::from_utf8_lossy(&output.stdout);
+            assert_eq!(stdout, "* Bundled Tool\n");
+            assert!(bundle.is_file());
+
+            // The tool is installed on another machine without the server.
+            let home = dir.path().join("home2");
+            let output = ctftools(&home, &toolkit)
+                .args(["install", "--from-bundle"])
+                .arg(&bundle)
+                .output()
+                .unwrap();
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            assert!(
+                stderr.contains("Successfully installed the following tools!"),
+                "{stderr}"
+            );
+
+            let executable = home
+                .join("data")
+                .join("prefix")
+                .join("bin")
+                .join("ctftools-bundled-tool");
+            let output = std::process::Command::new(executable).output().unwrap();
+            assert_eq!(output.stdout, b"bundled\n");
+        }
+    }
+}
@@ -0,0 +1,125 @@
+use assert_cmd::{Command, cargo_bin};
+use minisign::KeyPair;
+use std::io::Cursor;
+use std::path::Path;
+use tempdir::TempDir;
+
+const SHARED_TOOLKIT: &str =
+    "shared-tool:\n  name: Shared Tool\n  description: A tool from a shared toolkit\n";
+
+fn ctftools(home: &Path) -> Command {
+    let mut cmd = Command::new(cargo_bin!("ctftools"));
+    cmd.env("CTFTOOLS_HOME", home);
+    cmd
+}
+
+fn write_shared_toolkit(dir: &Path, keypair: Option<&KeyPair>) -> std::path::PathBuf {
+    let path = dir.join("shared.yml");
+    std::fs::write(&path, SHARED_TOOLKIT).unwrap();
+
+    if let Some(keypair) = keypair {
+        let signature = minisign::sign(
+            None,
+            &keypair.sk,
+            Cursor::new(SHARED_TOOLKIT.as_bytes()),
+            None,
+            None,
+        )
+        .unwrap();
+        std::fs::write(dir.join("shared.yml.minisig"), signature.into_string()).unwrap();
+    }
+    path
+}
+
+#[test]
+fn test_refuse_unsigned_toolkit() {
+    let home = TempDir::new("ctftools_test").unwrap();
+    let shared = write_shared_toolkit(home.path(), None);
+
+    let output = ctftools(home.path())
+        .arg("--toolkit")
+        .arg(&shared)
+        .arg("check")
+        .output()
+        .unwrap();
+
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("refusing to load unverified toolkit"));
+
+    let output = ctftools(home.path())
+        .arg("--toolkit")
+        .arg(&shared)
+        .arg("--allow-unsigned")
+        .arg("check")
+        .output()
+        .unwrap();
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("Shared Tool"));
+}
+
+#[test]
+fn test_load_toolkit_signed_by_trusted_key() {
+    let home = TempDir::new("ctftools_test").unwrap();
+    let keypair = KeyPair::generate_unencrypted_keypair().unwrap();
+    let shared = write_shared_toolkit(home.path(), Some(&keypair));
+
+    let key = home.path().join("publisher.pub");
+    std::fs::write(&key, keypair.pk.to_box().unwrap().into_string()).unwrap();
+
+    // The program asks whether to go back to the menu afterwards
+    // which fails without a terminal, so we only check its output.
+    let output = ctftools(home.path())
+        .args(["toolkit", "trust"])
+        .arg(&key)
+        .output()
+        .unwrap();
+
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("Trusted \"publisher\""));
+
+    assert!(
+        home.path()
+            .join("config/trusted-keys/publisher.pub")
+            .exists()
+    );
+
+    let output = ctftools(home.path())
+        .arg("--toolkit")
+        .arg(&shared)
+        .arg("check")
+        .output()
+        .unwrap();
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("Shared Tool"));
+}
+
+#[test]
+fn test_malformed_trusted_key() {
+    let home = TempDir::new("ctftools_test").unwrap();
+    let keys = home.path().join("config/trusted-keys");
+    std::fs::create_dir_all(&keys).unwrap();
+    std::fs::write(keys.join("broken.pub"), "not a minisign key").unwrap();
+
+    // Trusted keys are not needed without shared toolkits.
+    let output = ctftools(home.path()).arg("check").output().unwrap();
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(!stderr.contains("trusted key"), "{stderr}");
+
+    let shared = write_shared_toolkit(home.path(), None);
+    let output = ctftools(home.path())
+        .arg("--toolkit")
+        .arg(&shared)
+        .arg("check")
+        .output()
+        .unwrap();
+
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains("could not load trusted key \"broken\""),
+        "{stderr}"
+    );
+}